@@ -1,13 +1,25 @@
+use crate::cli::RaceRole;
 use crate::config::TukaiConfig;
-use crate::event_handler::{EventHandler, TukaiEvent};
-use crate::screens::ActiveScreenEnum;
+use crate::event_handler::{EventHandler, EventSource, TukaiEvent};
+use crate::profile::ProfileHandler;
+use crate::race::{client::RaceClient, host::RaceHost};
+use crate::screens::lessons::LessonsScreen;
+use crate::screens::profiles::ProfilesScreen;
+use crate::screens::race::RaceScreen;
 use crate::screens::repeat::RepeatScreen;
-use crate::screens::{Screen, stats::StatsScreen, typing::TypingScreen};
-use crate::storage::storage_handler::StorageHandler;
+use crate::screens::replay::ReplayScreen;
+use crate::screens::{ActiveScreenEnum, ScreenAction};
+use crate::screens::{
+  Screen,
+  stats::StatsScreen,
+  typing::{Ghost, TypingScreen},
+  zen::ZenScreen,
+};
+use crate::storage::{stats::RunMode, storage_handler::StorageHandler};
 use std::{cell::RefCell, rc::Rc};
 
 use ratatui::Terminal;
-use ratatui::prelude::CrosstermBackend;
+use ratatui::backend::Backend;
 use ratatui::{
   Frame,
   crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
@@ -16,17 +28,16 @@ use ratatui::{
 
 use anyhow::Result;
 
-type TukaiTerminal = Terminal<CrosstermBackend<std::io::Stdout>>;
-
-pub struct Tukai<'a> {
+pub struct Tukai<'a, E: EventSource = EventHandler> {
   // App config
   pub config: Rc<RefCell<TukaiConfig>>,
 
-  // Handles a crossterm keyboard events
-  event_handler: &'a mut EventHandler,
+  // Source of the keyboard and timer events
+  event_handler: &'a mut E,
 
   // Storage handler
-  storage_handler: StorageHandler,
+  // Shared with screens which display stored data
+  storage_handler: Rc<RefCell<StorageHandler>>,
 
   // App was terminated
   is_terminated: bool,
@@ -35,15 +46,70 @@ pub struct Tukai<'a> {
   screen: Box<dyn Screen>,
 }
 
-impl<'a> Tukai<'a> {
+impl<'a, E: EventSource> Tukai<'a, E> {
   /// Attempts to create a new Tukai application.
   /// Tries to initialize `StorageHandler` then load
   /// an existing saved settings file.
-  pub fn try_new(event_handler: &'a mut EventHandler, mut config: TukaiConfig) -> Result<Self> {
+  pub fn try_new(event_handler: &'a mut E, mut config: TukaiConfig) -> Result<Self> {
     let storage_handler = StorageHandler::new(config.get_file_path()).init()?;
 
+    Self::apply_storage_preferences(&mut config, &storage_handler);
+
+    let config = Rc::new(RefCell::new(config));
+    let typing_screen = TypingScreen::new(Rc::clone(&config));
+
+    Ok(Self {
+      config,
+
+      event_handler,
+
+      storage_handler: Rc::new(RefCell::new(storage_handler)),
+
+      is_terminated: false,
+
+      screen: Box::new(typing_screen),
+    })
+  }
+
+  /// Sets the screen displayed after the start
+  pub fn initial_screen(mut self, screen: ActiveScreenEnum) -> Self {
+    self.switch_screen(screen);
+    self
+  }
+
+  /// Sets the race screen hosting or joining the lobby
+  ///
+  /// The player name defaults to the profile name.
+  pub fn race(mut self, race_role: RaceRole, player_name: Option<String>) -> Result<Self> {
+    let player_name =
+      player_name.unwrap_or_else(|| self.config.borrow().get_profile_name().clone());
+
+    let (client, host) = match race_role {
+      RaceRole::Host(addr) => {
+        let host = RaceHost::bind(&addr)?;
+
+        // The host races as a player of its own lobby
+        (host.connect_player(player_name), Some(host))
+      }
+      RaceRole::Join(addr) => (RaceClient::connect(addr, player_name), None),
+    };
+
+    self.screen = Box::new(RaceScreen::new(self.config.clone(), client, host));
+
+    Ok(self)
+  }
+
+  /// Applies preferences saved in the storage into the config
+  fn apply_storage_preferences(config: &mut TukaiConfig, storage_handler: &StorageHandler) {
     config.typing_duration = storage_handler.get_typing_duration();
     config.has_transparent_bg = storage_handler.get_has_transparent_bg();
+    config.record_keystrokes = storage_handler.get_record_keystrokes();
+    config.pace_caret = storage_handler.get_pace_caret();
+    config.difficulty = storage_handler.get_difficulty();
+    config.visibility = storage_handler.get_visibility();
+    config.keyboard_layout = storage_handler.get_keyboard_layout();
+    config.remap_keys = storage_handler.get_remap_keys();
+    config.pace_wpm = storage_handler.get_pace_wpm(config.pace_caret, &config.typing_duration);
 
     {
       let mut layout = config.get_layout_mut();
@@ -54,28 +120,104 @@ impl<'a> Tukai<'a> {
       let mut language = config.get_language_mut();
       language.current_index(storage_handler.get_language_index());
     }
+  }
 
-    let config = Rc::new(RefCell::new(config));
-    let typing_screen = TypingScreen::new(Rc::clone(&config));
+  /// Resolves the target WPM of the pace caret again
+  ///
+  /// Used after the pace caret or the typing duration changed.
+  fn update_pace_wpm(&mut self) {
+    let mut config = self.config.borrow_mut();
 
-    Ok(Self {
-      config,
+    config.pace_wpm = self
+      .storage_handler
+      .borrow()
+      .get_pace_wpm(config.pace_caret, &config.typing_duration);
+  }
 
-      event_handler,
+  /// Switches the active profile.
+  ///
+  /// Flushes the current storage, then loads the storage
+  /// and preferences of the selected profile.
+  ///
+  /// The active profile is kept if the storage of the selected profile can't be loaded.
+  fn switch_profile(&mut self, profile_name: String) -> Result<()> {
+    self.storage_handler.borrow().flush()?;
 
-      storage_handler,
+    let storage_handler =
+      StorageHandler::new(ProfileHandler::get_storage_file_path(&profile_name)).init()?;
 
-      is_terminated: false,
+    let mut config = self.config.borrow_mut();
+    config.set_profile_name(profile_name);
 
-      screen: Box::new(typing_screen),
-    })
+    Self::apply_storage_preferences(&mut config, &storage_handler);
+
+    self.storage_handler.replace(storage_handler);
+
+    Ok(())
+  }
+
+  /// Handles an action requested by the active screen
+  fn handle_screen_action(&mut self, screen_action: ScreenAction) {
+    match screen_action {
+      ScreenAction::SwitchProfile(profile_name) => match self.switch_profile(profile_name) {
+        Ok(()) => self.switch_screen(ActiveScreenEnum::Typing),
+        Err(error) => {
+          self.screen = Box::new(
+            ProfilesScreen::new(self.config.clone())
+              .with_error(format!("Failed to switch the profile: {error}")),
+          );
+        }
+      },
+      ScreenAction::RaceBestGhost => {
+        let typing_duration = self.config.borrow().typing_duration.clone();
+
+        let ghost = self
+          .storage_handler
+          .borrow()
+          .get_ghost_stat(&typing_duration, RunMode::Timed)
+          .and_then(Ghost::from_stat);
+
+        match ghost {
+          Some(ghost) => self.race_ghost(ghost),
+          None => {
+            self.screen = Box::new(TypingScreen::new(self.config.clone()).with_notice(
+              String::from("No recorded run to race, record the keystrokes with ctrl-e"),
+            ));
+          }
+        }
+      }
+      ScreenAction::RaceGhost(stat) => {
+        if let Some(ghost) = Ghost::from_stat(&stat) {
+          // The ghost is raced for the duration of its run
+          self.config.borrow_mut().typing_duration = stat.get_typing_duration().clone();
+          self
+            .storage_handler
+            .borrow_mut()
+            .set_typing_duration(stat.get_typing_duration().clone());
+
+          self.update_pace_wpm();
+
+          self.race_ghost(ghost);
+        }
+      }
+      ScreenAction::Replay(stat) => {
+        self.screen = Box::new(ReplayScreen::new(self.config.clone(), *stat));
+      }
+    }
+  }
+
+  /// Switches to the typing screen racing the ghost
+  fn race_ghost(&mut self, ghost: Ghost) {
+    self.screen = Box::new(TypingScreen::new(self.config.clone()).with_ghost(ghost));
   }
 
   /// Runs and renders tui components.
   ///
-  /// Handles events from `EventHandler`
-  /// Handles tick (seconds, it's time counter) from `EventHandler`
-  pub async fn run(&mut self, terminal: &mut TukaiTerminal) -> Result<()> {
+  /// Handles events from the event source (`EventHandler`)
+  /// Handles tick (seconds, it's time counter) from the event source
+  ///
+  /// Renders into any ratatui backend (e.g. `TestBackend` in the tests).
+  pub async fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
     while !self.is_terminated {
       match self.event_handler.next().await? {
         TukaiEvent::Key(key_event) => self.handle_events(key_event),
@@ -84,10 +226,23 @@ impl<'a> Tukai<'a> {
             self.screen.increment_time_secs();
           }
         }
+        TukaiEvent::Frame => {
+          // Frames only redraw the animated screens
+          if !self.screen.is_animated() {
+            continue;
+          }
+
+          self.screen.on_frame();
+        }
+        // Resized terminal is redrawn immediately
+        TukaiEvent::Resize => {}
+        TukaiEvent::FocusLost => self.screen.on_focus_lost(),
+        TukaiEvent::FocusGained => continue,
+        TukaiEvent::Paste(text) => self.screen.handle_paste(&text),
       };
 
       if self.screen.get_remaining_time() == 0 {
-        self.screen.stop(&mut self.storage_handler);
+        self.screen.stop(&mut self.storage_handler.borrow_mut());
       }
 
       terminal.draw(|frame| self.draw(frame))?;
@@ -124,6 +279,7 @@ impl<'a> Tukai<'a> {
 
     self
       .storage_handler
+      .borrow()
       .flush()
       .expect("Error occured while saving into the file");
   }
@@ -135,9 +291,38 @@ impl<'a> Tukai<'a> {
   fn switch_screen(&mut self, switch_to_screen: ActiveScreenEnum) {
     self.screen = match switch_to_screen {
       ActiveScreenEnum::Typing => Box::new(TypingScreen::new(self.config.clone())),
-      ActiveScreenEnum::Repeat => Box::new(RepeatScreen::new(self.config.clone())),
-      ActiveScreenEnum::Stats => Box::new(StatsScreen::new(self.config.clone())),
+      ActiveScreenEnum::Repeat => Box::new(RepeatScreen::new(
+        self.config.clone(),
+        self.storage_handler.clone(),
+      )),
+      ActiveScreenEnum::Stats => Box::new(StatsScreen::new(
+        self.config.clone(),
+        self.storage_handler.clone(),
+      )),
+      ActiveScreenEnum::Profiles => Box::new(ProfilesScreen::new(self.config.clone())),
+      ActiveScreenEnum::Zen => Box::new(ZenScreen::new(self.config.clone())),
+      ActiveScreenEnum::Lessons => Box::new(LessonsScreen::new(
+        self.config.clone(),
+        self.storage_handler.clone(),
+      )),
+    }
+  }
+
+  /// Translates the typed character from QWERTY into the selected keyboard layout
+  ///
+  /// Only if the remapping is on and the screen accepts the remapped input
+  /// (see `Screen::accepts_remapped_input`).
+  fn remap_key_event(&self, mut key_event: KeyEvent) -> KeyEvent {
+    let config = self.config.borrow();
+
+    if config.remap_keys
+      && self.screen.accepts_remapped_input()
+      && let KeyCode::Char(c) = key_event.code
+    {
+      key_event.code = KeyCode::Char(config.keyboard_layout.remap(c));
     }
+
+    key_event
   }
 
   /// Handles crossterm events.
@@ -149,6 +334,10 @@ impl<'a> Tukai<'a> {
     if key_event.modifiers.contains(KeyModifiers::CONTROL) {
       // Handle screen specific CTRL key events
       if self.screen.handle_control_events(key_event) {
+        if let Some(screen_action) = self.screen.take_action() {
+          self.handle_screen_action(screen_action);
+        }
+
         return;
       }
 
@@ -166,16 +355,71 @@ impl<'a> Tukai<'a> {
             }
           }
           'c' => self.exit(),
+          'o' => self.switch_screen(ActiveScreenEnum::Profiles),
+          'z' => self.switch_screen(ActiveScreenEnum::Zen),
+          'n' => self.switch_screen(ActiveScreenEnum::Lessons),
           'd' => {
             self
               .storage_handler
+              .borrow_mut()
               .set_typing_duration(self.config.borrow_mut().switch_typing_duration());
 
+            self.update_pace_wpm();
+            self.reset();
+          }
+          'k' => {
+            let new_pace_caret = self.config.borrow_mut().switch_pace_caret();
+            self
+              .storage_handler
+              .borrow_mut()
+              .set_pace_caret(new_pace_caret);
+
+            self.update_pace_wpm();
+          }
+          'e' => {
+            let new_state = self.config.borrow_mut().toggle_record_keystrokes();
+            self
+              .storage_handler
+              .borrow_mut()
+              .set_record_keystrokes(new_state);
+          }
+          'b' => {
+            let new_visibility = self.config.borrow_mut().switch_visibility();
+            self
+              .storage_handler
+              .borrow_mut()
+              .set_visibility(new_visibility);
+
+            self.reset();
+          }
+          'y' => {
+            let new_keyboard_layout = self.config.borrow_mut().switch_keyboard_layout();
+            self
+              .storage_handler
+              .borrow_mut()
+              .set_keyboard_layout(new_keyboard_layout);
+
+            self.reset();
+          }
+          'u' => {
+            let new_state = self.config.borrow_mut().toggle_remap_keys();
+            self.storage_handler.borrow_mut().set_remap_keys(new_state);
+          }
+          'f' => {
+            let new_difficulty = self.config.borrow_mut().switch_difficulty();
+            self
+              .storage_handler
+              .borrow_mut()
+              .set_difficulty(new_difficulty);
+
             self.reset();
           }
           't' => {
             let new_state = self.config.borrow_mut().toggle_transparent_bg();
-            self.storage_handler.set_transparent_bg(new_state);
+            self
+              .storage_handler
+              .borrow_mut()
+              .set_transparent_bg(new_state);
           }
           's' => {
             let new_layout = self
@@ -184,7 +428,7 @@ impl<'a> Tukai<'a> {
               .get_layout_mut()
               .switch_to_next_layout();
 
-            self.storage_handler.set_layout(new_layout);
+            self.storage_handler.borrow_mut().set_layout(new_layout);
           }
           'p' => {
             // switches language
@@ -194,7 +438,10 @@ impl<'a> Tukai<'a> {
               .get_language_mut()
               .switch_language();
 
-            self.storage_handler.set_language_index(new_language_index);
+            self
+              .storage_handler
+              .borrow_mut()
+              .set_language_index(new_language_index);
             self.reset();
           }
           _ => {}
@@ -204,20 +451,26 @@ impl<'a> Tukai<'a> {
       return;
     }
 
+    let key_event = self.remap_key_event(key_event);
+
     if self.screen.handle_events(key_event) {
+      if let Some(screen_action) = self.screen.take_action() {
+        self.handle_screen_action(screen_action);
+      }
+
       return;
     }
 
     if key_event.code == KeyCode::Esc {
       self.exit();
-    } else if key_event.code == KeyCode::Left {
-      if let Some(previous_screen) = self.screen.get_previous_screen() {
-        self.switch_screen(previous_screen);
-      }
-    } else if key_event.code == KeyCode::Right {
-      if let Some(next_screen) = self.screen.get_next_screen() {
-        self.switch_screen(next_screen);
-      }
+    } else if key_event.code == KeyCode::Left
+      && let Some(previous_screen) = self.screen.get_previous_screen()
+    {
+      self.switch_screen(previous_screen);
+    } else if key_event.code == KeyCode::Right
+      && let Some(next_screen) = self.screen.get_next_screen()
+    {
+      self.switch_screen(next_screen);
     }
   }
 }
@@ -0,0 +1,143 @@
+use anyhow::{Result, bail};
+
+use crate::profile::ProfileHandler;
+
+/// Usage text printed with the `--help` flag
+pub const USAGE: &str = "Usage: tukai [OPTIONS]
+
+Options:
+  -p, --profile <NAME>  Use the profile (created if it does not exist)
+      --host <ADDR>     Host a race lobby on the address (e.g. 0.0.0.0:7878)
+      --join <ADDR>     Join the race lobby on the address (e.g. 192.168.1.10:7878)
+      --name <NAME>     Name in the race (defaults to the profile name)
+  -h, --help            Print help
+  -V, --version         Print version";
+
+/// Role in the network race selected with the `--host` or `--join` flag
+#[derive(Debug, PartialEq, Clone)]
+pub enum RaceRole {
+  // Hosts the lobby on the address
+  Host(String),
+
+  // Joins the lobby on the address
+  Join(String),
+}
+
+/// Parsed command line arguments
+#[derive(Debug, Default, PartialEq)]
+pub struct CliArgs {
+  // Profile selected with the `--profile` flag
+  pub profile_name: Option<String>,
+
+  // Network race to host or join
+  pub race_role: Option<RaceRole>,
+
+  // Player name selected with the `--name` flag
+  pub player_name: Option<String>,
+
+  // Prints the usage and exits
+  pub show_help: bool,
+
+  // Prints the version and exits
+  pub show_version: bool,
+}
+
+impl CliArgs {
+  /// Parses the arguments of the running process
+  pub fn parse() -> Result<Self> {
+    CliArgs::parse_from(std::env::args().skip(1))
+  }
+
+  /// Parses the arguments (without the binary name)
+  pub fn parse_from<I: IntoIterator<Item = String>>(args: I) -> Result<Self> {
+    let mut cli_args = CliArgs::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+      match arg.as_str() {
+        "-p" | "--profile" => {
+          let Some(profile_name) = args.next() else {
+            bail!("Missing a profile name for the `{arg}` flag");
+          };
+
+          if !ProfileHandler::is_valid_name(&profile_name) {
+            bail!(
+              "Invalid profile name `{profile_name}` (allowed are letters, digits, `-` and `_`)"
+            );
+          }
+
+          cli_args.profile_name = Some(profile_name);
+        }
+        "--host" | "--join" => {
+          let Some(addr) = args.next() else {
+            bail!("Missing an address for the `{arg}` flag");
+          };
+
+          if cli_args.race_role.is_some() {
+            bail!("Only one of the `--host` and `--join` flags can be used");
+          }
+
+          cli_args.race_role = Some(if arg == "--host" {
+            RaceRole::Host(addr)
+          } else {
+            RaceRole::Join(addr)
+          });
+        }
+        "--name" => {
+          let Some(player_name) = args.next() else {
+            bail!("Missing a name for the `{arg}` flag");
+          };
+
+          if !ProfileHandler::is_valid_name(&player_name) {
+            bail!("Invalid name `{player_name}` (allowed are letters, digits, `-` and `_`)");
+          }
+
+          cli_args.player_name = Some(player_name);
+        }
+        "-h" | "--help" => cli_args.show_help = true,
+        "-V" | "--version" => cli_args.show_version = true,
+        _ => bail!("Unknown argument `{arg}`\n\n{USAGE}"),
+      }
+    }
+
+    Ok(cli_args)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse(args: &[&str]) -> Result<CliArgs> {
+    CliArgs::parse_from(args.iter().map(|arg| arg.to_string()))
+  }
+
+  #[test]
+  fn parse_profile() {
+    let cli_args = parse(&["--profile", "alice"]).unwrap();
+    assert_eq!(cli_args.profile_name, Some(String::from("alice")));
+
+    let cli_args = parse(&["-p", "bob_2"]).unwrap();
+    assert_eq!(cli_args.profile_name, Some(String::from("bob_2")));
+  }
+
+  #[test]
+  fn parse_race() {
+    let cli_args = parse(&["--join", "127.0.0.1:7878", "--name", "carol"]).unwrap();
+    assert_eq!(
+      cli_args.race_role,
+      Some(RaceRole::Join(String::from("127.0.0.1:7878")))
+    );
+    assert_eq!(cli_args.player_name, Some(String::from("carol")));
+
+    assert!(parse(&["--host"]).is_err());
+    assert!(parse(&["--host", "0.0.0.0:7878", "--join", "127.0.0.1:7878"]).is_err());
+  }
+
+  #[test]
+  fn parse_invalid_arguments() {
+    assert!(parse(&["--profile"]).is_err());
+    assert!(parse(&["--profile", "../etc"]).is_err());
+    assert!(parse(&["--unknown"]).is_err());
+  }
+}
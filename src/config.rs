@@ -8,6 +8,10 @@ use std::path::{Path, PathBuf};
 use std::{collections::HashMap, fmt::Display, hash::Hash};
 
 use maplit::hashmap;
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::keyboard::KeyboardLayout;
+use crate::profile::{DEFAULT_PROFILE_NAME, ProfileHandler};
 use ratatui::style::Color;
 
 pub trait ToColor {
@@ -16,7 +20,8 @@ pub trait ToColor {
   /// # Example
   ///
   /// ```
-  /// use ratatui::style::Color
+  /// use ratatui::style::Color;
+  /// use tukai::config::ToColor;
   ///
   /// let rgb: (u8, u8, u8) = (128, 64, 255);
   /// let color = rgb.to_color();
@@ -129,8 +134,8 @@ pub struct TukaiLayout {
   active_layout_name: TukaiLayoutName,
 }
 
-impl TukaiLayout {
-  pub fn default() -> Self {
+impl Default for TukaiLayout {
+  fn default() -> Self {
     use TukaiLayoutName::*;
 
     let layouts = hashmap! {
@@ -212,7 +217,9 @@ impl TukaiLayout {
       active_layout_name: TukaiLayoutName::Iced,
     }
   }
+}
 
+impl TukaiLayout {
   /// Returns the currect active layout name
   pub fn get_active_layout_name(&self) -> &TukaiLayoutName {
     &self.active_layout_name
@@ -281,9 +288,9 @@ pub struct Language {
   words: Vec<String>,
 }
 
-impl Language {
+impl Default for Language {
   // Creates default empty list of the language files
-  pub fn default() -> Self {
+  fn default() -> Self {
     Self {
       language_files: Vec::new(),
       current_index: 0,
@@ -291,17 +298,20 @@ impl Language {
       words: Vec::new(),
     }
   }
+}
 
-  pub fn init_lang_code(&mut self) {
-    let filename = &self.language_files[self.current_index];
-
-    let lang_code = Path::new(filename)
+impl Language {
+  /// Returns the language code from the language file name
+  fn get_file_lang_code(filename: &str) -> String {
+    Path::new(filename)
       .file_stem()
       .and_then(|s| s.to_str())
       .unwrap_or("unknown")
-      .to_string();
+      .to_string()
+  }
 
-    self.lang_code = lang_code;
+  pub fn init_lang_code(&mut self) {
+    self.lang_code = Language::get_file_lang_code(&self.language_files[self.current_index]);
   }
 
   /// Load language files from the `words` folder
@@ -389,9 +399,18 @@ impl Language {
   pub fn get_lang_code(&self) -> &String {
     &self.lang_code
   }
+
+  /// Returns the codes of all available languages
+  pub fn get_lang_codes(&self) -> Vec<String> {
+    self
+      .language_files
+      .iter()
+      .map(|filename| Language::get_file_lang_code(filename))
+      .collect()
+  }
 }
 
-#[derive(Serialize, Deserialize, Hash, PartialEq, Eq, Debug, Clone)]
+#[derive(Serialize, Deserialize, Hash, PartialEq, Eq, Debug, Clone, Default)]
 /// Represents the available durations for the test
 ///
 /// This enum defines default durations
@@ -403,16 +422,11 @@ impl Language {
 pub enum TypingDuration {
   FifteenSec,
   ThirtySec,
+  #[default]
   Minute,
   ThreeMinutes,
 }
 
-impl Default for TypingDuration {
-  fn default() -> Self {
-    Self::Minute
-  }
-}
-
 impl TypingDuration {
   pub fn as_seconds(&self) -> usize {
     use TypingDuration::*;
@@ -432,7 +446,146 @@ pub trait ConfigBuilder<T> {
   fn build(self) -> T;
 }
 
+/// Pace caret moving through the text at a target WPM
+///
+/// Switchable with a `ctrl-k` shortcut
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum PaceCaret {
+  #[default]
+  Off,
+
+  // Fixed target WPM
+  Fixed(usize),
+
+  // Average WPM of the runs with the active duration
+  Average,
+
+  // Best WPM of the runs with the active duration
+  Best,
+}
+
+impl PaceCaret {
+  /// Returns the next pace caret (used for a switching)
+  ///
+  /// Off → 40 → 60 → 80 → 100 → 120 → Average → Best → Off
+  pub fn next(&self) -> Self {
+    use PaceCaret::*;
+
+    match self {
+      Off => Fixed(40),
+      Fixed(wpm) if *wpm < 120 => Fixed(wpm + 20),
+      Fixed(_) => Average,
+      Average => Best,
+      Best => Off,
+    }
+  }
+}
+
+impl Display for PaceCaret {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      PaceCaret::Off => write!(f, "off"),
+      PaceCaret::Fixed(wpm) => write!(f, "{wpm} WPM"),
+      PaceCaret::Average => write!(f, "average"),
+      PaceCaret::Best => write!(f, "best"),
+    }
+  }
+}
+
+/// Difficulty of the typing test
+///
+/// Switchable with a `ctrl-f` shortcut
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum Difficulty {
+  #[default]
+  Normal,
+
+  // Cursor doesn't advance until the correct key is pressed
+  StopOnLetter,
+
+  // Space can't be typed while the word has errors
+  StopOnWord,
+
+  // First error ends the run
+  SuddenDeath,
+}
+
+impl Difficulty {
+  /// Returns the next difficulty (used for a switching)
+  pub fn next(&self) -> Self {
+    use Difficulty::*;
+
+    match self {
+      Normal => StopOnLetter,
+      StopOnLetter => StopOnWord,
+      StopOnWord => SuddenDeath,
+      SuddenDeath => Normal,
+    }
+  }
+}
+
+impl Display for Difficulty {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let display_text = match self {
+      Difficulty::Normal => "normal",
+      Difficulty::StopOnLetter => "stop on letter",
+      Difficulty::StopOnWord => "stop on word",
+      Difficulty::SuddenDeath => "sudden death",
+    };
+
+    write!(f, "{display_text}")
+  }
+}
+
+/// Visibility of the text and the feedback during the run
+///
+/// Switchable with a `ctrl-b` shortcut
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum Visibility {
+  #[default]
+  Normal,
+
+  // Errors aren't highlighted until the run ends
+  Blind,
+
+  // Upcoming words are hidden after a short preview
+  Memory,
+
+  // Only the current word is visible
+  Masked,
+}
+
+impl Visibility {
+  /// Returns the next visibility (used for a switching)
+  pub fn next(&self) -> Self {
+    use Visibility::*;
+
+    match self {
+      Normal => Blind,
+      Blind => Memory,
+      Memory => Masked,
+      Masked => Normal,
+    }
+  }
+}
+
+impl Display for Visibility {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let display_text = match self {
+      Visibility::Normal => "normal",
+      Visibility::Blind => "blind",
+      Visibility::Memory => "memory",
+      Visibility::Masked => "masked",
+    };
+
+    write!(f, "{display_text}")
+  }
+}
+
 pub struct TukaiConfig {
+  // Active user profile name
+  profile_name: String,
+
   // Path to the storage file
   file_path: PathBuf,
 
@@ -447,39 +600,91 @@ pub struct TukaiConfig {
 
   // Typing duration
   pub typing_duration: TypingDuration,
+
+  // Selected pace caret
+  pub pace_caret: PaceCaret,
+
+  // Target WPM of the pace caret resolved from the stats (None if off)
+  pub pace_wpm: Option<usize>,
+
+  // Keystrokes of the runs are recorded (for the ghost and the replay, off by default)
+  pub record_keystrokes: bool,
+
+  // Selected difficulty
+  pub difficulty: Difficulty,
+
+  // Selected visibility of the text
+  pub visibility: Visibility,
+
+  // Physical keyboard layout (used by the lessons)
+  pub keyboard_layout: KeyboardLayout,
+
+  // Typed characters are translated from QWERTY into the keyboard layout
+  pub remap_keys: bool,
+
+  // Generates the texts (seeded for the reproducible texts)
+  rng: RefCell<StdRng>,
 }
 
-impl TukaiConfig {
-  pub fn default() -> Self {
+impl Default for TukaiConfig {
+  fn default() -> Self {
     Self {
-      file_path: PathBuf::from("tukai.bin"),
+      profile_name: String::from(DEFAULT_PROFILE_NAME),
+      file_path: ProfileHandler::get_storage_file_path(DEFAULT_PROFILE_NAME),
       layout: RefCell::new(TukaiLayout::default()),
       language: RefCell::new(Language::default().init()),
       has_transparent_bg: false,
       typing_duration: TypingDuration::default(),
+      pace_caret: PaceCaret::default(),
+      pace_wpm: None,
+      record_keystrokes: false,
+      difficulty: Difficulty::default(),
+      visibility: Visibility::default(),
+      keyboard_layout: KeyboardLayout::default(),
+      remap_keys: false,
+      rng: RefCell::new(StdRng::from_entropy()),
     }
   }
+}
 
-  pub fn get_layout(&self) -> Ref<TukaiLayout> {
+impl TukaiConfig {
+  pub fn get_layout(&self) -> Ref<'_, TukaiLayout> {
     self.layout.borrow()
   }
 
-  pub fn get_language(&self) -> Ref<Language> {
+  pub fn get_language(&self) -> Ref<'_, Language> {
     self.language.borrow()
   }
 
-  pub fn get_layout_mut(&mut self) -> RefMut<TukaiLayout> {
+  pub fn get_layout_mut(&mut self) -> RefMut<'_, TukaiLayout> {
     self.layout.borrow_mut()
   }
 
-  pub fn get_language_mut(&mut self) -> RefMut<Language> {
+  pub fn get_language_mut(&mut self) -> RefMut<'_, Language> {
     self.language.borrow_mut()
   }
 
+  /// Returns the random generator of the texts
+  pub fn get_rng_mut(&self) -> RefMut<'_, StdRng> {
+    self.rng.borrow_mut()
+  }
+
   pub fn get_file_path(&self) -> &PathBuf {
     &self.file_path
   }
 
+  pub fn get_profile_name(&self) -> &String {
+    &self.profile_name
+  }
+
+  /// Sets the active profile
+  ///
+  /// Also sets the storage file path of the profile.
+  pub fn set_profile_name(&mut self, profile_name: String) {
+    self.file_path = ProfileHandler::get_storage_file_path(&profile_name);
+    self.profile_name = profile_name;
+  }
+
   /// Toggles the background between transparent and the layout color.
   ///
   /// Flips the `has_transparent_bg` flag and returns the updated state.
@@ -491,6 +696,15 @@ impl TukaiConfig {
     self.has_transparent_bg
   }
 
+  /// Toggles the keystrokes recording
+  ///
+  /// # Returns
+  /// The new state of the recording (`true` if recorded, `false` otherwise).
+  pub fn toggle_record_keystrokes(&mut self) -> bool {
+    self.record_keystrokes = !self.record_keystrokes;
+    self.record_keystrokes
+  }
+
   /// Switches the typing duration.
   ///
   /// Options:
@@ -509,6 +723,38 @@ impl TukaiConfig {
     self.typing_duration.clone()
   }
 
+  /// Switches the pace caret.
+  ///
+  /// The target WPM has to be resolved again (see `StorageHandler::get_pace_wpm`).
+  pub fn switch_pace_caret(&mut self) -> PaceCaret {
+    self.pace_caret = self.pace_caret.next();
+    self.pace_caret
+  }
+
+  /// Switches the difficulty.
+  pub fn switch_difficulty(&mut self) -> Difficulty {
+    self.difficulty = self.difficulty.next();
+    self.difficulty
+  }
+
+  /// Switches the visibility of the text.
+  pub fn switch_visibility(&mut self) -> Visibility {
+    self.visibility = self.visibility.next();
+    self.visibility
+  }
+
+  /// Switches the physical keyboard layout.
+  pub fn switch_keyboard_layout(&mut self) -> KeyboardLayout {
+    self.keyboard_layout = self.keyboard_layout.next();
+    self.keyboard_layout
+  }
+
+  /// Toggles the remapping of the typed characters into the keyboard layout.
+  pub fn toggle_remap_keys(&mut self) -> bool {
+    self.remap_keys = !self.remap_keys;
+    self.remap_keys
+  }
+
   /// Returns the background color of the selected layout.
   ///
   /// If `has_transparent_bg` is `true`, no background color is applied.
@@ -523,6 +769,9 @@ impl TukaiConfig {
 }
 
 pub struct TukaiConfigBuilder {
+  // Selected profile name
+  profile_name: Option<String>,
+
   // Path to the `language file`
   file_path: Option<PathBuf>,
 
@@ -537,19 +786,35 @@ pub struct TukaiConfigBuilder {
 
   // Typing duration per run
   typing_duration: Option<TypingDuration>,
+
+  // Seed of the generated texts
+  seed: Option<u64>,
+}
+
+impl Default for TukaiConfigBuilder {
+  fn default() -> Self {
+    Self::new()
+  }
 }
 
 impl TukaiConfigBuilder {
   pub fn new() -> Self {
     Self {
+      profile_name: None,
       file_path: None,
       layout: None,
       language: None,
       has_transparent_bg: true,
       typing_duration: None,
+      seed: None,
     }
   }
 
+  pub fn profile_name(mut self, profile_name: String) -> Self {
+    self.profile_name = Some(profile_name);
+    self
+  }
+
   #[allow(unused)]
   pub fn file_path<P: AsRef<Path>>(mut self, file_path: P) -> Self {
     self.file_path = Some(file_path.as_ref().to_path_buf());
@@ -562,17 +827,43 @@ impl TukaiConfigBuilder {
     self
   }
 
+  /// Seeds the generated texts, so the runs are reproducible (e.g. in the tests)
+  #[allow(unused)]
+  pub fn seed(mut self, seed: u64) -> Self {
+    self.seed = Some(seed);
+    self
+  }
+
   pub fn build(self) -> TukaiConfig {
     let config_default = TukaiConfig::default();
 
+    let profile_name = self.profile_name.unwrap_or(config_default.profile_name);
+
+    // Explicit file path has a priority over the profile storage file
+    let file_path = self
+      .file_path
+      .unwrap_or_else(|| ProfileHandler::get_storage_file_path(&profile_name));
+
     TukaiConfig {
-      file_path: self.file_path.unwrap_or(config_default.file_path),
+      profile_name,
+      file_path,
       layout: self.layout.unwrap_or(config_default.layout),
       language: self.language.unwrap_or(config_default.language),
       has_transparent_bg: self.has_transparent_bg,
       typing_duration: self
         .typing_duration
         .unwrap_or(config_default.typing_duration),
+      pace_caret: config_default.pace_caret,
+      pace_wpm: config_default.pace_wpm,
+      record_keystrokes: config_default.record_keystrokes,
+      difficulty: config_default.difficulty,
+      visibility: config_default.visibility,
+      keyboard_layout: config_default.keyboard_layout,
+      remap_keys: config_default.remap_keys,
+      rng: self
+        .seed
+        .map(|seed| RefCell::new(StdRng::seed_from_u64(seed)))
+        .unwrap_or(config_default.rng),
     }
   }
 }
@@ -0,0 +1,1291 @@
+use std::{collections::BTreeMap, time::Instant};
+
+use chrono::Utc;
+
+use crate::{
+  config::{Difficulty, TypingDuration, Visibility},
+  storage::{
+    drills::{DrillRepetition, DrillResult},
+    key_stats::KeyCounter,
+    keystroke_log::{Keystroke, KeystrokeLog},
+    stat_helper::StatHelper,
+    stats::{RunMetrics, Stat, StatFlags},
+  },
+};
+
+/// Handler for the advanced run metrics
+///
+/// Counts keystrokes and corrections and samples the typing pace
+#[derive(Default)]
+pub struct MetricsHandler {
+  keystrokes_counter: usize,
+
+  corrected_errors_counter: usize,
+
+  // Typed characters count at the end of every second
+  chars_counter_per_second: Vec<usize>,
+
+  run_started_at: Option<Instant>,
+
+  // Start of the current pause
+  paused_at: Option<Instant>,
+
+  // Milliseconds of the finished pauses
+  paused_ms: u64,
+
+  time_to_first_error_ms: Option<u64>,
+
+  keystroke_log: KeystrokeLog,
+
+  // Presses and mistakes by the expected characters
+  key_counters: BTreeMap<char, KeyCounter>,
+}
+
+impl MetricsHandler {
+  /// Creates MetricsHandler with empty counters
+  pub fn new() -> Self {
+    Self {
+      keystrokes_counter: 0,
+      corrected_errors_counter: 0,
+      chars_counter_per_second: Vec::new(),
+      run_started_at: None,
+      paused_at: None,
+      paused_ms: 0,
+      time_to_first_error_ms: None,
+      keystroke_log: KeystrokeLog::default(),
+      key_counters: BTreeMap::new(),
+    }
+  }
+
+  /// Marks the start of the run typing the text
+  pub fn start(&mut self, text: &str) {
+    self.run_started_at = Some(Instant::now());
+    self.keystroke_log = KeystrokeLog::new(text.to_string());
+  }
+
+  /// Returns milliseconds from the run start (without the pauses)
+  pub fn get_elapsed_ms(&self) -> u32 {
+    self.run_started_at.map_or(0, |run_started_at| {
+      let current_pause_ms = self
+        .paused_at
+        .map_or(0, |paused_at| paused_at.elapsed().as_millis() as u64);
+
+      (run_started_at.elapsed().as_millis() as u64)
+        .saturating_sub(self.paused_ms + current_pause_ms) as u32
+    })
+  }
+
+  /// Stops counting the elapsed time
+  pub fn pause(&mut self) {
+    self.paused_at.get_or_insert_with(Instant::now);
+  }
+
+  /// Continues counting the elapsed time
+  pub fn resume(&mut self) {
+    if let Some(paused_at) = self.paused_at.take() {
+      self.paused_ms += paused_at.elapsed().as_millis() as u64;
+    }
+  }
+
+  /// Counts a pressed key rejected by the difficulty
+  ///
+  /// Rejected mistaken key counts as a corrected error.
+  pub fn add_rejected_keystroke(&mut self, is_mistaken: bool) {
+    self.keystrokes_counter += 1;
+
+    if is_mistaken {
+      self.corrected_errors_counter += 1;
+      self.add_error();
+    }
+  }
+
+  /// Counts and logs a pressed key
+  pub fn add_keystroke(&mut self, keystroke: Keystroke) {
+    self.keystrokes_counter += 1;
+
+    let elapsed_ms = self.get_elapsed_ms();
+    self.keystroke_log.push(elapsed_ms, keystroke);
+  }
+
+  /// Counts a press of the expected character
+  pub fn add_key_press(&mut self, expected_char: char, is_mistaken: bool) {
+    self
+      .key_counters
+      .entry(expected_char)
+      .or_default()
+      .add_press(is_mistaken);
+  }
+
+  /// Returns the presses and mistakes by the expected characters
+  pub fn get_key_counters(&self) -> &BTreeMap<char, KeyCounter> {
+    &self.key_counters
+  }
+
+  /// Takes the logged keystrokes of the run
+  pub fn take_keystroke_log(&mut self) -> KeystrokeLog {
+    std::mem::take(&mut self.keystroke_log)
+  }
+
+  /// Counts fixed mistakes
+  pub fn add_corrected_errors(&mut self, count: usize) {
+    self.corrected_errors_counter += count;
+  }
+
+  /// Records the time of the first mistake
+  pub fn add_error(&mut self) {
+    if self.time_to_first_error_ms.is_none() && self.run_started_at.is_some() {
+      self.time_to_first_error_ms = Some(self.get_elapsed_ms() as u64);
+    }
+  }
+
+  /// Samples the typed characters count (called every second)
+  pub fn add_second(&mut self, chars_counter: usize) {
+    self.chars_counter_per_second.push(chars_counter);
+  }
+
+  /// Returns WPM typed in every second of the run
+  pub fn get_per_second_wpm(&self) -> Vec<f64> {
+    let mut previous_chars_counter = 0;
+
+    self
+      .chars_counter_per_second
+      .iter()
+      .map(|chars_counter| {
+        let typed_chars = chars_counter.saturating_sub(previous_chars_counter);
+        previous_chars_counter = *chars_counter;
+
+        typed_chars as f64 / 5.0 * 60.0
+      })
+      .collect()
+  }
+
+  /// Creates the run metrics of the typed text
+  pub fn get_run_metrics(&self, typed_text: &TypedText) -> RunMetrics {
+    let (extra_chars, missed_chars) = typed_text.get_extra_and_missed_chars();
+
+    RunMetrics {
+      consistency: StatHelper::get_calculated_consistency(&self.get_per_second_wpm()),
+      corrected_errors: self.corrected_errors_counter,
+      uncorrected_errors: typed_text.get_mistakes_counter(),
+      extra_chars,
+      missed_chars,
+      keystrokes: self.keystrokes_counter,
+      time_to_first_error_ms: self.time_to_first_error_ms,
+    }
+  }
+
+  /// Creates the run metrics of the free text (zen mode)
+  ///
+  /// Free text has no mistakes, only the keystrokes and the pace are measured.
+  pub fn get_free_run_metrics(&self) -> RunMetrics {
+    RunMetrics {
+      consistency: StatHelper::get_calculated_consistency(&self.get_per_second_wpm()),
+      keystrokes: self.keystrokes_counter,
+      ..Default::default()
+    }
+  }
+}
+
+/// Input typed against the text, aligned word by word
+///
+/// Space jumps to the next word (the remaining letters are missed),
+/// letters typed beyond the word are extra. Backspace returns
+/// to the previous word only if it was typed incorrectly.
+#[derive(Debug, Clone, Default)]
+pub struct TypedText {
+  // Words of the text (split by the spaces)
+  words: Vec<String>,
+
+  // Typed words, the last one is the current word
+  typed_words: Vec<String>,
+}
+
+impl TypedText {
+  pub fn new(text: &str) -> Self {
+    Self {
+      words: text.split(' ').map(String::from).collect(),
+      typed_words: vec![String::new()],
+    }
+  }
+
+  /// Returns the words of the text
+  pub fn get_words(&self) -> &[String] {
+    &self.words
+  }
+
+  /// Returns the typed word (None if not reached yet)
+  pub fn get_typed_word(&self, word_index: usize) -> Option<&str> {
+    self.typed_words.get(word_index).map(String::as_str)
+  }
+
+  /// Returns the index of the current word
+  pub fn get_current_word_index(&self) -> usize {
+    self.typed_words.len() - 1
+  }
+
+  fn get_word(&self, word_index: usize) -> &str {
+    self.words.get(word_index).map_or("", String::as_str)
+  }
+
+  fn get_current_word(&self) -> &str {
+    self.get_word(self.get_current_word_index())
+  }
+
+  fn get_current_typed_word(&self) -> &str {
+    self.typed_words.last().map_or("", String::as_str)
+  }
+
+  /// Returns the typed input (words separated by the spaces)
+  pub fn get_input(&self) -> String {
+    self.typed_words.join(" ")
+  }
+
+  /// Returns the count of the typed characters (including the spaces)
+  pub fn get_typed_chars_count(&self) -> usize {
+    self.get_input().chars().count()
+  }
+
+  /// Returns the cursor index (in characters) within the text
+  ///
+  /// Extra letters keep the cursor at the end of the word.
+  pub fn get_cursor_index(&self) -> usize {
+    let current_word_index = self.get_current_word_index();
+
+    let passed_chars = (0..current_word_index)
+      .map(|word_index| self.get_word(word_index).chars().count() + 1)
+      .sum::<usize>();
+
+    let current_chars = self
+      .get_current_typed_word()
+      .chars()
+      .count()
+      .min(self.get_current_word().chars().count());
+
+    passed_chars + current_chars
+  }
+
+  /// Verifies if the typed character of the word is mistaken
+  fn is_char_mistaken(&self, word_index: usize, char_index: usize, c: char) -> bool {
+    self.get_word(word_index).chars().nth(char_index) != Some(c)
+  }
+
+  /// Verifies if the current word is typed completely and correctly
+  pub fn is_current_word_correct(&self) -> bool {
+    self.get_current_typed_word() == self.get_current_word()
+  }
+
+  /// Verifies if the typed character leaves the input unchanged
+  ///
+  /// Space at the word start or after the last word is ignored.
+  pub fn is_char_ignored(&self, c: char) -> bool {
+    c == ' '
+      && ((self.get_current_typed_word().is_empty() && !self.get_current_word().is_empty())
+        || self.get_current_word_index() + 1 >= self.words.len())
+  }
+
+  /// Types the character
+  ///
+  /// Returns whether the character is correct
+  /// (the space is correct if it ends the correctly typed word).
+  pub fn type_char(&mut self, c: char) -> bool {
+    if c != ' ' {
+      let current_word_index = self.get_current_word_index();
+      let char_index = self.get_current_typed_word().chars().count();
+
+      if let Some(typed_word) = self.typed_words.last_mut() {
+        typed_word.push(c);
+      }
+
+      return !self.is_char_mistaken(current_word_index, char_index, c);
+    }
+
+    if self.is_char_ignored(c) {
+      return true;
+    }
+
+    let is_correct = self.is_current_word_correct();
+    self.typed_words.push(String::new());
+
+    is_correct
+  }
+
+  /// Removes the last typed character
+  ///
+  /// Returns whether the removed character was mistaken.
+  pub fn backspace(&mut self) -> bool {
+    if self.get_current_typed_word().is_empty() {
+      self.return_to_previous_word();
+      return false;
+    }
+
+    let current_word_index = self.get_current_word_index();
+
+    let Some(removed_char) = self.typed_words.last_mut().and_then(String::pop) else {
+      return false;
+    };
+
+    let char_index = self.get_current_typed_word().chars().count();
+
+    self.is_char_mistaken(current_word_index, char_index, removed_char)
+  }
+
+  /// Deletes the current word (or the previous incorrect word)
+  ///
+  /// Returns the count of the removed mistaken characters.
+  pub fn delete_word(&mut self) -> usize {
+    if self.get_current_typed_word().is_empty() {
+      self.return_to_previous_word();
+    }
+
+    let current_word_index = self.get_current_word_index();
+
+    let mistakes_count = self
+      .get_current_typed_word()
+      .chars()
+      .enumerate()
+      .filter(|(char_index, c)| self.is_char_mistaken(current_word_index, *char_index, *c))
+      .count();
+
+    if let Some(typed_word) = self.typed_words.last_mut() {
+      typed_word.clear();
+    }
+
+    mistakes_count
+  }
+
+  /// Returns to the previous word if it was typed incorrectly
+  fn return_to_previous_word(&mut self) {
+    let current_word_index = self.get_current_word_index();
+
+    if current_word_index > 0
+      && self.typed_words[current_word_index - 1] != self.get_word(current_word_index - 1)
+    {
+      self.typed_words.pop();
+    }
+  }
+
+  /// Applies the keystroke
+  ///
+  /// Returns whether the typed character is correct (corrections are always correct).
+  pub fn apply(&mut self, keystroke: Keystroke) -> bool {
+    match keystroke {
+      Keystroke::Char(c) => self.type_char(c),
+      Keystroke::Backspace => {
+        self.backspace();
+        true
+      }
+      Keystroke::DeleteWord => {
+        self.delete_word();
+        true
+      }
+    }
+  }
+
+  /// Returns the count of the extra and the missed characters
+  ///
+  /// Letters of the current word aren't missed yet.
+  pub fn get_extra_and_missed_chars(&self) -> (usize, usize) {
+    let current_word_index = self.get_current_word_index();
+
+    self
+      .typed_words
+      .iter()
+      .enumerate()
+      .fold((0, 0), |(extra, missed), (word_index, typed_word)| {
+        let typed_len = typed_word.chars().count();
+        let word_len = self.get_word(word_index).chars().count();
+
+        let word_missed = if word_index < current_word_index {
+          word_len.saturating_sub(typed_len)
+        } else {
+          0
+        };
+
+        (
+          extra + typed_len.saturating_sub(word_len),
+          missed + word_missed,
+        )
+      })
+  }
+
+  /// Returns the count of the mistakes
+  ///
+  /// Mistaken, extra and missed characters are counted.
+  pub fn get_mistakes_counter(&self) -> usize {
+    let (extra, missed) = self.get_extra_and_missed_chars();
+
+    let mistaken = self
+      .typed_words
+      .iter()
+      .enumerate()
+      .map(|(word_index, typed_word)| {
+        typed_word
+          .chars()
+          .zip(self.get_word(word_index).chars())
+          .filter(|(typed_char, c)| typed_char != c)
+          .count()
+      })
+      .sum::<usize>();
+
+    mistaken + extra + missed
+  }
+}
+
+/// Seconds without a keystroke after which the run is AFK (invalid)
+pub const AFK_TIMEOUT_SECS: u32 = 10;
+
+/// Typing session of a single run independent of the UI
+///
+/// Holds the text, the typed input, the mistakes and the timing,
+/// and computes the stat of the run when it's finished.
+pub struct TypingSession {
+  // Text to type
+  text: String,
+
+  // User typed input aligned with the words of the text
+  typed_text: TypedText,
+
+  metrics_handler: MetricsHandler,
+
+  typing_duration: TypingDuration,
+
+  difficulty: Difficulty,
+
+  visibility: Visibility,
+
+  is_running: bool,
+
+  // Running run is paused (the timer is frozen)
+  is_paused: bool,
+
+  // Seconds counted by the `tick`
+  time_secs: u32,
+
+  // Seconds counted by the `tick` since the last keystroke
+  idle_secs: u32,
+
+  // Run was ended by a mistake (sudden death)
+  is_failed: bool,
+
+  // Irregularities of the running run
+  flags: StatFlags,
+}
+
+impl TypingSession {
+  /// Creates the session typing the text for the duration
+  pub fn new(text: String, typing_duration: TypingDuration) -> Self {
+    Self {
+      typed_text: TypedText::new(&text),
+      text,
+      metrics_handler: MetricsHandler::new(),
+      typing_duration,
+      difficulty: Difficulty::default(),
+      visibility: Visibility::default(),
+      is_running: false,
+      is_paused: false,
+      time_secs: 0,
+      idle_secs: 0,
+      is_failed: false,
+      flags: StatFlags::default(),
+    }
+  }
+
+  /// Sets the difficulty of the run
+  pub fn with_difficulty(mut self, difficulty: Difficulty) -> Self {
+    self.difficulty = difficulty;
+    self
+  }
+
+  /// Sets the visibility of the text during the run
+  pub fn with_visibility(mut self, visibility: Visibility) -> Self {
+    self.visibility = visibility;
+    self
+  }
+
+  pub fn get_text(&self) -> &str {
+    &self.text
+  }
+
+  pub fn get_input(&self) -> String {
+    self.typed_text.get_input()
+  }
+
+  /// Returns the input aligned with the words of the text
+  pub fn get_typed_text(&self) -> &TypedText {
+    &self.typed_text
+  }
+
+  pub fn get_cursor_index(&self) -> usize {
+    self.typed_text.get_cursor_index()
+  }
+
+  pub fn get_typing_duration(&self) -> &TypingDuration {
+    &self.typing_duration
+  }
+
+  pub fn is_running(&self) -> bool {
+    self.is_running
+  }
+
+  pub fn is_paused(&self) -> bool {
+    self.is_paused
+  }
+
+  pub fn get_difficulty(&self) -> Difficulty {
+    self.difficulty
+  }
+
+  pub fn get_visibility(&self) -> Visibility {
+    self.visibility
+  }
+
+  /// Returns the seconds of the run counted by the `tick`
+  pub fn get_time_secs(&self) -> u32 {
+    self.time_secs
+  }
+
+  /// Verifies if the run was ended by a mistake (sudden death)
+  pub fn is_failed(&self) -> bool {
+    self.is_failed
+  }
+
+  /// Verifies if there was no keystroke for the `AFK_TIMEOUT_SECS`
+  pub fn is_idle(&self) -> bool {
+    self.idle_secs >= AFK_TIMEOUT_SECS
+  }
+
+  /// Returns the count of the uncorrected mistakes
+  ///
+  /// Mistaken, extra and missed characters are counted.
+  pub fn get_mistakes_counter(&self) -> usize {
+    self.typed_text.get_mistakes_counter()
+  }
+
+  /// Returns the presses and mistakes of the run by the expected characters
+  pub fn get_key_counters(&self) -> &BTreeMap<char, KeyCounter> {
+    self.metrics_handler.get_key_counters()
+  }
+
+  /// Returns milliseconds from the run start
+  pub fn get_elapsed_ms(&self) -> u32 {
+    self.metrics_handler.get_elapsed_ms()
+  }
+
+  /// Returns seconds from the run start (rounded, without the pauses)
+  pub fn get_elapsed_secs(&self) -> usize {
+    ((self.get_elapsed_ms() + 500) / 1000) as usize
+  }
+
+  /// Returns the remaining seconds counted by the `tick`
+  ///
+  /// No time remains for the failed run.
+  pub fn get_remaining_time(&self) -> usize {
+    if self.is_failed {
+      return 0;
+    }
+
+    self
+      .typing_duration
+      .as_seconds()
+      .saturating_sub(self.time_secs as usize)
+  }
+
+  /// Verifies if the whole text is typed
+  pub fn is_text_completed(&self) -> bool {
+    self.get_cursor_index() >= self.text.chars().count()
+  }
+
+  /// Returns the WPM since the run start
+  pub fn get_current_wpm(&self) -> usize {
+    let elapsed_secs = (self.get_elapsed_ms() / 1000) as usize;
+
+    StatHelper::get_calculated_wpm(
+      self.get_cursor_index(),
+      self.get_mistakes_counter(),
+      elapsed_secs.max(1),
+    )
+  }
+
+  /// Starts the run
+  ///
+  /// The first typed character starts the run too.
+  pub fn start(&mut self) {
+    if !self.is_running {
+      self.metrics_handler = MetricsHandler::new();
+      self.metrics_handler.start(&self.text);
+      self.flags = StatFlags::default();
+    }
+
+    self.is_running = true;
+  }
+
+  /// Pauses the running run
+  ///
+  /// The timer is frozen and the run is flagged as paused.
+  pub fn pause(&mut self) {
+    if self.is_running && !self.is_paused {
+      self.is_paused = true;
+      self.flags.was_paused = true;
+      self.metrics_handler.pause();
+    }
+  }
+
+  /// Resumes the paused run
+  pub fn resume(&mut self) {
+    if self.is_paused {
+      self.is_paused = false;
+      self.idle_secs = 0;
+      self.metrics_handler.resume();
+    }
+  }
+
+  /// Counts a second of the running run (called every second)
+  ///
+  /// Seconds of the paused run aren't counted.
+  /// The idle run is flagged as AFK.
+  pub fn tick(&mut self) {
+    if self.is_paused {
+      return;
+    }
+
+    self.idle_secs += 1;
+
+    if self.is_idle() {
+      self.flags.is_afk = true;
+    }
+
+    self.time_secs += 1;
+    self
+      .metrics_handler
+      .add_second(self.typed_text.get_typed_chars_count());
+  }
+
+  /// Handles the key pressed by the user
+  ///
+  /// Keystrokes of the running run are counted and logged.
+  /// A keystroke of the paused run only resumes it.
+  pub fn press(&mut self, keystroke: Keystroke) {
+    if self.is_paused {
+      self.resume();
+      return;
+    }
+
+    let rejection = self.get_rejection(keystroke);
+
+    // Only a keystroke changing the input starts the run
+    let changes_input = match keystroke {
+      Keystroke::Char(c) => rejection.is_none() && !self.typed_text.is_char_ignored(c),
+      _ => false,
+    };
+
+    if changes_input && self.get_cursor_index() == 0 {
+      self.start();
+    }
+
+    if self.is_running {
+      self.idle_secs = 0;
+    }
+
+    // Key stats count the rejected characters too (the key was pressed)
+    if self.is_running
+      && let Keystroke::Char(c) = keystroke
+      && !self.typed_text.is_char_ignored(c)
+      && let Some(expected_char) = self.text.chars().nth(self.get_cursor_index())
+    {
+      self
+        .metrics_handler
+        .add_key_press(expected_char, c != expected_char);
+    }
+
+    // Rejected keystrokes are counted, but not logged (they don't change the input)
+    if let Some(is_mistaken) = rejection {
+      if self.is_running {
+        self.metrics_handler.add_rejected_keystroke(is_mistaken);
+      }
+
+      return;
+    }
+
+    if self.is_running {
+      self.metrics_handler.add_keystroke(keystroke);
+    }
+
+    self.apply_keystroke(keystroke);
+  }
+
+  /// Verifies if the difficulty rejects the keystroke
+  ///
+  /// Returns whether the rejected key is mistaken, None if it is accepted.
+  fn get_rejection(&self, keystroke: Keystroke) -> Option<bool> {
+    let Keystroke::Char(c) = keystroke else {
+      return None;
+    };
+
+    let expected_char = self.text.chars().nth(self.get_cursor_index())?;
+
+    match self.difficulty {
+      Difficulty::StopOnLetter if c != expected_char => Some(true),
+      Difficulty::StopOnWord if c == ' ' && !self.typed_text.is_current_word_correct() => {
+        Some(c != expected_char)
+      }
+      _ => None,
+    }
+  }
+
+  /// Applies the keystroke on the input without logging it (e.g. a replay)
+  pub fn apply_keystroke(&mut self, keystroke: Keystroke) {
+    match keystroke {
+      Keystroke::Char(c) => {
+        if !self.typed_text.type_char(c) {
+          self.add_error();
+        }
+      }
+      Keystroke::Backspace => {
+        if self.typed_text.backspace() {
+          self.metrics_handler.add_corrected_errors(1);
+        }
+      }
+      Keystroke::DeleteWord => {
+        let corrected_errors = self.typed_text.delete_word();
+        self.metrics_handler.add_corrected_errors(corrected_errors);
+      }
+    }
+  }
+
+  /// Rejects the pasted text
+  ///
+  /// The text is never typed, the running run is flagged.
+  pub fn reject_paste(&mut self) {
+    if self.is_running {
+      self.flags.has_paste = true;
+    }
+  }
+
+  /// Flags the running run when the terminal loses the focus
+  pub fn lose_focus(&mut self) {
+    if self.is_running {
+      self.flags.has_focus_loss = true;
+    }
+  }
+
+  /// Returns the irregularities of the run
+  pub fn get_flags(&self) -> &StatFlags {
+    &self.flags
+  }
+
+  /// Stops the run and returns its stat
+  ///
+  /// The keystroke log is attached only if recorded.
+  pub fn finish(&mut self, language: String, record_keystrokes: bool) -> Stat {
+    self.resume();
+    self.is_running = false;
+
+    let run_metrics = self.metrics_handler.get_run_metrics(&self.typed_text);
+
+    // Missed characters are passed as mistakes
+    let chars_counter = self.typed_text.get_typed_chars_count() + run_metrics.missed_chars;
+
+    // Completed text or a failure ends the run before its duration
+    let stat = if self.is_text_completed() || self.is_failed {
+      Stat::new_ended_early(
+        self.typing_duration.clone(),
+        self.get_elapsed_secs(),
+        language,
+        chars_counter,
+        self.get_mistakes_counter(),
+      )
+    } else {
+      Stat::new(
+        self.typing_duration.clone(),
+        language,
+        chars_counter,
+        self.get_mistakes_counter(),
+      )
+    };
+
+    let stat = stat
+      .with_metrics(run_metrics)
+      .with_flags(self.flags)
+      .with_difficulty(self.difficulty)
+      .with_visibility(self.visibility);
+
+    if record_keystrokes {
+      stat.with_keystroke_log(self.metrics_handler.take_keystroke_log())
+    } else {
+      stat
+    }
+  }
+
+  /// Records the mistake
+  ///
+  /// The first mistake ends the run in the sudden death.
+  fn add_error(&mut self) {
+    self.metrics_handler.add_error();
+
+    if self.difficulty == Difficulty::SuddenDeath {
+      self.is_failed = true;
+    }
+  }
+}
+
+/// Removes the last word of the input (with the spaces after it)
+fn delete_last_word(input: &mut String) {
+  let word_start = input
+    .trim_end()
+    .rfind(' ')
+    .map_or(0, |space_index| space_index + 1);
+
+  input.truncate(word_start);
+}
+
+/// Free typing session without a text (zen mode)
+///
+/// Runs until the user stops it, the stat is based on the typed input and the time.
+#[derive(Default)]
+pub struct ZenSession {
+  // Freely typed input
+  input: String,
+
+  metrics_handler: MetricsHandler,
+
+  is_running: bool,
+
+  // Seconds counted by the `tick`
+  time_secs: u32,
+}
+
+impl ZenSession {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn get_input(&self) -> &str {
+    &self.input
+  }
+
+  pub fn is_running(&self) -> bool {
+    self.is_running
+  }
+
+  /// Returns the seconds of the run counted by the `tick`
+  pub fn get_time_secs(&self) -> u32 {
+    self.time_secs
+  }
+
+  /// Returns the count of the typed words
+  pub fn get_words_count(&self) -> usize {
+    self.input.split_whitespace().count()
+  }
+
+  /// Returns the WPM since the run start
+  pub fn get_current_wpm(&self) -> usize {
+    StatHelper::get_calculated_wpm(
+      self.input.chars().count(),
+      0,
+      self.time_secs.max(1) as usize,
+    )
+  }
+
+  /// Counts a second of the running run (called every second)
+  pub fn tick(&mut self) {
+    if !self.is_running {
+      return;
+    }
+
+    self.time_secs += 1;
+    self.metrics_handler.add_second(self.input.chars().count());
+  }
+
+  /// Handles the key pressed by the user
+  ///
+  /// The first typed character starts the run.
+  pub fn press(&mut self, keystroke: Keystroke) {
+    if !self.is_running {
+      if !matches!(keystroke, Keystroke::Char(_)) {
+        return;
+      }
+
+      self.is_running = true;
+      self.metrics_handler.start("");
+    }
+
+    self.metrics_handler.add_keystroke(keystroke);
+
+    match keystroke {
+      Keystroke::Char(c) => self.input.push(c),
+      Keystroke::Backspace => {
+        self.input.pop();
+      }
+      Keystroke::DeleteWord => delete_last_word(&mut self.input),
+    }
+  }
+
+  /// Stops the run and returns its stat
+  pub fn finish(&mut self, language: String) -> Stat {
+    self.is_running = false;
+
+    Stat::new_untimed(
+      self.time_secs as usize,
+      language,
+      self.input.chars().count(),
+    )
+    .with_metrics(self.metrics_handler.get_free_run_metrics())
+  }
+}
+
+/// Drill repeating the word or phrase
+///
+/// Every repetition is completed by typing the phrase correctly,
+/// its speed and accuracy are measured separately.
+pub struct DrillSession {
+  // Drilled word or phrase
+  phrase: String,
+
+  repetitions_count: usize,
+
+  // Input of the current repetition
+  input: String,
+
+  // Typed characters of the current repetition (including the corrected)
+  typed_chars_counter: usize,
+
+  // Mistaken characters of the current repetition (including the corrected)
+  mistakes_counter: usize,
+
+  // Elapsed ms of the first keystroke of the current repetition
+  repetition_started_ms: Option<u32>,
+
+  repetitions: Vec<DrillRepetition>,
+
+  drill_started_at: Option<Instant>,
+}
+
+impl DrillSession {
+  pub fn new(phrase: String, repetitions_count: usize) -> Self {
+    Self {
+      phrase,
+      repetitions_count,
+      input: String::new(),
+      typed_chars_counter: 0,
+      mistakes_counter: 0,
+      repetition_started_ms: None,
+      repetitions: Vec::new(),
+      drill_started_at: None,
+    }
+  }
+
+  pub fn get_phrase(&self) -> &str {
+    &self.phrase
+  }
+
+  pub fn get_input(&self) -> &str {
+    &self.input
+  }
+
+  pub fn get_repetitions_count(&self) -> usize {
+    self.repetitions_count
+  }
+
+  /// Returns the measured repetitions
+  pub fn get_repetitions(&self) -> &[DrillRepetition] {
+    &self.repetitions
+  }
+
+  /// Verifies if the typed character of the current repetition is mistaken
+  pub fn is_char_mistaken(&self, char_index: usize) -> bool {
+    self.input.chars().nth(char_index) != self.phrase.chars().nth(char_index)
+  }
+
+  /// Verifies if the drill is started and not completed yet
+  pub fn is_running(&self) -> bool {
+    self.drill_started_at.is_some() && !self.is_completed()
+  }
+
+  /// Verifies if all the repetitions are typed
+  pub fn is_completed(&self) -> bool {
+    self.repetitions.len() >= self.repetitions_count
+  }
+
+  /// Handles the key pressed by the user
+  pub fn press(&mut self, keystroke: Keystroke) {
+    let drill_started_at = *self.drill_started_at.get_or_insert_with(Instant::now);
+
+    self.press_at(keystroke, drill_started_at.elapsed().as_millis() as u32);
+  }
+
+  /// Handles the key pressed in the milliseconds from the drill start
+  ///
+  /// Every repetition is measured from its own first keystroke.
+  pub fn press_at(&mut self, keystroke: Keystroke, elapsed_ms: u32) {
+    if self.is_completed() {
+      return;
+    }
+
+    self.drill_started_at.get_or_insert_with(Instant::now);
+
+    let repetition_started_ms = *self.repetition_started_ms.get_or_insert(elapsed_ms);
+
+    match keystroke {
+      Keystroke::Char(c) => {
+        let char_index = self.input.chars().count();
+
+        self.input.push(c);
+        self.typed_chars_counter += 1;
+
+        if self.is_char_mistaken(char_index) {
+          self.mistakes_counter += 1;
+        }
+      }
+      Keystroke::Backspace => {
+        self.input.pop();
+      }
+      Keystroke::DeleteWord => delete_last_word(&mut self.input),
+    }
+
+    if self.input == self.phrase {
+      let repetition_ms = elapsed_ms.saturating_sub(repetition_started_ms).max(1);
+      let phrase_chars = self.phrase.chars().count();
+
+      self.repetitions.push(DrillRepetition {
+        wpm: (phrase_chars as f64 / 5.0 * 60_000.0 / repetition_ms as f64).round() as usize,
+        accuracy: StatHelper::get_calculated_accuracy(
+          self.typed_chars_counter,
+          self.mistakes_counter,
+        ),
+      });
+
+      self.input.clear();
+      self.typed_chars_counter = 0;
+      self.mistakes_counter = 0;
+      self.repetition_started_ms = None;
+    }
+  }
+
+  /// Returns the result of the drill
+  pub fn get_result(&self) -> DrillResult {
+    DrillResult {
+      phrase: self.phrase.clone(),
+      repetitions: self.repetitions.clone(),
+      created_at: Utc::now().timestamp(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::storage::stats::RunMode;
+
+  fn type_text(session: &mut TypingSession, text: &str) {
+    for c in text.chars() {
+      session.press(Keystroke::Char(c));
+    }
+  }
+
+  #[test]
+  fn session_tracks_mistakes_and_corrections() {
+    let mut session = TypingSession::new(String::from("hello world "), TypingDuration::FifteenSec);
+
+    // Ignored keystroke doesn't start the run
+    session.press(Keystroke::Char(' '));
+    assert!(!session.is_running());
+
+    type_text(&mut session, "hellp");
+    assert!(session.is_running());
+    assert_eq!(session.get_mistakes_counter(), 1);
+
+    session.press(Keystroke::Backspace);
+    type_text(&mut session, "o wrld");
+    assert_eq!(session.get_mistakes_counter(), 3);
+
+    session.press(Keystroke::DeleteWord);
+    assert_eq!(session.get_input(), "hello ");
+    assert_eq!(session.get_mistakes_counter(), 0);
+
+    type_text(&mut session, "world ");
+    assert!(session.is_text_completed());
+
+    let stat = session.finish(String::from("en"), true);
+    assert!(!session.is_running());
+
+    let metrics = stat.get_metrics().unwrap();
+    assert_eq!(metrics.corrected_errors, 4);
+    assert_eq!(metrics.uncorrected_errors, 0);
+    assert_eq!(metrics.keystrokes, 19);
+    assert_eq!(stat.get_accuracy(), 100.0);
+    assert!(stat.get_keystroke_log().is_some());
+
+    // Completed text is scored over the typed seconds, not the whole duration
+    assert_eq!(stat.get_practice_secs(), 1);
+    assert_eq!(stat.get_average_wpm(), 144);
+  }
+
+  #[test]
+  fn typed_text_aligns_words() {
+    let mut typed_text = TypedText::new("hello world again ");
+
+    // Space skips the rest of the word, extra letters stay in the word
+    for c in "hel worldss ".chars() {
+      typed_text.type_char(c);
+    }
+
+    assert_eq!(typed_text.get_current_word_index(), 2);
+    assert_eq!(typed_text.get_cursor_index(), 12);
+    assert_eq!(typed_text.get_extra_and_missed_chars(), (2, 2));
+    assert_eq!(typed_text.get_mistakes_counter(), 4);
+
+    // Backspace returns to the previous incorrect word
+    typed_text.backspace();
+    assert_eq!(typed_text.get_current_word_index(), 1);
+    assert_eq!(typed_text.get_cursor_index(), 11);
+
+    typed_text.backspace();
+    typed_text.backspace();
+    assert!(typed_text.is_current_word_correct());
+
+    // Correct words are kept
+    typed_text.type_char(' ');
+    typed_text.backspace();
+    assert_eq!(typed_text.get_current_word_index(), 2);
+    assert_eq!(typed_text.get_input(), "hel world ");
+  }
+
+  #[test]
+  fn difficulty_rejects_keystrokes() {
+    let text = String::from("ab cd ");
+
+    let mut session = TypingSession::new(text.clone(), TypingDuration::FifteenSec)
+      .with_difficulty(Difficulty::StopOnLetter);
+
+    type_text(&mut session, "x");
+    assert!(!session.is_running());
+
+    type_text(&mut session, "axb");
+    assert_eq!(session.get_input(), "ab");
+
+    let mut session = TypingSession::new(text.clone(), TypingDuration::FifteenSec)
+      .with_difficulty(Difficulty::StopOnWord);
+
+    type_text(&mut session, "ax ");
+    assert_eq!(session.get_input(), "ax");
+
+    session.press(Keystroke::Backspace);
+    type_text(&mut session, "b ");
+    assert_eq!(session.get_input(), "ab ");
+
+    let mut session =
+      TypingSession::new(text, TypingDuration::FifteenSec).with_difficulty(Difficulty::SuddenDeath);
+
+    type_text(&mut session, "ab");
+    assert!(!session.is_failed());
+
+    type_text(&mut session, "x");
+    assert!(session.is_failed());
+    assert_eq!(session.get_remaining_time(), 0);
+
+    let stat = session.finish(String::from("en"), false);
+    assert_eq!(stat.get_difficulty(), Difficulty::SuddenDeath);
+
+    // Failed run is scored over the typed seconds, not the whole duration
+    assert_eq!(stat.get_practice_secs(), 1);
+    assert_eq!(stat.get_average_wpm(), 24);
+    assert_eq!(stat.get_raw_wpm(), 36);
+  }
+
+  #[test]
+  fn session_counts_key_presses() {
+    let mut session = TypingSession::new(String::from("ab cd "), TypingDuration::FifteenSec)
+      .with_difficulty(Difficulty::StopOnLetter);
+
+    // Rejected mistake is counted into the expected key
+    type_text(&mut session, "axb c");
+
+    let key_counters = session.get_key_counters();
+    assert_eq!(
+      key_counters.get(&'b'),
+      Some(&KeyCounter {
+        presses: 2,
+        mistakes: 1
+      })
+    );
+    assert_eq!(key_counters.get(&'a').unwrap().mistakes, 0);
+    assert_eq!(key_counters.get(&' ').unwrap().presses, 1);
+    assert_eq!(key_counters.get(&'d'), None);
+  }
+
+  #[test]
+  fn zen_session_measures_free_text() {
+    let mut session = ZenSession::new();
+
+    session.press(Keystroke::Backspace);
+    assert!(!session.is_running());
+
+    for c in "free typing wrds".chars() {
+      session.press(Keystroke::Char(c));
+    }
+
+    session.press(Keystroke::DeleteWord);
+    session.tick();
+
+    for c in "words".chars() {
+      session.press(Keystroke::Char(c));
+    }
+
+    session.tick();
+
+    assert_eq!(session.get_input(), "free typing words");
+    assert_eq!(session.get_words_count(), 3);
+
+    let stat = session.finish(String::from("en"));
+    assert!(!session.is_running());
+
+    assert_eq!(stat.get_mode(), RunMode::Zen);
+    assert_eq!(stat.get_practice_secs(), 2);
+    assert_eq!(stat.get_average_wpm(), 102);
+    assert_eq!(stat.get_metrics().unwrap().keystrokes, 22);
+  }
+
+  #[test]
+  fn drill_session_measures_repetitions() {
+    let mut session = DrillSession::new(String::from("abcde"), 2);
+
+    for (i, c) in "abxcde".chars().enumerate() {
+      session.press_at(Keystroke::Char(c), i as u32 * 200);
+    }
+
+    assert_eq!(session.get_input(), "abxcde");
+
+    session.press_at(Keystroke::DeleteWord, 1200);
+
+    for (i, c) in "abcde".chars().enumerate() {
+      session.press_at(Keystroke::Char(c), 1400 + i as u32 * 200);
+    }
+
+    // 5 characters in 2.2 seconds, 4 of 11 typed characters mistaken
+    assert_eq!(session.get_repetitions().len(), 1);
+    assert_eq!(session.get_repetitions()[0].wpm, 27);
+    assert_eq!(session.get_repetitions()[0].accuracy, 63.64);
+
+    // Next repetition is measured from its own first keystroke (not the pause before it)
+    for (i, c) in "abcde".chars().enumerate() {
+      session.press_at(Keystroke::Char(c), 5000 + i as u32 * 100);
+    }
+
+    assert!(session.is_completed());
+
+    let result = session.get_result();
+    assert_eq!(result.repetitions[1].wpm, 150);
+    assert_eq!(result.repetitions[1].accuracy, 100.0);
+    assert_eq!(result.get_wpm_improvement(), 123);
+
+    // Deleting a word keeps the previous words of the phrase
+    let mut session = DrillSession::new(String::from("ab cd"), 1);
+
+    for c in "ab cx".chars() {
+      session.press_at(Keystroke::Char(c), 0);
+    }
+
+    session.press_at(Keystroke::DeleteWord, 0);
+    assert_eq!(session.get_input(), "ab ");
+  }
+}
@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{collections::VecDeque, future::Future, time::Duration};
 
 use anyhow::{Result, anyhow};
 use ratatui::crossterm::event::{Event, EventStream, KeyEvent};
@@ -13,11 +13,31 @@ use futures::{FutureExt, StreamExt};
 /// Represents events generated by the Tukai application.
 ///
 /// - `Tick`: A periodic timer event used for time remainder on the typing screen.
-/// - `Key`: An event representing a keyboard input, resolution change, wrapping a [`KeyEvent`].
-#[derive(Clone, Copy, Debug)]
+/// - `Frame`: A frequent timer event used for redrawing animated screens.
+/// - `Key`: An event representing a keyboard input, wrapping a [`KeyEvent`].
+/// - `Resize`: The terminal was resized, the screen is redrawn immediately.
+/// - `FocusLost`, `FocusGained`: The terminal lost or gained the focus.
+/// - `Paste`: Text pasted into the terminal (bracketed paste).
+#[derive(Clone, Debug)]
 pub enum TukaiEvent {
   Tick,
+  Frame,
   Key(KeyEvent),
+  Resize,
+  FocusLost,
+  FocusGained,
+  Paste(String),
+}
+
+/// Source of the events driving the application
+///
+/// The terminal events come from the [`EventHandler`],
+/// the tests drive the application with the [`ScriptedEvents`].
+pub trait EventSource {
+  /// Waits for the next `TukaiEvent`
+  ///
+  /// Fails if there are no more events.
+  fn next(&mut self) -> impl Future<Output = Result<TukaiEvent>>;
 }
 
 /// Handles sending and receiving `TukaiEvent`s asynchronously.
@@ -32,14 +52,22 @@ pub struct EventHandler {
   rx: mpsc::UnboundedReceiver<TukaiEvent>,
 }
 
+impl Default for EventHandler {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
 impl EventHandler {
   /// Spawns a background asynchronous task that:
-  /// - Listens for terminal input events and forwards keyboard events as `TukaiEvent::Key`.
+  /// - Listens for terminal input events and forwards the keyboard, resize, focus and paste events.
   /// - Sends periodic `TukaiEvent::Tick` events every second.
+  /// - Sends periodic `TukaiEvent::Frame` events every 100 milliseconds.
   ///
   /// The event loop uses Tokio’s async runtime and crossterm’s `EventStream` to handle input.
   pub fn new() -> Self {
     let tick_rate = Duration::from_secs(1);
+    let frame_rate = Duration::from_millis(100);
     let (_tx, rx) = mpsc::unbounded_channel::<TukaiEvent>();
 
     let tx_clone = _tx.clone();
@@ -47,39 +75,57 @@ impl EventHandler {
     tokio::spawn(async move {
       let mut reader = EventStream::new();
       let mut interval = tokio::time::interval(tick_rate);
+      let mut frame_interval = tokio::time::interval(frame_rate);
 
       loop {
         let tick_delay = interval.tick();
+        let frame_delay = frame_interval.tick();
         let crossterm_event = reader.next().fuse();
 
         tokio::select! {
-          Some(Ok(Event::Key(key_event))) = crossterm_event => {
-            // On Windows terminal takes press and release
-            // To avoid symbols duplications checks a event kind
-            #[cfg(target_os = "windows")]
-            {
-              if key_event.kind != KeyEventKind::Press {
-                continue
+          Some(Ok(event)) = crossterm_event => {
+            let tukai_event = match event {
+              Event::Key(key_event) => {
+                // On Windows terminal takes press and release
+                // To avoid symbols duplications checks a event kind
+                #[cfg(target_os = "windows")]
+                {
+                  if key_event.kind != KeyEventKind::Press {
+                    continue
+                  }
+                }
+                TukaiEvent::Key(key_event)
               }
-            }
-            tx_clone.send(TukaiEvent::Key(key_event)).unwrap();
+              Event::Resize(_, _) => TukaiEvent::Resize,
+              Event::FocusLost => TukaiEvent::FocusLost,
+              Event::FocusGained => TukaiEvent::FocusGained,
+              Event::Paste(text) => TukaiEvent::Paste(text),
+              Event::Mouse(_) => continue,
+            };
+
+            tx_clone.send(tukai_event).unwrap();
           },
           _ = tick_delay => {
             tx_clone.send(TukaiEvent::Tick).unwrap();
           },
+          _ = frame_delay => {
+            tx_clone.send(TukaiEvent::Frame).unwrap();
+          },
         }
       }
     });
 
     Self { _tx, rx }
   }
+}
 
+impl EventSource for EventHandler {
   /// Asynchronously received the next `TukaiEvent` from the event channel.
   ///
   /// # Returns
   /// A [`Result`] containing the next `TukaiEvent` on success,
   /// or an error if the event stream has been closed.
-  pub async fn next(&mut self) -> Result<TukaiEvent> {
+  async fn next(&mut self) -> Result<TukaiEvent> {
     self
       .rx
       .recv()
@@ -87,3 +133,28 @@ impl EventHandler {
       .ok_or_else(|| anyhow!("Some IO error occurred"))
   }
 }
+
+/// Scripted sequence of the events (e.g. a session in the tests)
+///
+/// Ends with an error after the last event,
+/// so the script should exit the application (`ctrl-c`).
+pub struct ScriptedEvents {
+  events: VecDeque<TukaiEvent>,
+}
+
+impl ScriptedEvents {
+  pub fn new<I: IntoIterator<Item = TukaiEvent>>(events: I) -> Self {
+    Self {
+      events: events.into_iter().collect(),
+    }
+  }
+}
+
+impl EventSource for ScriptedEvents {
+  async fn next(&mut self) -> Result<TukaiEvent> {
+    self
+      .events
+      .pop_front()
+      .ok_or_else(|| anyhow!("No scripted events left"))
+  }
+}
@@ -1,43 +1,83 @@
 use std::{
-  fs::{File, OpenOptions, create_dir_all},
+  ffi::OsString,
+  fs::{File, OpenOptions, create_dir_all, rename},
   io::{Read, Write},
-  path::Path,
+  path::{Path, PathBuf},
 };
 
-use anyhow::{Error, Result};
+use anyhow::Result;
 
 pub struct FileHandler;
 
 impl FileHandler {
-  /// Opens a file for reading and writing, creating it if it does not exist.
+  /// Returns a sibling path of the file with an appended extension.
   ///
-  /// If the file at the specified path exists, it is opened with read/write access.
-  /// If it does not exist, a new file is created.
+  /// i.e. `tukai.bin` with `bak` results in `tukai.bin.bak`
+  fn get_sibling_path<P: AsRef<Path>>(path: P, extension: &str) -> PathBuf {
+    let mut file_name = OsString::from(path.as_ref().as_os_str());
+    file_name.push(".");
+    file_name.push(extension);
+    PathBuf::from(file_name)
+  }
+
+  /// Returns the path of the temporary file used while writing.
+  pub fn get_temp_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    FileHandler::get_sibling_path(path, "tmp")
+  }
+
+  /// Returns the path of the backup file (previous version of the file).
+  pub fn get_backup_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    FileHandler::get_sibling_path(path, "bak")
+  }
+
+  /// Returns a free path to keep an unreadable file aside.
   ///
-  /// # Parameters
-  /// - `path`: A path to the file to open or create.
+  /// i.e. `tukai.bin.corrupt`, or `tukai.bin.corrupt.1` if the former already exists
+  pub fn get_corrupt_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    let mut corrupt_path = FileHandler::get_sibling_path(&path, "corrupt");
+    let mut index = 0;
+
+    while corrupt_path.exists() {
+      index += 1;
+      corrupt_path = FileHandler::get_sibling_path(&path, &format!("corrupt.{index}"));
+    }
+
+    corrupt_path
+  }
+
+  /// Moves the file aside (see [`FileHandler::get_corrupt_path`]), if it exists.
   ///
-  /// # Returns
-  /// A [`Result`] containing the opened [`File`] on success, or an error on failure.
-  fn open_file<P: AsRef<Path>>(path: P) -> Result<File> {
-    let path_buf = path.as_ref().to_path_buf();
+  /// The file is never overwritten or rotated into the backup afterwards.
+  pub fn move_aside<P: AsRef<Path>>(path: P) -> Result<()> {
+    let path = path.as_ref();
 
-    if let Some(parent_dir) = path_buf.parent() {
-      create_dir_all(parent_dir)?;
+    if path.exists() {
+      rename(path, FileHandler::get_corrupt_path(path))?;
     }
 
-    OpenOptions::new()
-      .read(true)
-      .write(true)
-      .create(true)
-      .open(path)
-      .map_err(Error::from)
+    Ok(())
+  }
+
+  /// Syncs the directory entries, so renames survive a crash.
+  ///
+  /// Directories can't be opened as files on Windows, so it's a no-op there.
+  fn sync_dir<P: AsRef<Path>>(dir: P) -> Result<()> {
+    #[cfg(unix)]
+    File::open(dir)?.sync_all()?;
+
+    #[cfg(not(unix))]
+    let _ = dir;
+
+    Ok(())
   }
 
   /// Writes the given bytes into a file at the specified path.
   ///
-  /// This function opens the file (creating it if it doesn’t exist),
-  /// writes all the provided bytes to it, and then closes the file.
+  /// The bytes are written into a temporary file first, which is synced to the disk
+  /// and then renamed over the original file. The previous version of the file is kept
+  /// as a backup (see [`FileHandler::get_backup_path`]).
+  ///
+  /// A crash in the middle of the write never leaves a partially written file.
   ///
   /// # Parameters
   /// - `path`: The path to the file where the bytes will be written.
@@ -45,10 +85,38 @@ impl FileHandler {
   ///
   /// # Returns
   /// A [`Result`] which is [`Ok`] if the operation succeeds,
-  /// or an error if opening or writing to the file fails.
+  /// or an error if writing or renaming the file fails.
   pub fn write_bytes_into_file<P: AsRef<Path>>(path: P, bytes: &[u8]) -> Result<()> {
-    let mut file = FileHandler::open_file(path)?;
-    file.write_all(bytes)?;
+    let path = path.as_ref();
+
+    if let Some(parent_dir) = path.parent() {
+      create_dir_all(parent_dir)?;
+    }
+
+    let temp_path = FileHandler::get_temp_path(path);
+
+    {
+      let mut temp_file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&temp_path)?;
+
+      temp_file.write_all(bytes)?;
+      temp_file.sync_all()?;
+    }
+
+    // Rotates the current file into the backup
+    if path.exists() {
+      rename(path, FileHandler::get_backup_path(path))?;
+    }
+
+    rename(&temp_path, path)?;
+
+    if let Some(parent_dir) = path.parent() {
+      FileHandler::sync_dir(parent_dir)?;
+    }
+
     Ok(())
   }
 
@@ -65,8 +133,19 @@ impl FileHandler {
   /// or an error if the file could not be opened or read.
   pub fn read_bytes_from_file<P: AsRef<Path>>(path: P) -> Result<Vec<u8>> {
     let mut bytes_buf = Vec::new();
-    let mut file = FileHandler::open_file(path)?;
+    let mut file = File::open(path)?;
     file.read_to_end(&mut bytes_buf)?;
     Ok(bytes_buf)
   }
+
+  /// Reads at most `count` first bytes from the file at the specified path.
+  ///
+  /// Used to read a header without reading the whole file.
+  pub fn read_first_bytes_from_file<P: AsRef<Path>>(path: P, count: usize) -> Result<Vec<u8>> {
+    let mut bytes_buf = Vec::new();
+    File::open(path)?
+      .take(count as u64)
+      .read_to_end(&mut bytes_buf)?;
+    Ok(bytes_buf)
+  }
 }
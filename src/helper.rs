@@ -1,8 +1,12 @@
-use std::iter::repeat_n;
-
-use crate::config::TukaiConfig;
+use crate::{config::TukaiConfig, storage::lessons::Lesson};
 use rand::{Rng, seq::SliceRandom};
 
+/// Count of the words of a lesson exercise
+const LESSON_EXERCISE_WORDS_COUNT: usize = 20;
+
+/// Dictionary words typed by the unlocked keys needed to build an exercise from the words only
+const LESSON_MIN_WORDS_COUNT: usize = 10;
+
 pub struct Generator;
 
 impl Generator {
@@ -30,35 +34,71 @@ impl Generator {
   /// # Returns
   /// A `String` composed of randomly selected words.
   pub fn generate_random_string(config: &TukaiConfig) -> String {
-    let mut rng = rand::thread_rng();
-
     Generator::get_words(config)
-      .choose_multiple(&mut rng, config.typing_duration.as_seconds() * 2)
+      .choose_multiple(
+        &mut *config.get_rng_mut(),
+        config.typing_duration.as_seconds() * 2,
+      )
       .fold(String::new(), |mut acc, c| {
         acc.push_str(format!("{c} ").as_str());
         acc
       })
   }
 
-  /// Generates a repeated word string based on the provided configuration.
-  ///
-  /// The word and the number of repetitions are taken from the [`TukaiConfig`] instance.
-  /// This is useful for producing visual effects like emphasis or animation in text-based UIs.
+  /// Generates a random word to drill based on the provided configuration.
   ///
   /// # Parameters
   /// - `config`: A reference to a [`TukaiConfig`] instance containing configuration options,
   ///   including the language to use.
   ///
   /// # Returns
-  /// A `String` composed of randomly selected multiple times repeated word.
-  pub fn generate_repeated_word(config: &TukaiConfig) -> String {
-    let mut rng = rand::thread_rng();
-    let word = Generator::get_words(config)
-      .choose(&mut rng)
+  /// A randomly selected word (`Hello` if the word list is empty).
+  pub fn generate_drill_word(config: &TukaiConfig) -> String {
+    Generator::get_words(config)
+      .choose(&mut *config.get_rng_mut())
       .cloned()
-      .unwrap_or(String::from("Hello"));
+      .unwrap_or(String::from("Hello"))
+  }
 
-    repeat_n(word, 50).collect::<Vec<String>>().join(" ")
+  /// Generates an exercise of the lesson restricted to its unlocked keys
+  /// of the selected keyboard layout.
+  ///
+  /// Words of the current dictionary typed by the unlocked keys are mixed
+  /// with groups of the keys introduced by the lesson (e.g. numbers and symbols
+  /// missing in the dictionary words).
+  ///
+  /// # Returns
+  /// A `String` composed of the exercise words.
+  pub fn generate_lesson_exercise(config: &TukaiConfig, lesson: Lesson) -> String {
+    let unlocked_keys = lesson.get_unlocked_keys(config.keyboard_layout);
+    let new_keys = lesson
+      .get_new_keys(config.keyboard_layout)
+      .chars()
+      .collect::<Vec<char>>();
+
+    let words = Generator::get_words(config)
+      .into_iter()
+      .filter(|word| word.chars().all(|c| unlocked_keys.contains(c)))
+      .collect::<Vec<String>>();
+
+    let mut rng = config.get_rng_mut();
+
+    (0..LESSON_EXERCISE_WORDS_COUNT)
+      .map(|_| {
+        if words.len() >= LESSON_MIN_WORDS_COUNT && rng.gen_ratio(2, 3) {
+          return words.choose(&mut *rng).cloned().unwrap_or_default();
+        }
+
+        let keys_group_len = rng.gen_range(2..=4);
+
+        (0..keys_group_len)
+          .filter_map(|_| new_keys.choose(&mut *rng))
+          .collect::<String>()
+      })
+      .fold(String::new(), |mut acc, word| {
+        acc.push_str(format!("{word} ").as_str());
+        acc
+      })
   }
 
   /// Generates and returns a random motto string.
@@ -66,7 +106,7 @@ impl Generator {
   /// This could be used, for example, in a screen footer.
   /// # Returns
   /// A random motto `String` selected from a predefined list of mottos.
-  pub fn generate_random_motto() -> String {
+  pub fn generate_random_motto(config: &TukaiConfig) -> String {
     let mottos = [
       " Practice today, master tomorrow ",
       " Fingers on keys, progress with ease ",
@@ -75,9 +115,7 @@ impl Generator {
       " Precision today, perfection tomorrow ",
     ];
 
-    let mut rng = rand::thread_rng();
-
-    let random_index = rng.gen_range(0..mottos.len());
+    let random_index = config.get_rng_mut().gen_range(0..mottos.len());
 
     String::from(mottos[random_index])
   }
@@ -0,0 +1,298 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// Row of the letter keys of the keyboard
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum KeyRow {
+  Top,
+  Home,
+  Bottom,
+}
+
+impl KeyRow {
+  fn get_index(&self) -> usize {
+    match self {
+      KeyRow::Top => 0,
+      KeyRow::Home => 1,
+      KeyRow::Bottom => 2,
+    }
+  }
+}
+
+/// Keys of the number row (the same in all the layouts)
+pub const NUMBER_ROW_KEYS: &str = "1234567890";
+
+/// Finger pressing the key by the touch typing
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Finger {
+  LeftPinky,
+  LeftRing,
+  LeftMiddle,
+  LeftIndex,
+  RightIndex,
+  RightMiddle,
+  RightRing,
+  RightPinky,
+  Thumb,
+}
+
+impl Finger {
+  /// Returns all the fingers from the left to the right
+  pub fn all() -> &'static [Finger] {
+    use Finger::*;
+
+    &[
+      LeftPinky,
+      LeftRing,
+      LeftMiddle,
+      LeftIndex,
+      RightIndex,
+      RightMiddle,
+      RightRing,
+      RightPinky,
+      Thumb,
+    ]
+  }
+
+  /// Returns the finger pressing the keys of the column
+  ///
+  /// Index fingers press two columns each.
+  fn from_column(column: usize) -> Self {
+    use Finger::*;
+
+    match column {
+      0 => LeftPinky,
+      1 => LeftRing,
+      2 => LeftMiddle,
+      3 | 4 => LeftIndex,
+      5 | 6 => RightIndex,
+      7 => RightMiddle,
+      8 => RightRing,
+      _ => RightPinky,
+    }
+  }
+}
+
+impl Display for Finger {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let display_text = match self {
+      Finger::LeftPinky => "Left pinky",
+      Finger::LeftRing => "Left ring",
+      Finger::LeftMiddle => "Left middle",
+      Finger::LeftIndex => "Left index",
+      Finger::RightIndex => "Right index",
+      Finger::RightMiddle => "Right middle",
+      Finger::RightRing => "Right ring",
+      Finger::RightPinky => "Right pinky",
+      Finger::Thumb => "Thumb",
+    };
+
+    write!(f, "{display_text}")
+  }
+}
+
+/// Physical keyboard layout
+///
+/// Switchable with a `ctrl-y` shortcut
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum KeyboardLayout {
+  #[default]
+  Qwerty,
+  Dvorak,
+  Colemak,
+  Azerty,
+  Qwertz,
+}
+
+impl KeyboardLayout {
+  /// Returns the next keyboard layout (used for a switching)
+  pub fn next(&self) -> Self {
+    use KeyboardLayout::*;
+
+    match self {
+      Qwerty => Dvorak,
+      Dvorak => Colemak,
+      Colemak => Azerty,
+      Azerty => Qwertz,
+      Qwertz => Qwerty,
+    }
+  }
+
+  /// Returns the keys of the letter rows (top, home and bottom)
+  ///
+  /// Every row has 10 keys ordered by the physical columns.
+  fn get_rows(&self) -> [&'static str; 3] {
+    use KeyboardLayout::*;
+
+    match self {
+      Qwerty => ["qwertyuiop", "asdfghjkl;", "zxcvbnm,./"],
+      Dvorak => ["',.pyfgcrl", "aoeuidhtns", ";qjkxbmwvz"],
+      Colemak => ["qwfpgjluy;", "arstdhneio", "zxcvbkm,./"],
+      Azerty => ["azertyuiop", "qsdfghjklm", "wxcvbn,;:!"],
+      Qwertz => ["qwertzuiop", "asdfghjklö", "yxcvbnm,.-"],
+    }
+  }
+
+  /// Returns the keys of the letter rows typed with the shift
+  fn get_shifted_rows(&self) -> [&'static str; 3] {
+    use KeyboardLayout::*;
+
+    match self {
+      Qwerty => ["QWERTYUIOP", "ASDFGHJKL:", "ZXCVBNM<>?"],
+      Dvorak => ["\"<>PYFGCRL", "AOEUIDHTNS", ":QJKXBMWVZ"],
+      Colemak => ["QWFPGJLUY:", "ARSTDHNEIO", "ZXCVBKM<>?"],
+      Azerty => ["AZERTYUIOP", "QSDFGHJKLM", "WXCVBN?./§"],
+      Qwertz => ["QWERTZUIOP", "ASDFGHJKLÖ", "YXCVBNM;:_"],
+    }
+  }
+
+  /// Returns the keys of the row (ordered by the physical columns)
+  pub fn get_row_keys(&self, row: KeyRow) -> &'static str {
+    self.get_rows()[row.get_index()]
+  }
+
+  /// Returns the letters of the row
+  pub fn get_row_letters(&self, row: KeyRow) -> String {
+    self.get_rows()[row.get_index()]
+      .chars()
+      .filter(|c| c.is_alphabetic())
+      .collect()
+  }
+
+  /// Returns the physical position (row, column and shift) of the character
+  fn get_key_position(&self, c: char) -> Option<(usize, usize, bool)> {
+    [(self.get_rows(), false), (self.get_shifted_rows(), true)]
+      .into_iter()
+      .find_map(|(rows, is_shifted)| {
+        rows.iter().enumerate().find_map(|(row, keys)| {
+          keys
+            .chars()
+            .position(|key| key == c)
+            .map(|column| (row, column, is_shifted))
+        })
+      })
+  }
+
+  /// Returns the key (not shifted character) of the character
+  ///
+  /// Used to count the shifted characters into their keys.
+  /// Returns None for the characters outside of the keyboard model.
+  pub fn get_key(&self, c: char) -> Option<char> {
+    if c == ' ' || NUMBER_ROW_KEYS.contains(c) {
+      return Some(c);
+    }
+
+    self
+      .get_key_position(c)
+      .and_then(|(row, column, _)| self.get_rows()[row].chars().nth(column))
+  }
+
+  /// Returns the finger pressing the character
+  pub fn get_finger(&self, c: char) -> Option<Finger> {
+    if c == ' ' {
+      return Some(Finger::Thumb);
+    }
+
+    if let Some(column) = NUMBER_ROW_KEYS.chars().position(|key| key == c) {
+      return Some(Finger::from_column(column));
+    }
+
+    self
+      .get_key_position(c)
+      .map(|(_, column, _)| Finger::from_column(column))
+  }
+
+  /// Translates the character typed on a QWERTY keyboard
+  /// into the character of the same physical key of the layout.
+  ///
+  /// Used to practise the layout on a QWERTY configured system.
+  /// Keys outside of the letter rows (e.g. numbers) are not translated.
+  pub fn remap(&self, c: char) -> char {
+    let Some((row, column, is_shifted)) = KeyboardLayout::Qwerty.get_key_position(c) else {
+      return c;
+    };
+
+    let rows = if is_shifted {
+      self.get_shifted_rows()
+    } else {
+      self.get_rows()
+    };
+
+    rows[row].chars().nth(column).unwrap_or(c)
+  }
+}
+
+impl Display for KeyboardLayout {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let display_text = match self {
+      KeyboardLayout::Qwerty => "qwerty",
+      KeyboardLayout::Dvorak => "dvorak",
+      KeyboardLayout::Colemak => "colemak",
+      KeyboardLayout::Azerty => "azerty",
+      KeyboardLayout::Qwertz => "qwertz",
+    };
+
+    write!(f, "{display_text}")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn remap_qwerty_keys() {
+    let remap = |layout: KeyboardLayout, text: &str| -> String {
+      text.chars().map(|c| layout.remap(c)).collect()
+    };
+
+    assert_eq!(remap(KeyboardLayout::Qwerty, "Hello; 42"), "Hello; 42");
+    assert_eq!(remap(KeyboardLayout::Colemak, "Hello; 42"), "Hfiiyo 42");
+    assert_eq!(remap(KeyboardLayout::Dvorak, "jdpps"), "hello");
+    assert_eq!(remap(KeyboardLayout::Azerty, "qw;"), "azm");
+
+    assert_eq!(
+      KeyboardLayout::Colemak.get_row_letters(KeyRow::Home),
+      "arstdhneio"
+    );
+    assert_eq!(
+      KeyboardLayout::Dvorak.get_row_letters(KeyRow::Top),
+      "pyfgcrl"
+    );
+  }
+
+  #[test]
+  fn fingers_of_keys() {
+    assert_eq!(
+      KeyboardLayout::Qwerty.get_finger('f'),
+      Some(Finger::LeftIndex)
+    );
+    assert_eq!(
+      KeyboardLayout::Qwerty.get_finger('G'),
+      Some(Finger::LeftIndex)
+    );
+    assert_eq!(
+      KeyboardLayout::Qwerty.get_finger('?'),
+      Some(Finger::RightPinky)
+    );
+    assert_eq!(
+      KeyboardLayout::Colemak.get_finger('t'),
+      Some(Finger::LeftIndex)
+    );
+    assert_eq!(
+      KeyboardLayout::Dvorak.get_finger('o'),
+      Some(Finger::LeftRing)
+    );
+    assert_eq!(
+      KeyboardLayout::Azerty.get_finger('7'),
+      Some(Finger::RightIndex)
+    );
+    assert_eq!(KeyboardLayout::Qwerty.get_finger(' '), Some(Finger::Thumb));
+    assert_eq!(KeyboardLayout::Qwerty.get_finger('é'), None);
+
+    assert_eq!(KeyboardLayout::Qwerty.get_key(':'), Some(';'));
+    assert_eq!(KeyboardLayout::Dvorak.get_key('<'), Some(','));
+    assert_eq!(KeyboardLayout::Qwertz.get_key('Ö'), Some('ö'));
+  }
+}
@@ -0,0 +1,30 @@
+//! Terminal-based touch typing application.
+//!
+//! Besides the terminal app, the crate exposes the UI independent typing engine
+//! ([`engine::TypingSession`]) and the storage API ([`storage::storage_handler::StorageHandler`]).
+//!
+//! ```
+//! use tukai::{config::TypingDuration, engine::TypingSession, storage::keystroke_log::Keystroke};
+//!
+//! let mut session = TypingSession::new(String::from("hello "), TypingDuration::FifteenSec);
+//!
+//! for c in "hello ".chars() {
+//!   session.press(Keystroke::Char(c));
+//! }
+//!
+//! let stat = session.finish(String::from("en"), false);
+//! assert_eq!(stat.get_accuracy(), 100.0);
+//! ```
+
+pub mod app;
+pub mod cli;
+pub mod config;
+pub mod engine;
+pub mod event_handler;
+pub mod file_handler;
+pub mod helper;
+pub mod keyboard;
+pub mod profile;
+pub mod race;
+pub mod screens;
+pub mod storage;
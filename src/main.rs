@@ -1,31 +1,87 @@
-mod app;
-mod config;
-mod file_handler;
-
-mod event_handler;
-mod helper;
-mod screens;
-mod storage;
+use std::io::stdout;
 
 use anyhow::Result;
-use app::Tukai;
-use config::TukaiConfigBuilder;
-use event_handler::EventHandler;
+use crossterm::{
+  event::{DisableBracketedPaste, DisableFocusChange, EnableBracketedPaste, EnableFocusChange},
+  execute,
+};
+use tukai::{
+  app::Tukai,
+  cli::{CliArgs, USAGE},
+  config::TukaiConfigBuilder,
+  event_handler::EventHandler,
+  profile::ProfileHandler,
+  screens::ActiveScreenEnum,
+};
+
+/// Restores the terminal, disables the paste and focus reporting
+fn restore_terminal() {
+  let _ = execute!(stdout(), DisableBracketedPaste, DisableFocusChange);
+  ratatui::restore();
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
+  let cli_args = CliArgs::parse()?;
+
+  if cli_args.show_help {
+    println!("{USAGE}");
+    return Ok(());
+  }
+
+  if cli_args.show_version {
+    println!("tukai {}", env!("CARGO_PKG_VERSION"));
+    return Ok(());
+  }
+
+  // Lets the user pick a profile if there are more of them
+  // and none was selected with the `--profile` flag
+  let has_profile_picker = cli_args.profile_name.is_none()
+    && cli_args.race_role.is_none()
+    && ProfileHandler::get_profile_names().len() > 1;
+
+  let mut app_config_builder = TukaiConfigBuilder::new();
+
+  if let Some(profile_name) = cli_args.profile_name {
+    app_config_builder = app_config_builder.profile_name(profile_name);
+  }
+
+  let app_config = app_config_builder.build();
+
   let mut terminal = ratatui::init();
-  let mut event_handler = EventHandler::new();
 
-  let app_config = TukaiConfigBuilder::new().build();
+  // Not supported by all the terminals
+  let _ = execute!(stdout(), EnableBracketedPaste, EnableFocusChange);
+  let mut event_handler = EventHandler::new();
 
   terminal.clear()?;
 
-  let app_result = Tukai::try_new(&mut event_handler, app_config)?
-    .run(&mut terminal)
-    .await;
+  // Storage errors (e.g. a file of a newer version) are shown in the restored terminal
+  let mut app = match Tukai::try_new(&mut event_handler, app_config) {
+    Ok(app) => app,
+    Err(error) => {
+      restore_terminal();
+      return Err(error);
+    }
+  };
 
-  ratatui::restore();
+  if has_profile_picker {
+    app = app.initial_screen(ActiveScreenEnum::Profiles);
+  }
+
+  if let Some(race_role) = cli_args.race_role {
+    app = match app.race(race_role, cli_args.player_name) {
+      Ok(app) => app,
+      Err(error) => {
+        restore_terminal();
+        return Err(error);
+      }
+    };
+  }
+
+  let app_result = app.run(&mut terminal).await;
+
+  restore_terminal();
 
   app_result
 }
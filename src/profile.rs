@@ -0,0 +1,69 @@
+use std::{fs::read_dir, path::PathBuf};
+
+use crate::storage::storage_handler::StorageHandler;
+
+/// Name of the profile used when no profile is selected
+pub const DEFAULT_PROFILE_NAME: &str = "default";
+
+/// Maximal length of the profile name
+const PROFILE_NAME_MAX_LEN: usize = 32;
+
+/// Folder (inside the app local directory) containing named profiles
+const PROFILES_DIR_NAME: &str = "profiles";
+
+/// Helper for the user profiles.
+///
+/// Every profile has its own storage file (stats and preferences).
+/// The default profile uses the original `tukai.bin` file,
+/// named profiles are stored in the `profiles/{name}/tukai.bin`.
+pub struct ProfileHandler;
+
+impl ProfileHandler {
+  /// Returns the storage file path of the profile
+  ///
+  /// Path is relative to the app local directory (see `StorageHandler::new`).
+  pub fn get_storage_file_path(profile_name: &str) -> PathBuf {
+    if profile_name == DEFAULT_PROFILE_NAME {
+      PathBuf::from("tukai.bin")
+    } else {
+      PathBuf::from(PROFILES_DIR_NAME)
+        .join(profile_name)
+        .join("tukai.bin")
+    }
+  }
+
+  /// Validates the profile name
+  ///
+  /// Allowed are only alphanumeric characters, `-` and `_`,
+  /// so the name is always a valid folder name.
+  pub fn is_valid_name(profile_name: &str) -> bool {
+    !profile_name.is_empty()
+      && profile_name.chars().count() <= PROFILE_NAME_MAX_LEN
+      && profile_name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+  }
+
+  /// Returns names of all existing profiles
+  ///
+  /// The default profile is always the first one, others are sorted by name.
+  pub fn get_profile_names() -> Vec<String> {
+    let profiles_dir_path = StorageHandler::get_local_dir_path().join(PROFILES_DIR_NAME);
+
+    let mut profile_names = read_dir(profiles_dir_path)
+      .map(|entries| {
+        entries
+          .filter_map(|entry| entry.ok())
+          .filter(|entry| entry.path().is_dir())
+          .filter_map(|entry| entry.file_name().into_string().ok())
+          .filter(|name| name != DEFAULT_PROFILE_NAME && ProfileHandler::is_valid_name(name))
+          .collect::<Vec<String>>()
+      })
+      .unwrap_or_default();
+
+    profile_names.sort();
+    profile_names.insert(0, String::from(DEFAULT_PROFILE_NAME));
+
+    profile_names
+  }
+}
@@ -0,0 +1,125 @@
+use tokio::{net::TcpStream, sync::mpsc, task::JoinHandle};
+
+use super::protocol::{ClientMessage, ServerMessage, read_message, write_message};
+
+/// Event received from the race lobby
+#[derive(Debug)]
+pub enum RaceEvent {
+  Message(ServerMessage),
+
+  // Connection failed or was closed (with the reason)
+  Disconnected(String),
+}
+
+/// Player's connection to the race lobby
+///
+/// Messages are exchanged in the background,
+/// so the events are polled without blocking the UI.
+pub struct RaceClient {
+  tx: mpsc::UnboundedSender<ClientMessage>,
+
+  rx: mpsc::UnboundedReceiver<RaceEvent>,
+
+  // Connects and reads the messages of the lobby
+  task: JoinHandle<()>,
+}
+
+impl RaceClient {
+  /// Connects to the lobby on the address and joins with the name
+  pub fn connect(addr: String, name: String) -> Self {
+    RaceClient::join(
+      addr,
+      ClientMessage::Join {
+        name,
+        host_token: None,
+      },
+    )
+  }
+
+  /// Connects to the lobby as its host (the token is kept by the `RaceHost`)
+  pub fn connect_as_host(addr: String, name: String, host_token: u64) -> Self {
+    RaceClient::join(
+      addr,
+      ClientMessage::Join {
+        name,
+        host_token: Some(host_token),
+      },
+    )
+  }
+
+  /// Connects to the lobby and sends the join message
+  fn join(addr: String, join_message: ClientMessage) -> Self {
+    let (tx, client_rx) = mpsc::unbounded_channel::<ClientMessage>();
+    let (event_tx, rx) = mpsc::unbounded_channel::<RaceEvent>();
+
+    let task = tokio::spawn(async move {
+      let reason = match RaceClient::run(&addr, join_message, client_rx, &event_tx).await {
+        Ok(()) => String::from("The host closed the lobby"),
+        Err(error) => format!("Disconnected from {addr}: {error}"),
+      };
+
+      let _ = event_tx.send(RaceEvent::Disconnected(reason));
+    });
+
+    Self { tx, rx, task }
+  }
+
+  /// Exchanges the messages with the lobby until the connection is closed
+  async fn run(
+    addr: &str,
+    join_message: ClientMessage,
+    mut client_rx: mpsc::UnboundedReceiver<ClientMessage>,
+    event_tx: &mpsc::UnboundedSender<RaceEvent>,
+  ) -> anyhow::Result<()> {
+    let stream = TcpStream::connect(addr).await?;
+    stream.set_nodelay(true)?;
+
+    let (mut reader, mut writer) = stream.into_split();
+
+    write_message(&mut writer, &join_message).await?;
+
+    // Writing isn't cancel safe, so it has its own task.
+    // The task ends when the client is dropped.
+    tokio::spawn(async move {
+      while let Some(message) = client_rx.recv().await {
+        if write_message(&mut writer, &message).await.is_err() {
+          break;
+        }
+      }
+    });
+
+    loop {
+      match read_message::<_, ServerMessage>(&mut reader).await {
+        Ok(message) => {
+          let _ = event_tx.send(RaceEvent::Message(message));
+        }
+        Err(error) => {
+          // Closed connection ends the stream cleanly
+          let is_closed = error
+            .downcast_ref::<std::io::Error>()
+            .is_some_and(|io_error| io_error.kind() == std::io::ErrorKind::UnexpectedEof);
+
+          return if is_closed { Ok(()) } else { Err(error) };
+        }
+      }
+    }
+  }
+
+  /// Sends the message to the lobby
+  ///
+  /// A failure is reported as the `RaceEvent::Disconnected`.
+  pub fn send(&self, message: ClientMessage) {
+    let _ = self.tx.send(message);
+  }
+
+  /// Returns the next received event without waiting
+  pub fn try_recv(&mut self) -> Option<RaceEvent> {
+    self.rx.try_recv().ok()
+  }
+}
+
+impl Drop for RaceClient {
+  fn drop(&mut self) {
+    self.task.abort();
+  }
+}
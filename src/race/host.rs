@@ -0,0 +1,482 @@
+use std::{
+  net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+  sync::{Arc, Mutex},
+};
+
+use anyhow::{Result, bail};
+use tokio::{
+  net::{TcpListener, TcpStream},
+  sync::mpsc,
+  task::{JoinHandle, JoinSet},
+};
+
+use super::{
+  client::RaceClient,
+  protocol::{ClientMessage, RacePlayer, ServerMessage, read_message, write_message},
+};
+
+/// Id of the player hosting the lobby (the host's own player, see `RaceHost::connect_player`)
+pub const HOST_PLAYER_ID: u32 = 0;
+
+/// Highest WPM accepted from a player, higher reported values are clamped
+const MAX_REPORTED_WPM: usize = 300;
+
+/// Countdown before the race starts in milliseconds
+const RACE_COUNTDOWN_MS: u64 = 3000;
+
+/// Player connected to the lobby
+struct LobbyPlayer {
+  player: RacePlayer,
+
+  // Sends the messages to the player's connection
+  tx: mpsc::UnboundedSender<ServerMessage>,
+}
+
+/// Shared state of the lobby
+struct Lobby {
+  players: Vec<LobbyPlayer>,
+
+  // Secret proving the joining player is the host
+  host_token: u64,
+
+  next_player_id: u32,
+
+  is_racing: bool,
+
+  // Characters of the raced text (the highest valid progress)
+  text_chars_count: usize,
+}
+
+impl Lobby {
+  fn new(host_token: u64) -> Self {
+    Self {
+      players: Vec::new(),
+      host_token,
+      next_player_id: HOST_PLAYER_ID + 1,
+      is_racing: false,
+      text_chars_count: 0,
+    }
+  }
+
+  /// Sends the message to all the players
+  fn broadcast(&self, message: ServerMessage) {
+    for lobby_player in self.players.iter() {
+      let _ = lobby_player.tx.send(message.clone());
+    }
+  }
+
+  /// Sends the current players to all the players
+  fn broadcast_players(&self) {
+    self.broadcast(ServerMessage::Players(self.get_players()));
+  }
+
+  fn get_players(&self) -> Vec<RacePlayer> {
+    self
+      .players
+      .iter()
+      .map(|lobby_player| lobby_player.player.clone())
+      .collect()
+  }
+
+  /// Adds the player into the lobby
+  ///
+  /// The host id is given only to the player with the host token (once at a time).
+  fn join(
+    &mut self,
+    name: String,
+    host_token: Option<u64>,
+    tx: mpsc::UnboundedSender<ServerMessage>,
+  ) -> u32 {
+    let is_host = host_token == Some(self.host_token)
+      && !self
+        .players
+        .iter()
+        .any(|lobby_player| lobby_player.player.id == HOST_PLAYER_ID);
+
+    let player_id = if is_host {
+      HOST_PLAYER_ID
+    } else {
+      let player_id = self.next_player_id;
+      self.next_player_id += 1;
+      player_id
+    };
+
+    let _ = tx.send(ServerMessage::Welcome { player_id, is_host });
+
+    self.players.push(LobbyPlayer {
+      player: RacePlayer::new(player_id, name),
+      tx,
+    });
+
+    self.broadcast_players();
+
+    player_id
+  }
+
+  /// Removes the player from the lobby
+  fn leave(&mut self, player_id: u32) {
+    self
+      .players
+      .retain(|lobby_player| lobby_player.player.id != player_id);
+
+    if !self.finish_race_if_over() {
+      self.broadcast_players();
+    }
+  }
+
+  /// Handles the message of the player
+  fn handle_message(&mut self, player_id: u32, message: ClientMessage) {
+    match message {
+      ClientMessage::Join { .. } => {}
+      ClientMessage::Start {
+        text,
+        language,
+        typing_duration,
+      } => {
+        if player_id != HOST_PLAYER_ID || self.is_racing {
+          return;
+        }
+
+        self.is_racing = true;
+        self.text_chars_count = text.chars().count();
+
+        for lobby_player in self.players.iter_mut() {
+          let player = &mut lobby_player.player;
+          *player = RacePlayer::new(player.id, player.name.clone());
+          player.is_racing = true;
+        }
+
+        self.broadcast_players();
+        self.broadcast(ServerMessage::Countdown {
+          text,
+          language,
+          typing_duration,
+          countdown_ms: RACE_COUNTDOWN_MS,
+        });
+      }
+      ClientMessage::Progress { progress, wpm } => {
+        let text_chars_count = self.text_chars_count;
+
+        if let Some(player) = self.get_racing_player_mut(player_id) {
+          player.progress = progress.min(text_chars_count);
+          player.wpm = wpm.min(MAX_REPORTED_WPM);
+
+          self.broadcast_players();
+        }
+      }
+      ClientMessage::Finish { wpm, accuracy } => {
+        if let Some(player) = self.get_racing_player_mut(player_id) {
+          player.wpm = wpm.min(MAX_REPORTED_WPM);
+          player.accuracy = if accuracy.is_finite() {
+            accuracy.clamp(0.0, 100.0)
+          } else {
+            0.0
+          };
+          player.is_finished = true;
+
+          if !self.finish_race_if_over() {
+            self.broadcast_players();
+          }
+        }
+      }
+    }
+  }
+
+  /// Returns the player racing in the running race
+  fn get_racing_player_mut(&mut self, player_id: u32) -> Option<&mut RacePlayer> {
+    if !self.is_racing {
+      return None;
+    }
+
+    self
+      .players
+      .iter_mut()
+      .map(|lobby_player| &mut lobby_player.player)
+      .find(|player| player.id == player_id && player.is_racing && !player.is_finished)
+  }
+
+  /// Sends the rankings if all the racing players finished
+  ///
+  /// Returns whether the race is over.
+  fn finish_race_if_over(&mut self) -> bool {
+    let mut racing_players = self
+      .get_players()
+      .into_iter()
+      .filter(|player| player.is_racing)
+      .collect::<Vec<RacePlayer>>();
+
+    if !self.is_racing || racing_players.iter().any(|player| !player.is_finished) {
+      return false;
+    }
+
+    self.is_racing = false;
+
+    racing_players.sort_by(|a, b| b.wpm.cmp(&a.wpm).then(b.accuracy.total_cmp(&a.accuracy)));
+
+    self.broadcast(ServerMessage::Results(racing_players));
+
+    true
+  }
+}
+
+/// Lobby of the network race accepting the players over TCP
+///
+/// The lobby is closed (all the players are disconnected) when dropped.
+pub struct RaceHost {
+  local_addr: SocketAddr,
+
+  // Secret given only to the host's own player
+  host_token: u64,
+
+  // Accepts the players, owns the tasks of the connections
+  task: JoinHandle<()>,
+}
+
+impl RaceHost {
+  /// Binds the lobby to the address (e.g. `0.0.0.0:7878`)
+  ///
+  /// Players are accepted in the background.
+  pub fn bind(addr: &str) -> Result<Self> {
+    let std_listener = std::net::TcpListener::bind(addr)?;
+    std_listener.set_nonblocking(true)?;
+
+    let listener = TcpListener::from_std(std_listener)?;
+    let local_addr = listener.local_addr()?;
+
+    let host_token = rand::random::<u64>();
+    let task = tokio::spawn(RaceHost::accept_players(listener, host_token));
+
+    Ok(Self {
+      local_addr,
+      host_token,
+      task,
+    })
+  }
+
+  /// Returns the address the lobby is bound to
+  pub fn get_local_addr(&self) -> SocketAddr {
+    self.local_addr
+  }
+
+  /// Connects the host's own player to the lobby
+  ///
+  /// Only this player gets the host role (starts the races).
+  pub fn connect_player(&self, name: String) -> RaceClient {
+    RaceClient::connect_as_host(self.get_client_addr().to_string(), name, self.host_token)
+  }
+
+  /// Returns the address the host's own player connects to
+  ///
+  /// Lobby bound to all the interfaces (e.g. `0.0.0.0`) is reached through the loopback.
+  pub fn get_client_addr(&self) -> SocketAddr {
+    let mut client_addr = self.local_addr;
+
+    if client_addr.ip().is_unspecified() {
+      let loopback: IpAddr = match client_addr {
+        SocketAddr::V4(_) => Ipv4Addr::LOCALHOST.into(),
+        SocketAddr::V6(_) => Ipv6Addr::LOCALHOST.into(),
+      };
+
+      client_addr.set_ip(loopback);
+    }
+
+    client_addr
+  }
+
+  /// Accepts the players until the lobby is closed
+  async fn accept_players(listener: TcpListener, host_token: u64) {
+    let lobby = Arc::new(Mutex::new(Lobby::new(host_token)));
+
+    // Dropping the set aborts the connections
+    let mut connections = JoinSet::new();
+
+    while let Ok((stream, _)) = listener.accept().await {
+      let _ = stream.set_nodelay(true);
+
+      connections.spawn(RaceHost::handle_connection(stream, Arc::clone(&lobby)));
+
+      // Cleans up the closed connections
+      while connections.try_join_next().is_some() {}
+    }
+  }
+
+  /// Handles the messages of the connected player until it disconnects
+  async fn handle_connection(stream: TcpStream, lobby: Arc<Mutex<Lobby>>) -> Result<()> {
+    let (mut reader, mut writer) = stream.into_split();
+
+    let ClientMessage::Join { name, host_token } = read_message(&mut reader).await? else {
+      bail!("The player has to join first");
+    };
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<ServerMessage>();
+
+    // Writing isn't cancel safe, so it has its own task
+    let writer_task = tokio::spawn(async move {
+      while let Some(message) = rx.recv().await {
+        if write_message(&mut writer, &message).await.is_err() {
+          break;
+        }
+      }
+    });
+
+    let player_id = lobby.lock().unwrap().join(name, host_token, tx);
+
+    while let Ok(message) = read_message::<_, ClientMessage>(&mut reader).await {
+      lobby.lock().unwrap().handle_message(player_id, message);
+    }
+
+    lobby.lock().unwrap().leave(player_id);
+    writer_task.abort();
+
+    Ok(())
+  }
+}
+
+impl Drop for RaceHost {
+  fn drop(&mut self) {
+    self.task.abort();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use super::*;
+  use crate::{
+    config::TypingDuration,
+    race::client::{RaceClient, RaceEvent},
+  };
+
+  /// Waits for the first server message matching the predicate
+  async fn wait_for<F: Fn(&ServerMessage) -> bool>(
+    client: &mut RaceClient,
+    predicate: F,
+  ) -> ServerMessage {
+    let wait = async {
+      loop {
+        match client.try_recv() {
+          Some(RaceEvent::Message(message)) if predicate(&message) => return message,
+          Some(RaceEvent::Disconnected(error)) => panic!("Disconnected: {error}"),
+          Some(_) => {}
+          None => tokio::time::sleep(Duration::from_millis(5)).await,
+        }
+      }
+    };
+
+    tokio::time::timeout(Duration::from_secs(5), wait)
+      .await
+      .expect("The message wasn't received")
+  }
+
+  #[tokio::test]
+  async fn host_player_connects_to_bound_addr() {
+    let host = RaceHost::bind("127.0.0.1:0").unwrap();
+    assert_eq!(host.get_client_addr(), host.get_local_addr());
+
+    let host = RaceHost::bind("0.0.0.0:0").unwrap();
+    assert_eq!(
+      host.get_client_addr(),
+      SocketAddr::new(Ipv4Addr::LOCALHOST.into(), host.get_local_addr().port())
+    );
+  }
+
+  #[tokio::test]
+  async fn race_on_localhost() {
+    let host = RaceHost::bind("127.0.0.1:0").unwrap();
+    let addr = host.get_local_addr().to_string();
+
+    // Player connected before the host's own player doesn't become the host
+    let mut bob = RaceClient::connect(addr.clone(), String::from("bob"));
+    let welcome = wait_for(&mut bob, |m| matches!(m, ServerMessage::Welcome { .. })).await;
+    assert_eq!(
+      welcome,
+      ServerMessage::Welcome {
+        player_id: 1,
+        is_host: false
+      }
+    );
+
+    let mut alice = host.connect_player(String::from("alice"));
+    let welcome = wait_for(&mut alice, |m| matches!(m, ServerMessage::Welcome { .. })).await;
+    assert_eq!(
+      welcome,
+      ServerMessage::Welcome {
+        player_id: HOST_PLAYER_ID,
+        is_host: true
+      }
+    );
+
+    wait_for(
+      &mut alice,
+      |m| matches!(m, ServerMessage::Players(players) if players.len() == 2),
+    )
+    .await;
+
+    // Only the host starts the race
+    bob.send(ClientMessage::Start {
+      text: String::from("ignored "),
+      language: String::from("en"),
+      typing_duration: TypingDuration::FifteenSec,
+    });
+
+    alice.send(ClientMessage::Start {
+      text: String::from("the quick brown fox "),
+      language: String::from("en"),
+      typing_duration: TypingDuration::FifteenSec,
+    });
+
+    for client in [&mut alice, &mut bob] {
+      let countdown = wait_for(client, |m| matches!(m, ServerMessage::Countdown { .. })).await;
+
+      assert!(matches!(
+        countdown,
+        ServerMessage::Countdown { text, .. } if text == "the quick brown fox "
+      ));
+    }
+
+    let is_bob_at = |progress: usize, wpm: usize| {
+      move |m: &ServerMessage| {
+        matches!(m, ServerMessage::Players(players) if players.iter().any(|player| {
+          player.name == "bob" && player.progress == progress && player.wpm == wpm
+        }))
+      }
+    };
+
+    bob.send(ClientMessage::Progress {
+      progress: 9,
+      wpm: 72,
+    });
+    wait_for(&mut alice, is_bob_at(9, 72)).await;
+
+    // Reported values are clamped to the text and the plausible WPM
+    bob.send(ClientMessage::Progress {
+      progress: 1000,
+      wpm: 5000,
+    });
+    wait_for(&mut alice, is_bob_at(20, MAX_REPORTED_WPM)).await;
+
+    bob.send(ClientMessage::Finish {
+      wpm: 72,
+      accuracy: 180.0,
+    });
+    alice.send(ClientMessage::Finish {
+      wpm: 55,
+      accuracy: 100.0,
+    });
+
+    let results = wait_for(&mut bob, |m| matches!(m, ServerMessage::Results(_))).await;
+
+    let ServerMessage::Results(rankings) = results else {
+      unreachable!();
+    };
+
+    let ranked_names = rankings
+      .iter()
+      .map(|player| player.name.as_str())
+      .collect::<Vec<&str>>();
+
+    assert_eq!(ranked_names, vec!["bob", "alice"]);
+    assert_eq!(rankings[0].accuracy, 100.0);
+  }
+}
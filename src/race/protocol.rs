@@ -0,0 +1,171 @@
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::config::TypingDuration;
+
+/// Maximal length of a single message in bytes
+const MAX_MESSAGE_LEN: u32 = 1024 * 1024;
+
+/// Player of the race as seen by all the players
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct RacePlayer {
+  pub id: u32,
+
+  pub name: String,
+
+  // Typed characters count
+  pub progress: usize,
+
+  pub wpm: usize,
+
+  pub accuracy: f64,
+
+  // Player takes part in the current race (late joiners wait for the next one)
+  pub is_racing: bool,
+
+  pub is_finished: bool,
+}
+
+impl RacePlayer {
+  /// Creates a player waiting in the lobby
+  pub fn new(id: u32, name: String) -> Self {
+    Self {
+      id,
+      name,
+      progress: 0,
+      wpm: 0,
+      accuracy: 0.0,
+      is_racing: false,
+      is_finished: false,
+    }
+  }
+}
+
+/// Message sent from a player to the host
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum ClientMessage {
+  // First message of every connection
+  Join {
+    name: String,
+
+    // Secret of the lobby given only to the host's own player (see `RaceHost::connect_player`)
+    host_token: Option<u64>,
+  },
+
+  // Starts the race (accepted only from the host player)
+  Start {
+    text: String,
+    language: String,
+    typing_duration: TypingDuration,
+  },
+
+  Progress {
+    progress: usize,
+    wpm: usize,
+  },
+
+  Finish {
+    wpm: usize,
+    accuracy: f64,
+  },
+}
+
+/// Message sent from the host to the players
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum ServerMessage {
+  // Reply to the join
+  Welcome {
+    player_id: u32,
+    is_host: bool,
+  },
+
+  // Players joined, left or made a progress
+  Players(Vec<RacePlayer>),
+
+  // Race starts after the countdown (same text for all the players)
+  Countdown {
+    text: String,
+    language: String,
+    typing_duration: TypingDuration,
+    countdown_ms: u64,
+  },
+
+  // Race is over, players are ranked from the first
+  Results(Vec<RacePlayer>),
+}
+
+/// Writes the message prefixed by its length
+pub async fn write_message<W, T>(writer: &mut W, message: &T) -> Result<()>
+where
+  W: AsyncWrite + Unpin,
+  T: Serialize,
+{
+  let bytes = bincode::serialize(message)?;
+
+  writer.write_u32(bytes.len() as u32).await?;
+  writer.write_all(&bytes).await?;
+  writer.flush().await?;
+
+  Ok(())
+}
+
+/// Reads the message prefixed by its length
+pub async fn read_message<R, T>(reader: &mut R) -> Result<T>
+where
+  R: AsyncRead + Unpin,
+  T: DeserializeOwned,
+{
+  let message_len = reader.read_u32().await?;
+
+  if message_len > MAX_MESSAGE_LEN {
+    bail!("Race message is too long ({message_len} bytes)");
+  }
+
+  let mut bytes = vec![0; message_len as usize];
+  reader.read_exact(&mut bytes).await?;
+
+  Ok(bincode::deserialize(&bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[tokio::test]
+  async fn messages_survive_framing() {
+    let (mut client, mut server) = tokio::io::duplex(64);
+
+    let message = ClientMessage::Start {
+      text: String::from("the quick brown fox "),
+      language: String::from("en"),
+      typing_duration: TypingDuration::ThirtySec,
+    };
+
+    let writer = tokio::spawn(async move {
+      write_message(&mut client, &message).await.unwrap();
+      write_message(
+        &mut client,
+        &ClientMessage::Progress {
+          progress: 4,
+          wpm: 48,
+        },
+      )
+      .await
+      .unwrap();
+      message
+    });
+
+    let received_start = read_message::<_, ClientMessage>(&mut server).await.unwrap();
+    let received_progress = read_message::<_, ClientMessage>(&mut server).await.unwrap();
+
+    assert_eq!(received_start, writer.await.unwrap());
+    assert_eq!(
+      received_progress,
+      ClientMessage::Progress {
+        progress: 4,
+        wpm: 48
+      }
+    );
+  }
+}
@@ -0,0 +1,555 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::{Alignment, Constraint, Flex, Layout, Rect},
+  style::{Style, Stylize},
+  text::{Line, Span, Text},
+  widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph, Wrap},
+};
+
+use crate::{
+  config::{TukaiConfig, TukaiLayout, TukaiLayoutColorTypeEnum, TypingDuration},
+  engine::TypingSession,
+  helper::Generator,
+  screens::{Instruction, InstructionWidget, Screen, ToDark, typing::TypingScreen},
+  storage::{
+    lessons::{Lesson, LessonResult},
+    storage_handler::StorageHandler,
+  },
+};
+
+use super::ActiveScreenEnum;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum LessonsState {
+  // Lessons with their progress
+  List,
+
+  // Exercise of the selected lesson is typed
+  Exercise,
+
+  // Result of the typed exercise
+  Result,
+}
+
+/// Guided touch typing lessons
+///
+/// Lessons are unlocked one by one by passing the previous lesson.
+pub struct LessonsScreen {
+  /// Application config
+  config: Rc<RefCell<TukaiConfig>>,
+
+  /// Storage with the progress of the lessons
+  storage_handler: Rc<RefCell<StorageHandler>>,
+
+  state: LessonsState,
+
+  /// Index of the selected lesson
+  selected_index: usize,
+
+  /// Typing of the exercise
+  typing_screen: TypingScreen,
+
+  /// Result of the typed exercise and whether it passed the lesson
+  result: Option<(LessonResult, bool)>,
+
+  /// Block motto
+  motto: String,
+}
+
+impl LessonsScreen {
+  pub fn new(
+    config: Rc<RefCell<TukaiConfig>>,
+    storage_handler: Rc<RefCell<StorageHandler>>,
+  ) -> Self {
+    let typing_screen = TypingScreen::new(Rc::clone(&config));
+
+    let motto = Generator::generate_random_motto(&config.borrow());
+
+    // The first lesson not passed yet is selected
+    let selected_index = {
+      let lessons_progress = storage_handler
+        .borrow()
+        .get_lessons_progress(config.borrow().keyboard_layout);
+
+      Lesson::all()
+        .iter()
+        .position(|lesson| {
+          !lessons_progress
+            .iter()
+            .any(|progress| progress.lesson == *lesson && progress.is_passed)
+        })
+        .unwrap_or(0)
+    };
+
+    Self {
+      config,
+      storage_handler,
+      state: LessonsState::List,
+      selected_index,
+      typing_screen,
+      result: None,
+      motto,
+    }
+  }
+
+  fn get_selected_lesson(&self) -> Lesson {
+    Lesson::all()[self.selected_index]
+  }
+
+  /// Starts the exercise of the selected lesson if it's unlocked
+  fn start_lesson(&mut self) {
+    let lesson = self.get_selected_lesson();
+
+    let lessons_progress = self
+      .storage_handler
+      .borrow()
+      .get_lessons_progress(self.config.borrow().keyboard_layout);
+
+    if !lesson.is_unlocked(&lessons_progress) {
+      return;
+    }
+
+    let text = Generator::generate_lesson_exercise(&self.config.borrow(), lesson);
+
+    // Exercise ends when the text is typed, the duration only limits it
+    self.typing_screen = TypingScreen::new(Rc::clone(&self.config));
+    self.typing_screen.session = TypingSession::new(text, TypingDuration::ThreeMinutes);
+
+    self.result = None;
+    self.state = LessonsState::Exercise;
+  }
+
+  /// Handles the key events of the lessons list
+  fn handle_list_events(&mut self, key_event: KeyEvent) -> bool {
+    match key_event.code {
+      KeyCode::Up | KeyCode::Char('k') => {
+        self.selected_index = self.selected_index.saturating_sub(1);
+      }
+      KeyCode::Down | KeyCode::Char('j') => {
+        self.selected_index = (self.selected_index + 1).min(Lesson::all().len() - 1);
+      }
+      KeyCode::Enter => self.start_lesson(),
+      _ => return false,
+    }
+
+    true
+  }
+
+  /// Handles the key events of the exercise result
+  ///
+  /// The passed lesson continues with the next lesson.
+  fn handle_result_events(&mut self, key_event: KeyEvent) -> bool {
+    match key_event.code {
+      KeyCode::Enter => {
+        let is_passed = self.result.is_some_and(|(_, is_passed)| is_passed);
+
+        if is_passed && self.selected_index + 1 < Lesson::all().len() {
+          self.selected_index += 1;
+        }
+
+        self.start_lesson();
+      }
+      KeyCode::Esc => self.state = LessonsState::List,
+      _ => return false,
+    }
+
+    true
+  }
+}
+
+impl Screen for LessonsScreen {
+  fn increment_time_secs(&mut self) {
+    self.typing_screen.increment_time_secs();
+  }
+
+  fn get_config(&self) -> &Rc<RefCell<TukaiConfig>> {
+    &self.config
+  }
+
+  fn get_screen_name(&self) -> String {
+    match self.state {
+      LessonsState::List => String::from("Lessons"),
+      _ => format!("Lesson 》{}", self.get_selected_lesson().get_title()),
+    }
+  }
+
+  /// No time remains once the exercise text is typed
+  fn get_remaining_time(&self) -> usize {
+    let session = &self.typing_screen.session;
+
+    if self.state != LessonsState::Exercise || session.is_text_completed() {
+      0
+    } else {
+      session.get_remaining_time()
+    }
+  }
+
+  fn get_previous_screen(&self) -> Option<ActiveScreenEnum> {
+    Some(ActiveScreenEnum::Typing)
+  }
+
+  /// Saves the result of the typed exercise into the lesson progress
+  fn stop(&mut self, storage_handler: &mut StorageHandler) {
+    let session = &mut self.typing_screen.session;
+
+    if self.state != LessonsState::Exercise || session.get_cursor_index() == 0 {
+      return;
+    }
+
+    let chars_counter = session.get_typed_text().get_typed_chars_count();
+    let mistakes_counter = session.get_mistakes_counter();
+    let elapsed_ms = session.get_elapsed_ms();
+
+    let language = self.config.borrow().get_language().get_lang_code().clone();
+    let stat = session.finish(language, false);
+
+    let corrected_errors = stat
+      .get_metrics()
+      .map_or(0, |metrics| metrics.corrected_errors);
+
+    let result = LessonResult::new(
+      chars_counter,
+      mistakes_counter,
+      corrected_errors,
+      elapsed_ms,
+    );
+
+    let keyboard_layout = self.config.borrow().keyboard_layout;
+    storage_handler.add_key_counters(keyboard_layout, session.get_key_counters());
+
+    let is_passed =
+      storage_handler.add_lesson_result(keyboard_layout, self.get_selected_lesson(), &result);
+
+    self.result = Some((result, is_passed));
+    self.state = LessonsState::Result;
+  }
+
+  fn is_running(&self) -> bool {
+    self.state == LessonsState::Exercise && self.typing_screen.is_running()
+  }
+
+  fn accepts_remapped_input(&self) -> bool {
+    self.state == LessonsState::Exercise
+  }
+
+  fn is_popup_visible(&self) -> bool {
+    self.state == LessonsState::Result
+  }
+
+  /// Restarts the exercise of the lesson
+  fn reset(&mut self) {
+    if self.state != LessonsState::List {
+      self.start_lesson();
+    }
+  }
+
+  fn handle_paste(&mut self, text: &str) {
+    if self.state == LessonsState::Exercise {
+      self.typing_screen.handle_paste(text);
+    }
+  }
+
+  fn on_focus_lost(&mut self) {
+    if self.state == LessonsState::Exercise {
+      self.typing_screen.on_focus_lost();
+    }
+  }
+
+  fn handle_control_events(&mut self, key_event: KeyEvent) -> bool {
+    match key_event.code {
+      KeyCode::Char('w') | KeyCode::Char('h') if self.state == LessonsState::Exercise => {
+        self.typing_screen.handle_control_events(key_event)
+      }
+      _ => false,
+    }
+  }
+
+  fn handle_events(&mut self, key_event: KeyEvent) -> bool {
+    match self.state {
+      LessonsState::List => self.handle_list_events(key_event),
+      LessonsState::Exercise if key_event.code == KeyCode::Esc => {
+        self.state = LessonsState::List;
+        true
+      }
+      LessonsState::Exercise => self.typing_screen.handle_events(key_event),
+      LessonsState::Result => self.handle_result_events(key_event),
+    }
+  }
+
+  fn render(&self, frame: &mut Frame, area: Rect) {
+    let app_config = self.config.borrow();
+    let app_layout = app_config.get_layout();
+
+    let horizontal_padding = if (area.width / 3) < 8 {
+      2
+    } else {
+      area.width / 3 - 8
+    };
+
+    let block = Block::new()
+      .title(self.get_title())
+      .title_alignment(Alignment::Left)
+      .title_bottom(self.motto.as_ref())
+      .title_style(Style::default().fg(app_layout.get_primary_color()))
+      .title_alignment(Alignment::Center)
+      .style(app_config.get_bg_color())
+      .borders(Borders::ALL)
+      .border_type(BorderType::Rounded)
+      .border_style(Style::default().fg(app_layout.get_primary_color()))
+      .padding(Padding::new(
+        horizontal_padding,
+        horizontal_padding,
+        (area.height / 2) - 5,
+        0,
+      ));
+
+    let p = self
+      .get_paragraph(&app_layout)
+      .block(block)
+      .alignment(Alignment::Left);
+
+    frame.render_widget(p, area);
+  }
+
+  fn render_instructions(&self, frame: &mut Frame, area: Rect) {
+    let app_config = self.config.borrow();
+    let app_layout = app_config.get_layout();
+
+    let mut instruction_widget = InstructionWidget::new(&app_layout);
+
+    match self.state {
+      LessonsState::List => {
+        instruction_widget.add_instruction(Instruction::new(
+          "Exit",
+          "esc",
+          TukaiLayoutColorTypeEnum::Secondary,
+        ));
+        instruction_widget.add_instruction(Instruction::new(
+          "Select",
+          "↑/↓",
+          TukaiLayoutColorTypeEnum::Secondary,
+        ));
+        instruction_widget.add_instruction(Instruction::new(
+          "Start",
+          "enter",
+          TukaiLayoutColorTypeEnum::Secondary,
+        ));
+      }
+      _ => {
+        instruction_widget.add_instruction(Instruction::new(
+          "Lessons",
+          "esc",
+          TukaiLayoutColorTypeEnum::Secondary,
+        ));
+        instruction_widget.add_instruction(Instruction::new(
+          "Restart",
+          "ctrl-r",
+          TukaiLayoutColorTypeEnum::Secondary,
+        ));
+      }
+    }
+
+    instruction_widget.add_instruction(Instruction::new(
+      "Typing",
+      "ctrl-h",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Layout",
+      "ctrl-s",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Language",
+      "ctrl-p",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+
+    let block = Block::new().padding(Padding::new(0, 0, area.height / 2, 0));
+
+    let instructions = instruction_widget
+      .get_paragraph()
+      .block(block)
+      .alignment(Alignment::Center)
+      .style(app_config.get_bg_color());
+
+    frame.render_widget(instructions, area);
+  }
+
+  /// Renders a popup screen
+  ///
+  /// Used after the exercise is typed
+  fn render_popup(&self, frame: &mut Frame) {
+    let Some((result, is_passed)) = self.result else {
+      return;
+    };
+
+    let app_config = self.config.borrow();
+    let app_layout = app_config.get_layout();
+    let area = frame.area();
+
+    let block = Block::bordered()
+      .style(app_config.get_bg_color())
+      .border_type(BorderType::Rounded)
+      .border_style(Style::new().fg(app_layout.get_primary_color()));
+
+    let primary_color = app_layout.get_primary_color();
+    let lesson = self.get_selected_lesson();
+    let (pass_wpm, pass_accuracy) = lesson.get_pass_thresholds();
+
+    let mut lines = vec![
+      Line::from(vec![
+        Span::from("🔥 WPM: "),
+        Span::from(format!("{} (pass {pass_wpm})", result.wpm)).bold(),
+      ])
+      .style(Style::default().fg(primary_color)),
+      Line::from(vec![
+        Span::from("🎯 Accuracy: "),
+        Span::from(format!("{}% (pass {pass_accuracy}%)", result.accuracy)).bold(),
+      ])
+      .style(Style::default().fg(primary_color)),
+      Line::from(""),
+    ];
+
+    if is_passed {
+      lines.push(
+        Line::from(format!("✅ {} passed", lesson.get_title()))
+          .style(Style::default().fg(primary_color).bold()),
+      );
+    } else {
+      lines.push(
+        Line::from(format!("❌ {} not passed", lesson.get_title()))
+          .style(Style::default().fg(app_layout.get_error_color())),
+      );
+    }
+
+    let next_action = if is_passed && self.selected_index + 1 < Lesson::all().len() {
+      "Next lesson"
+    } else {
+      "Try again"
+    };
+
+    lines.extend([
+      Line::from(""),
+      Line::from(vec![
+        Span::from(next_action).style(Style::default().fg(primary_color)),
+        Span::from(" enter").style(Style::default().fg(primary_color).bold()),
+      ]),
+    ]);
+
+    // Text lines with the vertical padding and borders
+    let popup_height = lines.len() as u16 + 4;
+
+    let p = Paragraph::new(Text::from(lines))
+      .block(block.padding(Padding::vertical(1)))
+      .alignment(Alignment::Center)
+      .centered();
+
+    let vertical = Layout::vertical([Constraint::Length(popup_height)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Length(44)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(p, area);
+  }
+}
+
+impl LessonsScreen {
+  /// Returns the lines of the lessons with their progress
+  fn get_list_lines(&self, layout: &TukaiLayout) -> Vec<Line<'_>> {
+    let keyboard_layout = self.config.borrow().keyboard_layout;
+    let lessons_progress = self
+      .storage_handler
+      .borrow()
+      .get_lessons_progress(keyboard_layout);
+
+    Lesson::all()
+      .iter()
+      .enumerate()
+      .map(|(index, lesson)| {
+        let progress = lessons_progress
+          .iter()
+          .find(|progress| progress.lesson == *lesson);
+
+        let icon = if progress.is_some_and(|progress| progress.is_passed) {
+          "✅"
+        } else if lesson.is_unlocked(&lessons_progress) {
+          "🔓"
+        } else {
+          "🔒"
+        };
+
+        let best = progress
+          .filter(|progress| progress.attempts > 0)
+          .map(|progress| {
+            format!(
+              "  best {} WPM {}%",
+              progress.best_wpm, progress.best_accuracy
+            )
+          })
+          .unwrap_or_default();
+
+        let (pass_wpm, pass_accuracy) = lesson.get_pass_thresholds();
+
+        let title_style = if index == self.selected_index {
+          Style::default().fg(layout.get_primary_color()).bold()
+        } else {
+          Style::default().fg(layout.get_text_color())
+        };
+
+        Line::from(vec![
+          Span::from(if index == self.selected_index {
+            "▶ "
+          } else {
+            "  "
+          })
+          .style(title_style),
+          Span::from(format!("{icon} {:<11}", lesson.get_title())).style(title_style),
+          Span::from(format!(
+            " {}  pass {pass_wpm} WPM {pass_accuracy}%{best}",
+            lesson.get_new_keys(keyboard_layout)
+          ))
+          .style(Style::default().fg(layout.get_text_color().to_dark())),
+        ])
+      })
+      .collect()
+  }
+
+  /// Prepares and returns a paragraph.
+  ///
+  /// Shows the lessons or the exercise of the selected lesson.
+  pub fn get_paragraph(&self, layout: &TukaiLayout) -> Paragraph<'_> {
+    let mut lines = Vec::new();
+
+    if self.state == LessonsState::List {
+      let keyboard_layout = self.config.borrow().keyboard_layout;
+
+      lines.push(
+        Line::from(format!("🎓 Lessons ({keyboard_layout})"))
+          .style(Style::default().fg(layout.get_primary_color()).bold()),
+      );
+      lines.push(Line::from(Vec::new()));
+      lines.extend(self.get_list_lines(layout));
+    } else {
+      let lesson = self.get_selected_lesson();
+
+      lines.push(Line::from(vec![
+        Span::from(format!("🎓 {}", lesson.get_title()))
+          .style(Style::default().fg(layout.get_primary_color()).bold()),
+        Span::from(format!(
+          "  keys: {}",
+          lesson.get_unlocked_keys(self.config.borrow().keyboard_layout)
+        ))
+        .style(Style::default().fg(layout.get_text_color())),
+      ]));
+      lines.push(Line::from(Vec::new()));
+      lines.push(self.typing_screen.get_text_line(layout));
+    }
+
+    Paragraph::new(Text::from(lines)).wrap(Wrap { trim: true })
+  }
+}
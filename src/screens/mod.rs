@@ -1,6 +1,11 @@
+pub mod lessons;
+pub mod profiles;
+pub mod race;
 pub mod repeat;
+pub mod replay;
 pub mod stats;
 pub mod typing;
+pub mod zen;
 
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -16,7 +21,7 @@ use ratatui::{
 
 use crate::{
   config::{TukaiConfig, TukaiLayout, TukaiLayoutColorTypeEnum},
-  storage::storage_handler::StorageHandler,
+  storage::{stats::Stat, storage_handler::StorageHandler},
 };
 
 #[derive(PartialEq, Hash, Eq, Debug)]
@@ -24,21 +29,42 @@ pub enum ActiveScreenEnum {
   Typing,
   Repeat,
   Stats,
+  Profiles,
+  Zen,
+  Lessons,
+}
+
+/// Action requested by a screen
+///
+/// Screens can't access the app state directly,
+/// so the app takes the action after the screen handled an event.
+#[derive(Debug)]
+pub enum ScreenAction {
+  SwitchProfile(String),
+
+  // Races the ghost of the best run with the active duration
+  RaceBestGhost,
+
+  // Races the ghost of the run
+  RaceGhost(Box<Stat>),
+
+  // Replays the recorded keystrokes of the run
+  Replay(Box<Stat>),
 }
 
 #[allow(unused)]
 pub trait ToDark {
-  /// Converts the `(u8, u8, u8)` tuple to a `Color::Rgb`
+  /// Darkens the `Color::Rgb` by 20 percent
   ///
   /// # Example
   ///
   /// ```
-  /// use ratatui::style::Color
+  /// use ratatui::style::Color;
+  /// use tukai::screens::ToDark;
   ///
-  /// let rgb: (u8, u8, u8) = (128, 64, 255);
-  /// let color = rgb.to_color();
+  /// let color = Color::Rgb(100, 50, 250);
   ///
-  /// assert_eq!(color, Color::Rgb(128, 64, 255));
+  /// assert_eq!(color.to_dark(), Color::Rgb(80, 40, 200));
   /// ```
   fn to_dark(self) -> Color;
 }
@@ -101,7 +127,7 @@ impl<'a> InstructionWidget<'a> {
   }
 
   /// Returns paragraph contains instructions
-  pub fn get_paragraph(&self) -> Paragraph {
+  pub fn get_paragraph(&self) -> Paragraph<'_> {
     let instructions_spans = self
       .instructions
       .iter()
@@ -153,8 +179,9 @@ pub trait Screen {
     let app_layout = app_config.get_layout();
 
     Title::from(format!(
-      " tukai v{} 》{} 》{} 》{} ",
+      " tukai v{} 》👤 {} 》{} 》{} 》{} ",
       env!("CARGO_PKG_VERSION"),
+      app_config.get_profile_name(),
       app_layout.get_active_layout_name(),
       app_config.get_language().get_lang_code(),
       self.get_screen_name()
@@ -173,6 +200,31 @@ pub trait Screen {
     false
   }
 
+  /// Screen changes without events (e.g. the ghost caret),
+  /// so it's redrawn on every frame.
+  fn is_animated(&self) -> bool {
+    false
+  }
+
+  /// Updates the animated screen before the frame is drawn
+  fn on_frame(&mut self) {}
+
+  /// Verifies if the typed characters are remapped into the keyboard layout
+  ///
+  /// Only the typing input is remapped (see `KeyboardLayout::remap`),
+  /// not the screen keys or the entered names and phrases.
+  fn accepts_remapped_input(&self) -> bool {
+    false
+  }
+
+  /// Handles the text pasted into the terminal
+  ///
+  /// Pasted text is never typed, the typing screens flag the running run.
+  fn handle_paste(&mut self, _text: &str) {}
+
+  /// Handles the terminal losing the focus
+  fn on_focus_lost(&mut self) {}
+
   /// Returns whether the popup is visible.
   ///
   /// Default set to false (not used in stats screen)
@@ -193,6 +245,13 @@ pub trait Screen {
   /// Used after the run is completed
   fn render_popup(&self, frame: &mut Frame);
 
+  /// Takes the action requested by the screen (if any).
+  ///
+  /// Called by the app after the screen consumed an event.
+  fn take_action(&mut self) -> Option<ScreenAction> {
+    None
+  }
+
   /// Handles control-modified key events specific to the screen.
   ///
   /// True if event consumed, false otherwise.
@@ -0,0 +1,293 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::{Alignment, Constraint, Flex, Layout, Rect},
+  style::{Style, Stylize},
+  text::{Line, Span},
+  widgets::{Block, BorderType, Borders, List, ListItem, ListState, Padding, Paragraph, Wrap},
+};
+
+use crate::{
+  config::{TukaiConfig, TukaiLayoutColorTypeEnum},
+  profile::ProfileHandler,
+  screens::{Instruction, InstructionWidget, Screen, ScreenAction, ToDark},
+};
+
+use super::ActiveScreenEnum;
+
+pub struct ProfilesScreen {
+  /// Application config
+  config: Rc<RefCell<TukaiConfig>>,
+
+  /// Names of the existing profiles
+  profile_names: Vec<String>,
+
+  /// Index of the selected profile
+  selected_index: usize,
+
+  /// Name of the profile being created (input mode)
+  new_profile_name: Option<String>,
+
+  /// Action requested by the user
+  action: Option<ScreenAction>,
+
+  /// Error of the failed profile switch
+  error_message: Option<String>,
+}
+
+impl ProfilesScreen {
+  pub fn new(config: Rc<RefCell<TukaiConfig>>) -> Self {
+    let profile_names = ProfileHandler::get_profile_names();
+
+    let selected_index = profile_names
+      .iter()
+      .position(|profile_name| profile_name == config.borrow().get_profile_name())
+      .unwrap_or(0);
+
+    Self {
+      config,
+
+      profile_names,
+
+      selected_index,
+
+      new_profile_name: None,
+
+      action: None,
+
+      error_message: None,
+    }
+  }
+
+  /// Shows the error of the failed profile switch
+  pub fn with_error(mut self, error_message: String) -> Self {
+    self.error_message = Some(error_message);
+    self
+  }
+}
+
+impl Screen for ProfilesScreen {
+  fn increment_time_secs(&mut self) {}
+
+  fn get_config(&self) -> &Rc<RefCell<TukaiConfig>> {
+    &self.config
+  }
+
+  fn get_screen_name(&self) -> String {
+    String::from("Profiles")
+  }
+
+  fn get_remaining_time(&self) -> usize {
+    0
+  }
+
+  fn get_previous_screen(&self) -> Option<ActiveScreenEnum> {
+    Some(ActiveScreenEnum::Typing)
+  }
+
+  fn reset(&mut self) {
+    self.new_profile_name = None;
+  }
+
+  fn take_action(&mut self) -> Option<ScreenAction> {
+    self.action.take()
+  }
+
+  fn handle_events(&mut self, key_event: KeyEvent) -> bool {
+    if self.new_profile_name.is_some() {
+      return self.handle_new_profile_events(key_event);
+    }
+
+    match key_event.code {
+      KeyCode::Up | KeyCode::Char('k') => {
+        self.selected_index = self.selected_index.saturating_sub(1);
+        true
+      }
+      KeyCode::Down | KeyCode::Char('j') => {
+        self.selected_index = (self.selected_index + 1).min(self.profile_names.len() - 1);
+        true
+      }
+      KeyCode::Char('n') => {
+        self.new_profile_name = Some(String::new());
+        true
+      }
+      KeyCode::Enter => {
+        let profile_name = self.profile_names[self.selected_index].clone();
+        self.action = Some(ScreenAction::SwitchProfile(profile_name));
+        true
+      }
+      _ => false,
+    }
+  }
+
+  fn render_instructions(&self, frame: &mut Frame, area: Rect) {
+    let app_config = self.config.borrow();
+    let app_layout = app_config.get_layout();
+
+    let mut instruction_widget = InstructionWidget::new(&app_layout);
+
+    instruction_widget.add_instruction(Instruction::new(
+      "Exit",
+      "esc",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+
+    if self.new_profile_name.is_some() {
+      instruction_widget.add_instruction(Instruction::new(
+        "Create",
+        "enter",
+        TukaiLayoutColorTypeEnum::Secondary,
+      ));
+    } else {
+      instruction_widget.add_instruction(Instruction::new(
+        "Select",
+        "enter",
+        TukaiLayoutColorTypeEnum::Secondary,
+      ));
+      instruction_widget.add_instruction(Instruction::new(
+        "New profile",
+        "n",
+        TukaiLayoutColorTypeEnum::Secondary,
+      ));
+    }
+
+    instruction_widget.add_instruction(Instruction::new(
+      "Typing",
+      "ctrl-h",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+
+    let block = Block::new().padding(Padding::new(0, 0, area.height / 2, 0));
+
+    let instructions = instruction_widget
+      .get_paragraph()
+      .block(block)
+      .alignment(Alignment::Center)
+      .style(app_config.get_bg_color());
+
+    frame.render_widget(instructions, area);
+  }
+
+  fn render(&self, frame: &mut Frame, area: Rect) {
+    let app_config = self.config.borrow();
+    let app_layout = app_config.get_layout();
+
+    let primary_color = app_layout.get_primary_color();
+    let text_color = app_layout.get_text_color();
+
+    let block = Block::new()
+      .title(self.get_title())
+      .title_style(Style::default().fg(primary_color))
+      .style(app_config.get_bg_color())
+      .borders(Borders::ALL)
+      .border_type(BorderType::Rounded)
+      .border_style(Style::default().fg(primary_color));
+
+    let inner_area = block.inner(area);
+    frame.render_widget(block, area);
+
+    let [content_area] = Layout::horizontal([Constraint::Length(40)])
+      .flex(Flex::Center)
+      .areas(inner_area);
+
+    let error_height = if self.error_message.is_some() { 3 } else { 0 };
+
+    let [header_area, list_area, input_area, error_area] = Layout::vertical([
+      Constraint::Length(2),
+      Constraint::Length(self.profile_names.len() as u16),
+      Constraint::Length(2),
+      Constraint::Length(error_height),
+    ])
+    .flex(Flex::Center)
+    .areas(content_area);
+
+    let header = Paragraph::new(Line::from("👤 Select a profile").bold())
+      .style(Style::default().fg(primary_color));
+
+    let items = self
+      .profile_names
+      .iter()
+      .map(|profile_name| {
+        let suffix = if profile_name == app_config.get_profile_name() {
+          " (active)"
+        } else {
+          ""
+        };
+
+        ListItem::new(Line::from(vec![
+          Span::from(profile_name.clone()),
+          Span::from(suffix).style(Style::default().fg(text_color.to_dark())),
+        ]))
+      })
+      .collect::<Vec<ListItem>>();
+
+    let list = List::new(items)
+      .style(Style::default().fg(text_color))
+      .highlight_style(
+        Style::default()
+          .fg(app_layout.get_text_current_color())
+          .bg(app_layout.get_text_current_bg_color()),
+      )
+      .highlight_symbol("» ");
+
+    let mut list_state = ListState::default().with_selected(Some(self.selected_index));
+
+    frame.render_widget(header, header_area);
+    frame.render_stateful_widget(list, list_area, &mut list_state);
+
+    if let Some(new_profile_name) = &self.new_profile_name {
+      let input = Paragraph::new(Line::from(vec![
+        Span::from("New profile: ").style(Style::default().fg(text_color)),
+        Span::from(format!("{new_profile_name}_")).style(Style::default().fg(primary_color).bold()),
+      ]))
+      .block(Block::new().padding(Padding::top(1)));
+
+      frame.render_widget(input, input_area);
+    }
+
+    if let Some(error_message) = &self.error_message {
+      let error = Paragraph::new(Line::from(error_message.as_str()))
+        .style(Style::default().fg(app_layout.get_error_color()))
+        .wrap(Wrap { trim: true });
+
+      frame.render_widget(error, error_area);
+    }
+  }
+
+  fn render_popup(&self, _frame: &mut Frame) {}
+}
+
+impl ProfilesScreen {
+  /// Handles key events while a new profile name is typed
+  fn handle_new_profile_events(&mut self, key_event: KeyEvent) -> bool {
+    let Some(new_profile_name) = self.new_profile_name.as_mut() else {
+      return false;
+    };
+
+    match key_event.code {
+      KeyCode::Esc => self.new_profile_name = None,
+      KeyCode::Backspace => {
+        new_profile_name.pop();
+      }
+      KeyCode::Char(c) => {
+        new_profile_name.push(c);
+
+        // Rejects characters which are not allowed in the profile name
+        if !ProfileHandler::is_valid_name(new_profile_name) {
+          new_profile_name.pop();
+        }
+      }
+      KeyCode::Enter => {
+        if ProfileHandler::is_valid_name(new_profile_name) {
+          self.action = Some(ScreenAction::SwitchProfile(new_profile_name.clone()));
+          self.new_profile_name = None;
+        }
+      }
+      _ => return false,
+    }
+
+    true
+  }
+}
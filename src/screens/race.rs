@@ -0,0 +1,530 @@
+use std::{
+  cell::RefCell,
+  rc::Rc,
+  time::{Duration, Instant},
+};
+
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::{Alignment, Rect},
+  style::{Color, Style, Stylize},
+  text::{Line, Span, Text},
+  widgets::{Block, BorderType, Borders, Padding, Paragraph, Wrap},
+};
+
+use crate::{
+  config::{TukaiConfig, TukaiLayoutColorTypeEnum},
+  engine::TypingSession,
+  helper::Generator,
+  race::{
+    client::{RaceClient, RaceEvent},
+    host::{HOST_PLAYER_ID, RaceHost},
+    protocol::{ClientMessage, RacePlayer, ServerMessage},
+  },
+  screens::{Instruction, InstructionWidget, Screen, ToDark},
+  storage::{stats::RunMode, storage_handler::StorageHandler},
+};
+
+use super::{ActiveScreenEnum, typing::TypingScreen};
+
+/// Width of the players progress bars in characters
+const PROGRESS_BAR_WIDTH: usize = 20;
+
+/// Phase of the race seen by the player
+enum RaceState {
+  Connecting,
+  Lobby,
+  Countdown { starts_at: Instant },
+  Running,
+
+  // Player finished, others are still racing
+  Finished,
+
+  // Players ranked from the first
+  Results(Vec<RacePlayer>),
+
+  // Connection failed or was closed (with the reason)
+  Disconnected(String),
+}
+
+pub struct RaceScreen {
+  /// Application config
+  config: Rc<RefCell<TukaiConfig>>,
+
+  /// Connection to the lobby
+  client: RaceClient,
+
+  /// Hosted lobby (closed when the screen is left)
+  host: Option<RaceHost>,
+
+  /// Id of the player assigned by the lobby
+  player_id: Option<u32>,
+
+  /// Player hosts the lobby (starts the races)
+  is_host: bool,
+
+  /// Players of the lobby
+  players: Vec<RacePlayer>,
+
+  /// Typing screen of the race text (handles the typing and renders the text)
+  typing_screen: TypingScreen,
+
+  /// Language code of the race text
+  language: String,
+
+  /// Progress last sent to the lobby
+  sent_progress: usize,
+
+  state: RaceState,
+}
+
+impl RaceScreen {
+  /// Creates the race screen connected to the lobby
+  ///
+  /// The host passes its lobby, which is closed together with the screen.
+  pub fn new(config: Rc<RefCell<TukaiConfig>>, client: RaceClient, host: Option<RaceHost>) -> Self {
+    let typing_screen = TypingScreen::new(Rc::clone(&config));
+    let language = config.borrow().get_language().get_lang_code().clone();
+
+    Self {
+      config,
+
+      client,
+
+      host,
+
+      player_id: None,
+
+      is_host: false,
+
+      players: Vec::new(),
+
+      typing_screen,
+
+      language,
+
+      sent_progress: 0,
+
+      state: RaceState::Connecting,
+    }
+  }
+
+  /// Handles the event received from the lobby
+  fn handle_race_event(&mut self, race_event: RaceEvent) {
+    match race_event {
+      RaceEvent::Message(ServerMessage::Welcome { player_id, is_host }) => {
+        self.player_id = Some(player_id);
+        self.is_host = is_host;
+        self.state = RaceState::Lobby;
+      }
+      RaceEvent::Message(ServerMessage::Players(players)) => self.players = players,
+      RaceEvent::Message(ServerMessage::Countdown {
+        text,
+        language,
+        typing_duration,
+        countdown_ms,
+      }) => {
+        self.typing_screen = TypingScreen::new(Rc::clone(&self.config));
+        self.typing_screen.session = TypingSession::new(text, typing_duration);
+        self.language = language;
+        self.sent_progress = 0;
+
+        self.state = RaceState::Countdown {
+          starts_at: Instant::now() + Duration::from_millis(countdown_ms),
+        };
+      }
+      RaceEvent::Message(ServerMessage::Results(rankings)) => {
+        self.state = RaceState::Results(rankings);
+      }
+      RaceEvent::Disconnected(reason) => self.state = RaceState::Disconnected(reason),
+    }
+  }
+
+  /// Starts a new race with a text generated by the host
+  fn start_race(&self) {
+    let app_config = self.config.borrow();
+
+    self.client.send(ClientMessage::Start {
+      text: Generator::generate_random_string(&app_config),
+      language: app_config.get_language().get_lang_code().clone(),
+      typing_duration: app_config.typing_duration.clone(),
+    });
+  }
+
+  /// Returns the progress bar line of the player
+  fn get_progress_line(&self, player: &RacePlayer, colors: (Color, Color)) -> Line<'_> {
+    let (primary_color, text_color) = colors;
+
+    let is_own = Some(player.id) == self.player_id;
+
+    // Own progress is shown without waiting for the lobby
+    let progress = if is_own {
+      self.typing_screen.session.get_cursor_index()
+    } else {
+      player.progress
+    };
+
+    let text_len = self.typing_screen.session.get_text().chars().count().max(1);
+    let filled_len = (progress * PROGRESS_BAR_WIDTH / text_len).min(PROGRESS_BAR_WIDTH);
+
+    let name_color = if is_own { primary_color } else { text_color };
+
+    Line::from(vec![
+      Span::from(format!("{:<12} ", player.name)).style(Style::default().fg(name_color).bold()),
+      Span::from("█".repeat(filled_len)).style(Style::default().fg(primary_color)),
+      Span::from("░".repeat(PROGRESS_BAR_WIDTH - filled_len))
+        .style(Style::default().fg(text_color.to_dark())),
+      Span::from(format!(
+        " {:>3} WPM{}",
+        player.wpm,
+        if player.is_finished { " 🏁" } else { "" }
+      ))
+      .style(Style::default().fg(text_color)),
+    ])
+  }
+
+  /// Returns the lines of the race state
+  fn get_lines(&self, app_config: &TukaiConfig) -> Vec<Line<'_>> {
+    let app_layout = app_config.get_layout();
+
+    let primary_color = app_layout.get_primary_color();
+    let error_color = app_layout.get_error_color();
+    let text_color = app_layout.get_text_color();
+
+    let header = |text: String, color: Color| {
+      Line::from(Span::from(text).style(Style::default().fg(color).bold()))
+    };
+
+    let racing_players = self.players.iter().filter(|player| player.is_racing);
+
+    let progress_lines = || {
+      racing_players
+        .clone()
+        .map(|player| self.get_progress_line(player, (primary_color, text_color)))
+        .collect::<Vec<Line>>()
+    };
+
+    let mut lines = match &self.state {
+      RaceState::Connecting => vec![header(String::from("Connecting…"), text_color)],
+      RaceState::Disconnected(reason) => vec![header(reason.clone(), error_color)],
+      RaceState::Lobby => {
+        let mut lines = vec![
+          header(
+            format!(
+              "Lobby · {} players · {}",
+              self.players.len(),
+              if self.is_host {
+                "press enter to start"
+              } else {
+                "waiting for the host"
+              }
+            ),
+            primary_color,
+          ),
+          Line::default(),
+        ];
+
+        lines.extend(self.players.iter().map(|player| {
+          Line::from(format!(
+            "👤 {}{}",
+            player.name,
+            if player.id == HOST_PLAYER_ID {
+              " (host)"
+            } else {
+              ""
+            }
+          ))
+          .style(Style::default().fg(text_color))
+        }));
+
+        lines
+      }
+      RaceState::Countdown { starts_at } => {
+        let remaining_secs = starts_at
+          .saturating_duration_since(Instant::now())
+          .as_secs_f64()
+          .ceil();
+
+        let mut lines = vec![
+          header(format!("Starting in {remaining_secs}…"), primary_color),
+          Line::default(),
+        ];
+
+        lines.extend(progress_lines());
+        lines
+      }
+      RaceState::Running => {
+        let mut lines = vec![
+          header(format!("⏳{}", self.get_remaining_time()), primary_color),
+          Line::default(),
+        ];
+
+        lines.extend(progress_lines());
+        lines
+      }
+      RaceState::Finished => {
+        let mut lines = vec![
+          header(
+            String::from("Finished · waiting for the others"),
+            primary_color,
+          ),
+          Line::default(),
+        ];
+
+        lines.extend(progress_lines());
+        lines
+      }
+      RaceState::Results(rankings) => {
+        let mut lines = vec![
+          header(
+            format!(
+              "Results{}",
+              if self.is_host {
+                " · press enter to race again"
+              } else {
+                ""
+              }
+            ),
+            primary_color,
+          ),
+          Line::default(),
+        ];
+
+        lines.extend(rankings.iter().enumerate().map(|(index, player)| {
+          let color = if Some(player.id) == self.player_id {
+            primary_color
+          } else {
+            text_color
+          };
+
+          Line::from(format!(
+            "{}. {:<12} {:>3} WPM  {}%",
+            index + 1,
+            player.name,
+            player.wpm,
+            player.accuracy
+          ))
+          .style(Style::default().fg(color))
+        }));
+
+        lines
+      }
+    };
+
+    if matches!(self.state, RaceState::Countdown { .. } | RaceState::Running) {
+      lines.push(Line::default());
+      lines.push(self.typing_screen.get_text_line(&app_layout));
+    }
+
+    lines
+  }
+}
+
+impl Screen for RaceScreen {
+  /// Idle racer isn't paused (the countdown runs for all the players)
+  fn increment_time_secs(&mut self) {
+    if matches!(self.state, RaceState::Running) {
+      self.typing_screen.session.tick();
+    }
+  }
+
+  fn get_config(&self) -> &Rc<RefCell<TukaiConfig>> {
+    &self.config
+  }
+
+  fn get_screen_name(&self) -> String {
+    match &self.host {
+      Some(host) => format!("Race 》{}", host.get_local_addr()),
+      None => String::from("Race"),
+    }
+  }
+
+  /// Remaining time of the running race
+  ///
+  /// The completed text ends the race immediately.
+  fn get_remaining_time(&self) -> usize {
+    let session = &self.typing_screen.session;
+
+    match self.state {
+      RaceState::Running if session.is_text_completed() => 0,
+      RaceState::Running => session
+        .get_typing_duration()
+        .as_seconds()
+        .saturating_sub((session.get_elapsed_ms() / 1000) as usize),
+      _ => 0,
+    }
+  }
+
+  fn get_previous_screen(&self) -> Option<ActiveScreenEnum> {
+    Some(ActiveScreenEnum::Typing)
+  }
+
+  /// Finishes the running race
+  ///
+  /// Saves the run into the stats and sends the result to the lobby.
+  fn stop(&mut self, storage_handler: &mut StorageHandler) {
+    if !matches!(self.state, RaceState::Running) {
+      return;
+    }
+
+    let app_config = self.config.borrow();
+
+    let stat = self
+      .typing_screen
+      .session
+      .finish(self.language.clone(), app_config.record_keystrokes)
+      .with_mode(RunMode::Race);
+
+    storage_handler.add_key_counters(
+      app_config.keyboard_layout,
+      self.typing_screen.session.get_key_counters(),
+    );
+    storage_handler.insert_into_stats(&stat);
+    storage_handler.unlock_achievements(&app_config.get_language().get_lang_codes());
+
+    self.client.send(ClientMessage::Finish {
+      wpm: stat.get_average_wpm(),
+      accuracy: stat.get_accuracy(),
+    });
+
+    self.state = RaceState::Finished;
+  }
+
+  fn reset(&mut self) {}
+
+  fn is_running(&self) -> bool {
+    matches!(self.state, RaceState::Running)
+  }
+
+  fn accepts_remapped_input(&self) -> bool {
+    self.is_running()
+  }
+
+  /// Lobby messages are polled on every frame
+  fn is_animated(&self) -> bool {
+    true
+  }
+
+  fn on_frame(&mut self) {
+    while let Some(race_event) = self.client.try_recv() {
+      self.handle_race_event(race_event);
+    }
+
+    match self.state {
+      RaceState::Countdown { starts_at } if Instant::now() >= starts_at => {
+        self.typing_screen.run();
+        self.state = RaceState::Running;
+      }
+      RaceState::Running => {
+        let progress = self.typing_screen.session.get_cursor_index();
+
+        if progress != self.sent_progress {
+          self.sent_progress = progress;
+
+          self.client.send(ClientMessage::Progress {
+            progress,
+            wpm: self.typing_screen.session.get_current_wpm(),
+          });
+        }
+      }
+      _ => {}
+    }
+  }
+
+  fn handle_paste(&mut self, text: &str) {
+    self.typing_screen.handle_paste(text);
+  }
+
+  /// Flags the running run, races aren't paused
+  fn on_focus_lost(&mut self) {
+    self.typing_screen.session.lose_focus();
+  }
+
+  fn handle_events(&mut self, key_event: KeyEvent) -> bool {
+    match self.state {
+      // Races can't be paused
+      RaceState::Running if key_event.code == KeyCode::Tab => false,
+      RaceState::Running => self.typing_screen.handle_events(key_event),
+      RaceState::Lobby | RaceState::Results(_)
+        if self.is_host && key_event.code == KeyCode::Enter =>
+      {
+        self.start_race();
+        true
+      }
+      _ => false,
+    }
+  }
+
+  fn render(&self, frame: &mut Frame, area: Rect) {
+    let app_config = self.config.borrow();
+    let primary_color = app_config.get_layout().get_primary_color();
+
+    let horizontal_padding = if (area.width / 3) < 8 {
+      2
+    } else {
+      area.width / 3 - 8
+    };
+
+    let block = Block::new()
+      .title(self.get_title())
+      .title_style(Style::default().fg(primary_color))
+      .title_alignment(Alignment::Center)
+      .style(app_config.get_bg_color())
+      .borders(Borders::ALL)
+      .border_type(BorderType::Rounded)
+      .border_style(Style::default().fg(primary_color))
+      .padding(Padding::new(
+        horizontal_padding,
+        horizontal_padding,
+        (area.height / 2).saturating_sub(8),
+        0,
+      ));
+
+    let p = Paragraph::new(Text::from(self.get_lines(&app_config)))
+      .wrap(Wrap { trim: true })
+      .block(block)
+      .alignment(Alignment::Left);
+
+    frame.render_widget(p, area);
+  }
+
+  fn render_instructions(&self, frame: &mut Frame, area: Rect) {
+    let app_config = self.config.borrow();
+    let app_layout = app_config.get_layout();
+
+    let mut instruction_widget = InstructionWidget::new(&app_layout);
+
+    instruction_widget.add_instruction(Instruction::new(
+      "Exit",
+      "esc",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+
+    if self.is_host && matches!(self.state, RaceState::Lobby | RaceState::Results(_)) {
+      instruction_widget.add_instruction(Instruction::new(
+        "Start",
+        "enter",
+        TukaiLayoutColorTypeEnum::Secondary,
+      ));
+    }
+
+    instruction_widget.add_instruction(Instruction::new(
+      "Leave race",
+      "ctrl-h",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+
+    let block = Block::new().padding(Padding::new(0, 0, area.height / 2, 0));
+
+    let instructions = instruction_widget
+      .get_paragraph()
+      .block(block)
+      .alignment(Alignment::Center)
+      .style(app_config.get_bg_color());
+
+    frame.render_widget(instructions, area);
+  }
+
+  fn render_popup(&self, _frame: &mut Frame) {}
+}
@@ -1,96 +1,136 @@
-use std::{cell::RefCell, collections::HashSet, rc::Rc};
+use std::{cell::RefCell, rc::Rc};
 
 use ratatui::{
   Frame,
   crossterm::event::{KeyCode, KeyEvent},
-  layout::{Alignment, Rect},
-  style::{Modifier, Style},
+  layout::{Alignment, Constraint, Flex, Layout, Rect},
+  style::{Modifier, Style, Stylize},
   text::{Line, Span, Text},
-  widgets::{Block, BorderType, Borders, Padding, Paragraph, Wrap},
+  widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph, Wrap},
 };
 
 use crate::{
   config::{TukaiConfig, TukaiLayout, TukaiLayoutColorTypeEnum},
+  engine::DrillSession,
   helper::Generator,
   screens::{Instruction, InstructionWidget, Screen, ToDark},
+  storage::{drills::DrillResult, keystroke_log::Keystroke, storage_handler::StorageHandler},
 };
 
 use super::ActiveScreenEnum;
 
-/// Handler for incorrect symbols
-///
-/// Inserts incorrect characters into a HashSet
-pub struct MistakeHandler {
-  mistakes_indexes: HashSet<usize>,
-}
-
-impl MistakeHandler {
-  /// Creates MistakeHandler with empty HashSet
-  fn new() -> Self {
-    Self {
-      mistakes_indexes: HashSet::new(),
-    }
-  }
+/// Presets of the repetitions count of the drill
+const REPETITIONS_PRESETS: [usize; 5] = [5, 10, 20, 30, 50];
 
-  /// Verifies if the character is mistaken
-  pub fn is_char_mistaken(&self, char_index: usize) -> bool {
-    self.mistakes_indexes.contains(&char_index)
-  }
-
-  /// Adds the typed character into the mistakes
-  pub fn add_to_mistakes_indexes(&mut self, char_index: usize) -> bool {
-    self.mistakes_indexes.insert(char_index)
-  }
-
-  /// Removes the typed character from mistakes
-  pub fn remove_from_mistakes_indexes(&mut self, char_index: usize) -> bool {
-    self.mistakes_indexes.remove(&char_index)
-  }
-}
+/// Default repetitions count of the drill
+const DEFAULT_REPETITIONS_COUNT: usize = 10;
 
+/// Drill of a word or a phrase
+///
+/// The phrase and the repetitions count are set up first,
+/// then every repetition is measured and the finished drill is saved.
 pub struct RepeatScreen {
   /// Application config
   config: Rc<RefCell<TukaiConfig>>,
 
-  /// Random generated text from a words list
-  pub generated_text: String,
+  /// Storage with the results of the previous drills
+  storage_handler: Rc<RefCell<StorageHandler>>,
+
+  /// Drilled word or phrase (random or entered by the user)
+  phrase: String,
+
+  /// Count of the repetitions to type
+  repetitions_count: usize,
 
-  /// User typed input
-  pub input: String,
+  /// Running drill (None while the drill is set up)
+  session: Option<DrillSession>,
 
-  /// Handle incorrect characters
-  pub mistake_handler: MistakeHandler,
+  /// Result of the finished drill
+  result: Option<DrillResult>,
 
-  /// The current cursor index withing generated_text
-  cursor_index: usize,
+  is_popup_visible: bool,
 
   /// Block motto
   motto: String,
 }
 
 impl RepeatScreen {
-  pub fn new(config: Rc<RefCell<TukaiConfig>>) -> Self {
-    let generated_text = Generator::generate_repeated_word(&config.borrow());
+  pub fn new(
+    config: Rc<RefCell<TukaiConfig>>,
+    storage_handler: Rc<RefCell<StorageHandler>>,
+  ) -> Self {
+    let phrase = Generator::generate_drill_word(&config.borrow());
+
+    let motto = Generator::generate_random_motto(&config.borrow());
 
     Self {
       config,
+      storage_handler,
+      phrase,
+      repetitions_count: DEFAULT_REPETITIONS_COUNT,
+      session: None,
+      result: None,
+      is_popup_visible: false,
+      motto,
+    }
+  }
+
+  /// Switches the repetitions count through the presets
+  fn switch_repetitions_count(&mut self, is_forward: bool) {
+    let index = REPETITIONS_PRESETS
+      .iter()
+      .position(|count| *count == self.repetitions_count)
+      .unwrap_or(0);
+
+    let next_index = if is_forward {
+      (index + 1).min(REPETITIONS_PRESETS.len() - 1)
+    } else {
+      index.saturating_sub(1)
+    };
 
-      generated_text,
+    self.repetitions_count = REPETITIONS_PRESETS[next_index];
+  }
 
-      input: String::new(),
+  /// Starts the drill of the phrase
+  fn start_drill(&mut self) {
+    let phrase = self.phrase.trim().to_string();
 
-      mistake_handler: MistakeHandler::new(),
+    if phrase.is_empty() {
+      return;
+    }
 
-      cursor_index: 0,
+    self.session = Some(DrillSession::new(phrase, self.repetitions_count));
+  }
 
-      motto: Generator::generate_random_motto(),
+  /// Handles the key events of the drill setup
+  fn handle_setup_events(&mut self, key_event: KeyEvent) -> bool {
+    match key_event.code {
+      KeyCode::Enter => self.start_drill(),
+      KeyCode::Tab => self.phrase = Generator::generate_drill_word(&self.config.borrow()),
+      KeyCode::Up => self.switch_repetitions_count(true),
+      KeyCode::Down => self.switch_repetitions_count(false),
+      KeyCode::Char(c) => self.phrase.push(c),
+      KeyCode::Backspace => {
+        self.phrase.pop();
+      }
+      _ => return false,
     }
+
+    true
   }
 }
 
 impl Screen for RepeatScreen {
   fn is_running(&self) -> bool {
-    true
+    self
+      .session
+      .as_ref()
+      .is_some_and(|session| session.is_running())
+  }
+
+  /// Only the drilled phrase is remapped, not the phrase entered in the setup
+  fn accepts_remapped_input(&self) -> bool {
+    self.session.is_some() && self.result.is_none()
   }
 
   fn increment_time_secs(&mut self) {}
@@ -99,12 +139,13 @@ impl Screen for RepeatScreen {
     &self.config
   }
 
+  /// Drill has no timer, the completed drill is saved by the `stop`
   fn get_remaining_time(&self) -> usize {
     0
   }
 
   fn get_screen_name(&self) -> String {
-    String::from("Repeat")
+    String::from("Drill")
   }
 
   fn get_next_screen(&self) -> Option<ActiveScreenEnum> {
@@ -115,27 +156,76 @@ impl Screen for RepeatScreen {
     Some(ActiveScreenEnum::Typing)
   }
 
-  /// Resets all necessary properties
+  /// Saves the result of the completed drill
+  fn stop(&mut self, storage_handler: &mut StorageHandler) {
+    if self.result.is_some() {
+      return;
+    }
+
+    if let Some(session) = self
+      .session
+      .as_ref()
+      .filter(|session| session.is_completed())
+    {
+      let result = session.get_result();
+
+      storage_handler.insert_drill_result(result.clone());
+
+      self.result = Some(result);
+      self.is_popup_visible = true;
+    }
+  }
+
+  fn is_popup_visible(&self) -> bool {
+    self.is_popup_visible
+  }
+
+  /// Returns to the drill setup (the phrase is kept)
   fn reset(&mut self) {
-    self.mistake_handler = MistakeHandler::new();
-    self.cursor_index = 0;
-    self.input = String::new();
-    let app_config = self.config.borrow();
-    self.generated_text = Generator::generate_repeated_word(&app_config);
+    self.session = None;
+    self.result = None;
+    self.is_popup_visible = false;
+  }
+
+  fn handle_control_events(&mut self, key_event: KeyEvent) -> bool {
+    match key_event.code {
+      // Clears the phrase to enter a new one
+      KeyCode::Char('w') if self.session.is_none() => {
+        self.phrase.clear();
+        true
+      }
+      KeyCode::Char('w') | KeyCode::Char('h') if self.is_running() => {
+        if let Some(session) = self.session.as_mut() {
+          session.press(Keystroke::DeleteWord);
+        }
+
+        true
+      }
+      _ => false,
+    }
   }
 
   fn handle_events(&mut self, key_event: KeyEvent) -> bool {
-    if self.cursor_index > 0 && !self.is_running() {
+    if self.result.is_some() {
+      if key_event.code == KeyCode::Esc && self.is_popup_visible {
+        self.is_popup_visible = false;
+        return true;
+      }
+
       return false;
     }
 
+    let Some(session) = self.session.as_mut() else {
+      return self.handle_setup_events(key_event);
+    };
+
     match key_event.code {
       KeyCode::Char(c) => {
-        self.move_cursor_forward_with(c);
+        session.press(Keystroke::Char(c));
         true
       }
       KeyCode::Backspace => {
-        self.move_cursor_backward();
+        session.press(Keystroke::Backspace);
         true
       }
       _ => false,
@@ -188,11 +278,36 @@ impl Screen for RepeatScreen {
       "esc",
       TukaiLayoutColorTypeEnum::Secondary,
     ));
-    instruction_widget.add_instruction(Instruction::new(
-      "Reset",
-      "ctrl-r",
-      TukaiLayoutColorTypeEnum::Secondary,
-    ));
+
+    if self.session.is_none() {
+      instruction_widget.add_instruction(Instruction::new(
+        "Start",
+        "enter",
+        TukaiLayoutColorTypeEnum::Secondary,
+      ));
+      instruction_widget.add_instruction(Instruction::new(
+        "Random word",
+        "tab",
+        TukaiLayoutColorTypeEnum::Secondary,
+      ));
+      instruction_widget.add_instruction(Instruction::new(
+        "Clear",
+        "ctrl-w",
+        TukaiLayoutColorTypeEnum::Secondary,
+      ));
+      instruction_widget.add_instruction(Instruction::new(
+        "Repetitions",
+        "↑/↓",
+        TukaiLayoutColorTypeEnum::Secondary,
+      ));
+    } else {
+      instruction_widget.add_instruction(Instruction::new(
+        "Setup",
+        "ctrl-r",
+        TukaiLayoutColorTypeEnum::Secondary,
+      ));
+    }
+
     instruction_widget.add_instruction(Instruction::new(
       "Layout",
       "ctrl-s",
@@ -232,101 +347,145 @@ impl Screen for RepeatScreen {
 
   /// Renders a popup screen
   ///
-  /// Used after the run is completed
-  fn render_popup(&self, _frame: &mut Frame) {}
-}
-
-impl RepeatScreen {
-  /// Validates an inserted char
-  ///
-  /// If it is not valid, insert it into the set of mistakes
-  fn validate_input_char(&mut self, inserted_char: char) {
-    if let Some(generated_char) = self.generated_text.chars().nth(self.cursor_index) {
-      if generated_char != inserted_char {
-        self
-          .mistake_handler
-          .add_to_mistakes_indexes(self.cursor_index);
-      }
-    }
-  }
-
-  /// Moves the cursor position forward
-  ///
-  /// Also validates a char
-  fn move_cursor_forward_with(&mut self, c: char) {
-    self.validate_input_char(c);
-    self.input.push(c);
-    self.cursor_index += 1;
-  }
-
-  /// Moves the cursor position backward
-  ///
-  /// Remove the incorrect symbol from the set if its exists
-  fn move_cursor_backward(&mut self) {
-    if self.input.pop().is_none() {
+  /// Used after the drill is completed
+  fn render_popup(&self, frame: &mut Frame) {
+    let Some(result) = &self.result else {
       return;
-    }
+    };
 
-    self.cursor_index -= 1;
+    let app_config = self.config.borrow();
+    let app_layout = app_config.get_layout();
+    let area = frame.area();
 
-    if self.mistake_handler.is_char_mistaken(self.cursor_index) {
-      self
-        .mistake_handler
-        .remove_from_mistakes_indexes(self.cursor_index);
-    }
-  }
+    let block = Block::bordered()
+      .style(app_config.get_bg_color())
+      .border_type(BorderType::Rounded)
+      .border_style(Style::new().fg(app_layout.get_primary_color()));
 
-  // Deletes the last word form the input.
-  // Handles trailing spaces and updates mistakes.
-  #[allow(unused)]
-  pub fn delete_last_word(&mut self) {
-    if self.input.is_empty() {
-      return;
-    }
+    let primary_color = app_layout.get_primary_color();
 
-    let original_input_len = self.input.len();
+    let get_line = |title: &'static str, value: String| {
+      Line::from(vec![Span::from(title), Span::from(value).bold()])
+        .style(Style::default().fg(primary_color.to_dark()))
+    };
 
-    // Find the end of the actual contnet (trim trailing whitespace for logic)
-    let trimmed_end_len = self.input.trim_end().len();
+    let best_wpm = result
+      .repetitions
+      .iter()
+      .map(|repetition| repetition.wpm)
+      .max()
+      .unwrap_or(0);
+
+    let (first_wpm, last_wpm) = (
+      result
+        .repetitions
+        .first()
+        .map_or(0, |repetition| repetition.wpm),
+      result
+        .repetitions
+        .last()
+        .map_or(0, |repetition| repetition.wpm),
+    );
+
+    let lines = vec![
+      Line::from(vec![
+        Span::from("🔥 Average WPM: "),
+        Span::from(result.get_average_wpm().to_string()).bold(),
+      ])
+      .style(Style::default().fg(primary_color)),
+      Line::from(vec![
+        Span::from("🎯 Accuracy: "),
+        Span::from(format!("{}%", result.get_average_accuracy())).bold(),
+      ])
+      .style(Style::default().fg(primary_color)),
+      get_line("🏆 Best WPM: ", best_wpm.to_string()),
+      get_line(
+        "📈 Improvement: ",
+        format!(
+          "{first_wpm} → {last_wpm} WPM ({:+})",
+          result.get_wpm_improvement()
+        ),
+      ),
+      get_line("🔁 Repetitions: ", result.repetitions.len().to_string()),
+      Line::from(""),
+      Line::from(vec![
+        Span::from("Drill again").style(Style::default().fg(primary_color)),
+        Span::from(" ctrl-r").style(Style::default().fg(primary_color).bold()),
+      ]),
+    ];
+
+    // Text lines with the vertical padding and borders
+    let popup_height = lines.len() as u16 + 4;
+
+    let p = Paragraph::new(Text::from(lines))
+      .block(block.padding(Padding::vertical(1)))
+      .alignment(Alignment::Center)
+      .centered();
 
-    if trimmed_end_len == 0 {
-      // Input was all spaces
-      for i in 0..original_input_len {
-        self.mistake_handler.remove_from_mistakes_indexes(i);
-      }
-      self.input.clear();
-      self.cursor_index = 0;
-      return;
-    }
+    let vertical = Layout::vertical([Constraint::Length(popup_height)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Length(44)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
 
-    // Find the last space before the last word in the trimmed part
-    let last_word_start_idx = match self.input[..trimmed_end_len].rfind(' ') {
-      Some(space_idx) => space_idx + 1, // Word starts after the space
-      None => 0,                        // No space found, word starts at the beginning
-    };
+    frame.render_widget(Clear, area);
+    frame.render_widget(p, area);
+  }
+}
 
-    for i in last_word_start_idx..original_input_len {
-      self.mistake_handler.remove_from_mistakes_indexes(i);
+impl RepeatScreen {
+  /// Returns the lines of the drill setup
+  ///
+  /// Shows the last drill of the same phrase (if any).
+  fn get_setup_lines(&self, layout: &TukaiLayout) -> Vec<Line<'_>> {
+    let primary_color = layout.get_primary_color();
+    let text_color = layout.get_text_color();
+
+    let mut lines = vec![
+      Line::from(vec![
+        Span::from("✏️ Phrase: ").style(Style::default().fg(text_color)),
+        Span::from(self.phrase.as_str()).style(Style::default().fg(primary_color).bold()),
+        Span::from(" ").style(Style::default().bg(layout.get_text_current_bg_color())),
+      ]),
+      Line::from(vec![
+        Span::from("🔁 Repetitions: ").style(Style::default().fg(text_color)),
+        Span::from(self.repetitions_count.to_string())
+          .style(Style::default().fg(primary_color).bold()),
+      ]),
+    ];
+
+    let storage_handler = self.storage_handler.borrow();
+
+    let last_result = storage_handler
+      .get_drill_results()
+      .iter()
+      .rev()
+      .find(|result| result.phrase == self.phrase.trim());
+
+    if let Some(last_result) = last_result {
+      lines.push(
+        Line::from(format!(
+          "📜 Last drill: {} WPM, {}% ({})",
+          last_result.get_average_wpm(),
+          last_result.get_average_accuracy(),
+          last_result.get_created_at_pretty()
+        ))
+        .style(Style::default().fg(text_color.to_dark())),
+      );
     }
 
-    self.input.truncate(last_word_start_idx);
-    self.cursor_index = self.input.len();
+    lines
   }
 
-  /// Prepares and returns a paragraph.
+  /// Returns the lines of the running drill
   ///
-  /// If popup window is showed then colors converts to dark.
-  pub fn get_paragraph(&self, layout: &TukaiLayout) -> Paragraph {
-    let mut lines = Vec::new();
-
+  /// Highlights the typed phrase and lists the WPM of every repetition.
+  fn get_drill_lines(&self, layout: &TukaiLayout, session: &DrillSession) -> Vec<Line<'_>> {
     let (primary_color, error_color, text_color) = {
-      let colors = {
-        (
-          layout.get_primary_color(),
-          layout.get_error_color(),
-          layout.get_text_color(),
-        )
-      };
+      let colors = (
+        layout.get_primary_color(),
+        layout.get_error_color(),
+        layout.get_text_color(),
+      );
 
       if self.is_popup_visible() {
         (colors.0.to_dark(), colors.1.to_dark(), colors.2.to_dark())
@@ -335,48 +494,78 @@ impl RepeatScreen {
       }
     };
 
-    let repeat_word_line =
-      Line::from("🔄 Repeat word").style(Style::default().fg(layout.get_primary_color()));
+    let input_len = session.get_input().chars().count();
 
-    let text_line = self
-      .generated_text
+    let mut phrase_spans = session
+      .get_phrase()
       .chars()
       .enumerate()
       .map(|(i, c)| {
-        if i == self.cursor_index {
-          Span::from(c.to_string()).style(
+        let span = Span::from(c.to_string());
+
+        if i == input_len && !session.is_completed() {
+          span.style(
             Style::default()
               .fg(layout.get_text_current_color())
               .bg(layout.get_text_current_bg_color()),
           )
-        } else if i < self.cursor_index {
-          if self.input.chars().nth(i) == Some(c) {
-            Span::from(c.to_string()).style(Style::default().fg(primary_color))
-          } else {
-            Span::from(c.to_string()).style(
-              Style::default()
-                .fg(error_color)
-                .add_modifier(Modifier::CROSSED_OUT),
-            )
-          }
+        } else if i >= input_len {
+          span.style(Style::default().fg(text_color))
+        } else if session.is_char_mistaken(i) {
+          span.style(
+            Style::default()
+              .fg(error_color)
+              .add_modifier(Modifier::CROSSED_OUT),
+          )
         } else {
-          Span::from(c.to_string()).style(Style::default().fg(text_color))
+          span.style(Style::default().fg(primary_color))
         }
       })
-      .collect::<Line>();
+      .collect::<Vec<Span>>();
 
-    let empty_line = Line::from(Vec::new());
+    // Characters typed beyond the phrase
+    let extra_chars = session
+      .get_input()
+      .chars()
+      .skip(session.get_phrase().chars().count())
+      .collect::<String>();
 
-    lines.push(repeat_word_line);
+    if !extra_chars.is_empty() {
+      phrase_spans.push(Span::from(extra_chars).style(Style::default().fg(error_color.to_dark())));
+    }
 
-    lines.push(empty_line.clone());
+    let repetitions_wpm = session
+      .get_repetitions()
+      .iter()
+      .map(|repetition| repetition.wpm.to_string())
+      .collect::<Vec<String>>()
+      .join(" ");
+
+    vec![
+      Line::from(format!(
+        "🔁 {}/{}",
+        session.get_repetitions().len(),
+        session.get_repetitions_count()
+      ))
+      .style(Style::default().fg(text_color)),
+      Line::from(phrase_spans),
+      Line::from(format!("📈 WPM: {repetitions_wpm}")).style(Style::default().fg(text_color)),
+    ]
+  }
 
-    lines.push(text_line);
+  /// Prepares and returns a paragraph.
+  ///
+  /// Shows the setup or the running drill.
+  pub fn get_paragraph(&self, layout: &TukaiLayout) -> Paragraph<'_> {
+    let drill_line = Line::from("🔄 Drill").style(Style::default().fg(layout.get_primary_color()));
 
-    lines.push(empty_line);
+    let mut lines = vec![drill_line, Line::from(Vec::new())];
 
-    let text = Text::from(lines);
+    match &self.session {
+      Some(session) => lines.extend(self.get_drill_lines(layout, session)),
+      None => lines.extend(self.get_setup_lines(layout)),
+    }
 
-    Paragraph::new(text).wrap(Wrap { trim: true })
+    Paragraph::new(Text::from(lines)).wrap(Wrap { trim: true })
   }
 }
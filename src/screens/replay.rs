@@ -0,0 +1,367 @@
+use std::{cell::RefCell, rc::Rc, time::Instant};
+
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::{Alignment, Rect},
+  style::{Style, Stylize},
+  text::{Line, Span, Text},
+  widgets::{Block, BorderType, Borders, Padding, Paragraph, Wrap},
+};
+
+use crate::{
+  config::{TukaiConfig, TukaiLayoutColorTypeEnum},
+  engine::TypingSession,
+  screens::{Instruction, InstructionWidget, Screen, ToDark},
+  storage::{
+    keystroke_log::{Keystroke, LoggedKeystroke},
+    stats::Stat,
+  },
+};
+
+use super::{ActiveScreenEnum, typing::TypingScreen};
+
+/// Speeds (multipliers of the real time) of the replay
+const REPLAY_SPEEDS: [f64; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+
+/// Index of the real time speed
+const REAL_TIME_SPEED_INDEX: usize = 2;
+
+pub struct ReplayScreen {
+  /// Application config
+  config: Rc<RefCell<TukaiConfig>>,
+
+  /// Replayed run
+  stat: Stat,
+
+  /// Typing screen the keystrokes are applied on (renders the text)
+  typing_screen: TypingScreen,
+
+  /// Logged keystrokes of the run
+  keystrokes: Vec<LoggedKeystroke>,
+
+  /// Count of the applied keystrokes
+  applied_count: usize,
+
+  /// Replayed time of the run in milliseconds
+  replay_ms: f64,
+
+  /// Index of the replay speed
+  speed_index: usize,
+
+  /// Replay is paused
+  is_paused: bool,
+
+  /// Time of the last replay update
+  last_update_at: Instant,
+}
+
+impl ReplayScreen {
+  /// Creates the replay of the run
+  ///
+  /// The run must have the logged keystrokes.
+  pub fn new(config: Rc<RefCell<TukaiConfig>>, stat: Stat) -> Self {
+    let keystrokes = stat
+      .get_keystroke_log()
+      .map(|keystroke_log| keystroke_log.get_keystrokes())
+      .unwrap_or_default();
+
+    let typing_screen = ReplayScreen::get_typing_screen(&config, &stat);
+
+    Self {
+      config,
+
+      stat,
+
+      typing_screen,
+
+      keystrokes,
+
+      applied_count: 0,
+
+      replay_ms: 0.0,
+
+      speed_index: REAL_TIME_SPEED_INDEX,
+
+      is_paused: false,
+
+      last_update_at: Instant::now(),
+    }
+  }
+
+  /// Creates the typing screen with the text of the run
+  fn get_typing_screen(config: &Rc<RefCell<TukaiConfig>>, stat: &Stat) -> TypingScreen {
+    let mut typing_screen = TypingScreen::new(Rc::clone(config));
+
+    if let Some(keystroke_log) = stat.get_keystroke_log() {
+      typing_screen.session = TypingSession::new(
+        keystroke_log.get_text().clone(),
+        stat.get_typing_duration().clone(),
+      )
+      .with_difficulty(stat.get_difficulty())
+      .with_visibility(stat.get_visibility());
+    }
+
+    typing_screen
+  }
+
+  /// Verifies if all the keystrokes are replayed
+  fn is_finished(&self) -> bool {
+    self.applied_count >= self.keystrokes.len()
+  }
+
+  /// Applies the next keystroke
+  fn apply_next_keystroke(&mut self) {
+    if let Some(logged_keystroke) = self.keystrokes.get(self.applied_count) {
+      self
+        .typing_screen
+        .session
+        .apply_keystroke(logged_keystroke.keystroke);
+      self.replay_ms = self.replay_ms.max(logged_keystroke.elapsed_ms as f64);
+      self.applied_count += 1;
+    }
+  }
+
+  /// Returns the last applied keystroke
+  fn get_last_keystroke(&self) -> Option<&LoggedKeystroke> {
+    self
+      .applied_count
+      .checked_sub(1)
+      .and_then(|index| self.keystrokes.get(index))
+  }
+}
+
+impl Screen for ReplayScreen {
+  fn increment_time_secs(&mut self) {}
+
+  fn get_config(&self) -> &Rc<RefCell<TukaiConfig>> {
+    &self.config
+  }
+
+  fn get_screen_name(&self) -> String {
+    String::from("Replay")
+  }
+
+  fn get_remaining_time(&self) -> usize {
+    0
+  }
+
+  fn get_previous_screen(&self) -> Option<ActiveScreenEnum> {
+    Some(ActiveScreenEnum::Stats)
+  }
+
+  /// Restarts the replay
+  fn reset(&mut self) {
+    self.typing_screen = ReplayScreen::get_typing_screen(&self.config, &self.stat);
+    self.applied_count = 0;
+    self.replay_ms = 0.0;
+    self.is_paused = false;
+    self.last_update_at = Instant::now();
+  }
+
+  fn is_animated(&self) -> bool {
+    !self.is_paused && !self.is_finished()
+  }
+
+  /// Replays the keystrokes pressed until the replayed time
+  fn on_frame(&mut self) {
+    let elapsed_ms = self.last_update_at.elapsed().as_millis() as f64;
+    self.last_update_at = Instant::now();
+
+    if !self.is_animated() {
+      return;
+    }
+
+    self.replay_ms += elapsed_ms * REPLAY_SPEEDS[self.speed_index];
+
+    while self
+      .keystrokes
+      .get(self.applied_count)
+      .is_some_and(|logged_keystroke| logged_keystroke.elapsed_ms as f64 <= self.replay_ms)
+    {
+      self.apply_next_keystroke();
+    }
+  }
+
+  fn handle_events(&mut self, key_event: KeyEvent) -> bool {
+    match key_event.code {
+      KeyCode::Char(' ') => {
+        self.is_paused = !self.is_paused;
+        self.last_update_at = Instant::now();
+      }
+      KeyCode::Char('+') | KeyCode::Up => {
+        self.speed_index = (self.speed_index + 1).min(REPLAY_SPEEDS.len() - 1);
+      }
+      KeyCode::Char('-') | KeyCode::Down => {
+        self.speed_index = self.speed_index.saturating_sub(1);
+      }
+      KeyCode::Char('n') if self.is_paused => self.apply_next_keystroke(),
+      _ => return false,
+    }
+
+    true
+  }
+
+  fn render(&self, frame: &mut Frame, area: Rect) {
+    let app_config = self.config.borrow();
+    let app_layout = app_config.get_layout();
+
+    let primary_color = app_layout.get_primary_color();
+    let error_color = app_layout.get_error_color();
+    let text_color = app_layout.get_text_color();
+
+    let horizontal_padding = if (area.width / 3) < 8 {
+      2
+    } else {
+      area.width / 3 - 8
+    };
+
+    let block = Block::new()
+      .title(self.get_title())
+      .title_style(Style::default().fg(primary_color))
+      .title_bottom(format!(
+        " {} WPM | {}% | {} ",
+        self.stat.get_average_wpm(),
+        self.stat.get_accuracy(),
+        self.stat.get_created_at_pretty()
+      ))
+      .title_alignment(Alignment::Center)
+      .style(app_config.get_bg_color())
+      .borders(Borders::ALL)
+      .border_type(BorderType::Rounded)
+      .border_style(Style::default().fg(primary_color))
+      .padding(Padding::new(
+        horizontal_padding,
+        horizontal_padding,
+        (area.height / 2).saturating_sub(5),
+        0,
+      ));
+
+    let replay_state = if self.is_finished() {
+      "⏹"
+    } else if self.is_paused {
+      "⏸"
+    } else {
+      "▶"
+    };
+
+    let mistakes_count = self.keystrokes[..self.applied_count]
+      .iter()
+      .filter(|logged_keystroke| !logged_keystroke.is_correct)
+      .count();
+
+    let header_line = Line::from(vec![
+      Span::from(format!(
+        "{replay_state} {}x",
+        REPLAY_SPEEDS[self.speed_index]
+      ))
+      .style(Style::default().fg(primary_color).bold()),
+      Span::from(format!(
+        "  ⏱ {:.1}s / {}s",
+        self.replay_ms / 1000.0,
+        self.stat.get_typing_duration().as_seconds()
+      ))
+      .style(Style::default().fg(text_color)),
+      Span::from(format!(
+        "  ⌨ {}/{}",
+        self.applied_count,
+        self.keystrokes.len()
+      ))
+      .style(Style::default().fg(text_color)),
+      Span::from(format!("  ✗ {mistakes_count}")).style(Style::default().fg(error_color)),
+    ]);
+
+    let last_keystroke_line = match self.get_last_keystroke() {
+      Some(logged_keystroke) => {
+        let key = match logged_keystroke.keystroke {
+          Keystroke::Char(' ') => String::from("space"),
+          Keystroke::Char(c) => format!("'{c}'"),
+          Keystroke::Backspace => String::from("backspace"),
+          Keystroke::DeleteWord => String::from("delete word"),
+        };
+
+        let (result, result_color) = if logged_keystroke.is_correct {
+          ("✓", primary_color)
+        } else {
+          ("✗", error_color)
+        };
+
+        Line::from(vec![
+          Span::from("Last key: ").style(Style::default().fg(text_color.to_dark())),
+          Span::from(key).style(Style::default().fg(text_color).bold()),
+          Span::from(format!(" {result}")).style(Style::default().fg(result_color)),
+        ])
+      }
+      None => Line::default(),
+    };
+
+    let text = Text::from(vec![
+      header_line,
+      Line::default(),
+      self.typing_screen.get_text_line(&app_layout),
+      Line::default(),
+      last_keystroke_line,
+    ]);
+
+    let p = Paragraph::new(text)
+      .wrap(Wrap { trim: true })
+      .block(block)
+      .alignment(Alignment::Left);
+
+    frame.render_widget(p, area);
+  }
+
+  fn render_instructions(&self, frame: &mut Frame, area: Rect) {
+    let app_config = self.config.borrow();
+    let app_layout = app_config.get_layout();
+
+    let mut instruction_widget = InstructionWidget::new(&app_layout);
+
+    instruction_widget.add_instruction(Instruction::new(
+      "Exit",
+      "esc",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+    instruction_widget.add_instruction(Instruction::new(
+      if self.is_paused { "Resume" } else { "Pause" },
+      "space",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Speed",
+      "+ -",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+
+    if self.is_paused {
+      instruction_widget.add_instruction(Instruction::new(
+        "Next key",
+        "n",
+        TukaiLayoutColorTypeEnum::Secondary,
+      ));
+    }
+
+    instruction_widget.add_instruction(Instruction::new(
+      "Restart",
+      "ctrl-r",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Stats",
+      "ctrl-h",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+
+    let block = Block::new().padding(Padding::new(0, 0, area.height / 2, 0));
+
+    let instructions = instruction_widget
+      .get_paragraph()
+      .block(block)
+      .alignment(Alignment::Center)
+      .style(app_config.get_bg_color());
+
+    frame.render_widget(instructions, area);
+  }
+
+  fn render_popup(&self, _frame: &mut Frame) {}
+}
@@ -1,36 +1,130 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+  cell::{Cell as StdCell, RefCell},
+  collections::BTreeMap,
+  rc::Rc,
+};
+
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate};
 
 use crate::{
   config::{TukaiConfig, TukaiLayoutColorTypeEnum},
-  screens::{Instruction, InstructionWidget, Screen, ToDark},
+  keyboard::{KeyRow, NUMBER_ROW_KEYS},
+  screens::{Instruction, InstructionWidget, Screen, ScreenAction, ToDark},
   storage::{
+    achievements::{Achievement, UnlockedAchievement},
+    goals::{DailyGoals, DailyProgress},
+    key_stats::{KeyCounter, KeyStats},
     stats::Stat,
-    storage_handler::{StatOverview, StorageHandler},
+    stats_filter::StatsFilter,
+    storage_handler::{
+      CHART_ROLLING_AVERAGE_WINDOWS, ChartData, StatOverview, StatsAggregates, StorageHandler,
+    },
   },
 };
 
 use ratatui::{
   Frame,
-  crossterm::event::KeyEvent,
-  layout::{Alignment, Constraint, Direction, Layout, Rect},
-  style::{Style, Stylize},
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::{Alignment, Constraint, Direction, Flex, Layout, Rect},
+  style::{Color, Style, Stylize},
   symbols,
   text::{Line, Span},
   widgets::{
-    Axis, Block, BorderType, Borders, Cell, Chart, Dataset, GraphType, Padding, Paragraph, Row,
-    Table,
+    Axis, Block, BorderType, Borders, Cell, Chart, Clear, Dataset, GraphType, LegendPosition,
+    Padding, Paragraph, Row, Table, TableState,
   },
 };
 
 use super::ActiveScreenEnum;
 
+/// Minimal accuracies of the good and the fair keys in the key heatmap
+const KEY_ACCURACY_THRESHOLDS: (f64, f64) = (97.0, 90.0);
+
+/// Count of the weakest keys listed under the key heatmap
+const WEAKEST_KEYS_COUNT: usize = 5;
+
+/// View displayed in the left part of the stats screen
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum StatsView {
+  // Last runs table and the WPM chart
+  History,
+
+  // Practice calendar and the daily goals
+  Progress,
+
+  // Unlocked and locked achievements
+  Achievements,
+
+  // Key heatmap and the fingers of the active keyboard layout
+  Keys,
+}
+
+impl StatsView {
+  /// Returns the next view (used for a switching)
+  fn next(&self) -> Self {
+    match self {
+      StatsView::History => StatsView::Progress,
+      StatsView::Progress => StatsView::Achievements,
+      StatsView::Achievements => StatsView::Keys,
+      StatsView::Keys => StatsView::History,
+    }
+  }
+
+  /// Returns the view name
+  fn get_name(&self) -> &'static str {
+    match self {
+      StatsView::History => "History",
+      StatsView::Progress => "Progress",
+      StatsView::Achievements => "Achievements",
+      StatsView::Keys => "Keys",
+    }
+  }
+}
+
 pub struct StatsScreen {
   config: Rc<RefCell<TukaiConfig>>,
+
+  // Application storage shared with the app
+  storage_handler: Rc<RefCell<StorageHandler>>,
+
+  // Selection and scroll offset of the last runs table
+  // Updated while rendering, so it's wrapped in the RefCell
+  last_runs_table_state: RefCell<TableState>,
+
+  // Count of the visible rows of the last runs table (page size)
+  last_runs_table_page_size: StdCell<usize>,
+
+  // Run detail popup is visible
+  is_popup_visible: bool,
+
+  // Active filter of the displayed stats
+  filter: StatsFilter,
+
+  // Active view switched with the tab key
+  view: StatsView,
+
+  // Action requested by the user
+  action: Option<ScreenAction>,
 }
 
 impl StatsScreen {
-  pub fn new(config: Rc<RefCell<TukaiConfig>>) -> Self {
-    Self { config }
+  pub fn new(
+    config: Rc<RefCell<TukaiConfig>>,
+    storage_handler: Rc<RefCell<StorageHandler>>,
+  ) -> Self {
+    let mut stats_screen = Self {
+      config,
+      storage_handler,
+      last_runs_table_state: RefCell::new(TableState::default()),
+      last_runs_table_page_size: StdCell::new(1),
+      is_popup_visible: false,
+      filter: StatsFilter::default(),
+      view: StatsView::History,
+      action: None,
+    };
+
+    stats_screen.select_run(0);
+    stats_screen
   }
 }
 
@@ -55,9 +149,72 @@ impl Screen for StatsScreen {
 
   fn reset(&mut self) {}
 
-  #[allow(dead_code)]
-  fn handle_events(&mut self, _key: KeyEvent) -> bool {
-    false
+  fn is_popup_visible(&self) -> bool {
+    self.is_popup_visible
+  }
+
+  fn take_action(&mut self) -> Option<ScreenAction> {
+    self.action.take()
+  }
+
+  fn handle_events(&mut self, key_event: KeyEvent) -> bool {
+    if self.is_popup_visible {
+      // Popup consumes all keys, so screens are not switched under it
+      match key_event.code {
+        KeyCode::Esc | KeyCode::Enter => self.is_popup_visible = false,
+        KeyCode::Char('g') => {
+          self.action = self
+            .get_selected_recorded_stat()
+            .map(|stat| ScreenAction::RaceGhost(Box::new(stat)));
+        }
+        KeyCode::Char('r') => {
+          self.action = self
+            .get_selected_recorded_stat()
+            .map(|stat| ScreenAction::Replay(Box::new(stat)));
+        }
+        _ => {}
+      }
+
+      return true;
+    }
+
+    if key_event.code == KeyCode::Tab {
+      self.view = self.view.next();
+
+      return true;
+    }
+
+    match self.view {
+      StatsView::Progress => return self.handle_progress_events(key_event),
+      StatsView::Achievements | StatsView::Keys => return false,
+      StatsView::History => {}
+    }
+
+    let selected_index = self.get_selected_run_index();
+    let page_size = self.last_runs_table_page_size.get().max(1);
+
+    match key_event.code {
+      KeyCode::Up | KeyCode::Char('k') => self.select_run(selected_index.saturating_sub(1)),
+      KeyCode::Down | KeyCode::Char('j') => self.select_run(selected_index + 1),
+      KeyCode::PageUp => self.select_run(selected_index.saturating_sub(page_size)),
+      KeyCode::PageDown => self.select_run(selected_index + page_size),
+      KeyCode::Home | KeyCode::Char('g') => self.select_run(0),
+      KeyCode::End | KeyCode::Char('G') => self.select_run(usize::MAX),
+      KeyCode::Enter => {
+        self.is_popup_visible = self.last_runs_table_state.borrow().selected().is_some();
+      }
+      KeyCode::Char('d') => self.update_filter(StatsFilter::switch_typing_duration),
+      KeyCode::Char('l') => {
+        let languages = self.storage_handler.borrow().get_stats_languages();
+        self.update_filter(|filter| filter.switch_language(&languages));
+      }
+      KeyCode::Char('m') => self.update_filter(StatsFilter::switch_mode),
+      KeyCode::Char('r') => self.update_filter(StatsFilter::switch_date_range),
+      KeyCode::Char('c') => self.update_filter(|filter| *filter = StatsFilter::default()),
+      _ => return false,
+    }
+
+    true
   }
 
   fn render_instructions(&self, frame: &mut Frame, area: Rect) {
@@ -76,6 +233,48 @@ impl Screen for StatsScreen {
       "ctrl-t",
       TukaiLayoutColorTypeEnum::Secondary,
     ));
+    instruction_widget.add_instruction(Instruction::new(
+      self.view.next().get_name(),
+      "tab",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+
+    match self.view {
+      StatsView::History => {
+        instruction_widget.add_instruction(Instruction::new(
+          "Navigate",
+          "↑↓ pgup pgdn",
+          TukaiLayoutColorTypeEnum::Secondary,
+        ));
+        instruction_widget.add_instruction(Instruction::new(
+          "Run detail",
+          "enter",
+          TukaiLayoutColorTypeEnum::Secondary,
+        ));
+        instruction_widget.add_instruction(Instruction::new(
+          "Filter duration/language/mode/range",
+          "d l m r",
+          TukaiLayoutColorTypeEnum::Secondary,
+        ));
+      }
+      StatsView::Progress => {
+        instruction_widget.add_instruction(Instruction::new(
+          "Goal minutes/runs/WPM",
+          "1 2 3",
+          TukaiLayoutColorTypeEnum::Secondary,
+        ));
+      }
+      StatsView::Achievements | StatsView::Keys => {}
+    }
+
+    if self.view == StatsView::History && self.filter.is_active() {
+      instruction_widget.add_instruction(Instruction::new(
+        "Clear filter",
+        "c",
+        TukaiLayoutColorTypeEnum::Secondary,
+      ));
+    }
+
     instruction_widget.add_instruction(Instruction::new(
       "Typing",
       "ctrl-h",
@@ -94,7 +293,7 @@ impl Screen for StatsScreen {
   }
 
   fn render(&self, frame: &mut Frame, area: Rect) {
-    let storage_handler = StorageHandler::new("tukai.bin").init().unwrap();
+    let stats_aggregates = self.get_stats_aggregates();
 
     let chunks = Layout::default()
       .direction(Direction::Horizontal)
@@ -108,40 +307,289 @@ impl Screen for StatsScreen {
 
     let right_widget = Layout::default()
       .direction(Direction::Vertical)
-      .constraints(vec![Constraint::Length(7), Constraint::Percentage(100)])
+      .constraints(vec![Constraint::Length(8), Constraint::Percentage(100)])
       .split(chunks[1]);
 
-    let last_runs_table_widget_data = storage_handler.get_data_stats_reversed();
-    let last_runs_table_widget = self.get_last_runs_table_widget(&last_runs_table_widget_data);
+    let best_score_widget = self.get_best_score_widget(&stats_aggregates.stats_best);
+    let stats_overview_widget = self.get_stats_overview_widget(&stats_aggregates.overview);
+
+    frame.render_widget(stats_overview_widget, right_widget[0]);
+    frame.render_widget(best_score_widget, right_widget[1]);
+
+    if self.view == StatsView::Progress {
+      let daily_goals = self.storage_handler.borrow().get_daily_goals().clone();
+
+      let practice_calendar_widget = self.get_practice_calendar_widget(
+        &stats_aggregates.daily_progress,
+        &daily_goals,
+        left_widget[0],
+      );
+      let daily_goals_widget =
+        self.get_daily_goals_widget(&stats_aggregates.daily_progress, &daily_goals);
+
+      frame.render_widget(practice_calendar_widget, left_widget[0]);
+      frame.render_widget(daily_goals_widget, left_widget[1]);
+      return;
+    }
 
-    let chart_widget_data = storage_handler.get_data_for_chart();
-    let chart_widget = self.get_chart_widget(&chart_widget_data);
+    if self.view == StatsView::Achievements {
+      let storage_handler = self.storage_handler.borrow();
+      let achievements_widget =
+        self.get_achievements_widget(storage_handler.get_unlocked_achievements());
 
-    let best_score_widget = self.get_best_score_widget(&storage_handler);
+      frame.render_widget(achievements_widget, chunks[0]);
+      return;
+    }
 
-    let chart_widget_data = storage_handler.get_data_for_overview();
-    let stats_overview_widget = self.get_stats_overview_widget(&chart_widget_data);
+    if self.view == StatsView::Keys {
+      let keyboard_layout = self.config.borrow().keyboard_layout;
+      let key_stats = self.storage_handler.borrow().get_key_stats(keyboard_layout);
 
-    frame.render_widget(last_runs_table_widget, left_widget[0]);
+      let key_heatmap_widget = self.get_key_heatmap_widget(&key_stats);
+      let fingers_widget = self.get_fingers_widget(&key_stats);
+
+      frame.render_widget(key_heatmap_widget, left_widget[0]);
+      frame.render_widget(fingers_widget, left_widget[1]);
+      return;
+    }
+
+    let last_runs_table_widget = self.get_last_runs_table_widget(&stats_aggregates.stats_reversed);
+    let chart_widget = self.get_chart_widget(&stats_aggregates.chart_data);
+
+    // Table area without borders, header and its margin
+    self
+      .last_runs_table_page_size
+      .set(left_widget[0].height.saturating_sub(4) as usize);
+
+    frame.render_stateful_widget(
+      last_runs_table_widget,
+      left_widget[0],
+      &mut self.last_runs_table_state.borrow_mut(),
+    );
     frame.render_widget(chart_widget, left_widget[1]);
-    frame.render_widget(stats_overview_widget, right_widget[0]);
-    frame.render_widget(best_score_widget, right_widget[1]);
   }
 
-  fn render_popup(&self, _frame: &mut Frame) {}
+  /// Renders a popup with the selected run detail
+  fn render_popup(&self, frame: &mut Frame) {
+    let stats_aggregates = self.get_stats_aggregates();
+    let stats = &stats_aggregates.stats_reversed;
+
+    let Some(stat) = stats.get(self.get_selected_run_index()) else {
+      return;
+    };
+
+    let app_config = self.config.borrow();
+    let app_layout = app_config.get_layout();
+
+    let primary_color = app_layout.get_primary_color();
+    let text_color = app_layout.get_text_color();
+
+    let get_detail_line = |title: &'static str, value: Line<'static>| {
+      let mut spans = vec![Span::from(title).style(Style::default().fg(text_color))];
+      spans.extend(value.spans);
+      Line::from(spans)
+    };
+
+    let get_value =
+      |value: String| Line::from(value).style(Style::default().fg(primary_color).bold());
+
+    let language = if stat.get_language().is_empty() {
+      String::from("-")
+    } else {
+      stat.get_language().clone()
+    };
+
+    let mut text = vec![
+      Line::default(),
+      get_detail_line(" 📅 Date: ", get_value(stat.get_created_at_pretty())),
+      get_detail_line(" 🌍 Language: ", get_value(language)),
+      get_detail_line(" 🎮 Mode: ", get_value(stat.get_mode().to_string())),
+      get_detail_line(
+        " 🎚 Difficulty: ",
+        get_value(stat.get_difficulty().to_string()),
+      ),
+      get_detail_line(
+        " 👁 Visibility: ",
+        get_value(stat.get_visibility().to_string()),
+      ),
+      get_detail_line(" ⏳ Duration: ", stat.get_duration_pretty()),
+      get_detail_line(
+        " 🔥 Average WPM: ",
+        get_value(stat.get_average_wpm().to_string()),
+      ),
+      get_detail_line(
+        " 🎯 Accuracy: ",
+        get_value(format!("{}%", stat.get_accuracy())),
+      ),
+      get_detail_line(" 🥩 Raw WPM: ", get_value(stat.get_raw_wpm().to_string())),
+    ];
+
+    if let Some(metrics) = stat.get_metrics() {
+      text.extend([
+        get_detail_line(
+          " 📈 Consistency: ",
+          get_value(stat.get_consistency_pretty()),
+        ),
+        get_detail_line(
+          " ✏️ Errors: ",
+          get_value(format!(
+            "{} corrected / {} uncorrected",
+            metrics.corrected_errors, metrics.uncorrected_errors
+          )),
+        ),
+        get_detail_line(
+          " 🔤 Chars: ",
+          get_value(format!(
+            "{} extra / {} missed",
+            metrics.extra_chars, metrics.missed_chars
+          )),
+        ),
+        get_detail_line(
+          " ⌨️ Keystrokes: ",
+          get_value(metrics.keystrokes.to_string()),
+        ),
+        get_detail_line(
+          " ⏱️ First error: ",
+          get_value(metrics.get_time_to_first_error_pretty()),
+        ),
+      ]);
+    }
+
+    if stat.get_flags().is_flagged() {
+      text.push(get_detail_line(
+        " ⚠️ Flagged: ",
+        Line::from(stat.get_flags().get_pretty())
+          .style(Style::default().fg(app_layout.get_error_color()).bold()),
+      ));
+    }
+
+    // Text lines with the bottom spacing and borders
+    let popup_height = text.len() as u16 + 3;
+
+    let block = Block::bordered()
+      .title(format!(
+        " Run #{} ",
+        stats.len() - self.get_selected_run_index()
+      ))
+      .title_style(Style::new().fg(primary_color))
+      .title_bottom(
+        Line::from(if stat.get_keystroke_log().is_some() {
+          " replay r | race ghost g | close esc "
+        } else {
+          " close esc "
+        })
+        .right_aligned(),
+      )
+      .style(app_config.get_bg_color())
+      .border_type(BorderType::Rounded)
+      .border_style(Style::new().fg(primary_color));
+
+    let p = Paragraph::new(text).block(block).alignment(Alignment::Left);
+
+    let vertical = Layout::vertical([Constraint::Length(popup_height)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Length(48)]).flex(Flex::Center);
+    let [area] = vertical.areas(frame.area());
+    let [area] = horizontal.areas(area);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(p, area);
+  }
 }
 
 impl StatsScreen {
+  /// Returns the stats aggregates for the active filter
+  fn get_stats_aggregates(&self) -> Rc<StatsAggregates> {
+    self
+      .storage_handler
+      .borrow()
+      .get_stats_aggregates(&self.filter)
+  }
+
+  /// Updates the filter, then selects the newest run
+  fn update_filter<F: FnOnce(&mut StatsFilter)>(&mut self, update: F) {
+    update(&mut self.filter);
+    self.select_run(0);
+  }
+
+  /// Returns the active filter description
+  fn get_filter_pretty(&self) -> String {
+    format!(
+      " ⏳ {} | 🌍 {} | 🎮 {} | 📅 {} ",
+      self
+        .filter
+        .typing_duration
+        .as_ref()
+        .map_or(String::from("all"), |typing_duration| format!(
+          "{}s",
+          typing_duration.as_seconds()
+        )),
+      self.filter.language.as_deref().unwrap_or("all"),
+      self
+        .filter
+        .mode
+        .map_or(String::from("all"), |mode| mode.to_string()),
+      self.filter.date_range,
+    )
+  }
+
+  /// Handles key events of the progress view
+  ///
+  /// Switches the daily goals.
+  fn handle_progress_events(&mut self, key_event: KeyEvent) -> bool {
+    let switch_goal: fn(&mut DailyGoals) = match key_event.code {
+      KeyCode::Char('1') => DailyGoals::switch_minutes_per_day,
+      KeyCode::Char('2') => DailyGoals::switch_runs_per_day,
+      KeyCode::Char('3') => DailyGoals::switch_target_wpm,
+      _ => return false,
+    };
+
+    let mut storage_handler = self.storage_handler.borrow_mut();
+
+    let mut daily_goals = storage_handler.get_daily_goals().clone();
+    switch_goal(&mut daily_goals);
+    storage_handler.set_daily_goals(daily_goals);
+
+    true
+  }
+
+  /// Returns the index of the selected run (newest first)
+  fn get_selected_run_index(&self) -> usize {
+    self.last_runs_table_state.borrow().selected().unwrap_or(0)
+  }
+
+  /// Returns the selected run if its keystrokes were recorded
+  fn get_selected_recorded_stat(&self) -> Option<Stat> {
+    self
+      .get_stats_aggregates()
+      .stats_reversed
+      .get(self.get_selected_run_index())
+      .filter(|stat| stat.get_keystroke_log().is_some())
+      .cloned()
+  }
+
+  /// Selects the run in the last runs table
+  ///
+  /// Index is clamped to the count of the runs.
+  fn select_run(&mut self, index: usize) {
+    let stats_count = self.get_stats_aggregates().stats_reversed.len();
+
+    let selected = if stats_count == 0 {
+      None
+    } else {
+      Some(index.min(stats_count - 1))
+    };
+
+    self.last_runs_table_state.get_mut().select(selected);
+  }
+
   /// Returns the right widget (Best score)
-  fn get_best_score_widget(&self, storage_handler: &StorageHandler) -> Table {
+  fn get_best_score_widget<'a>(&self, stats: &[Stat]) -> Table<'a> {
     let app_config = self.config.borrow();
     let app_layout = &app_config.get_layout();
 
     let primary_color = app_layout.get_primary_color();
     let text_color = app_layout.get_text_color();
 
-    let stats = storage_handler.get_data_stats_best();
-
     let block = Block::new()
       .title(" Best score ")
       .title_style(Style::new().fg(primary_color))
@@ -186,9 +634,17 @@ impl StatsScreen {
     let primary_color = app_layout.get_primary_color();
     let text_color = app_layout.get_text_color();
 
+    let position = self
+      .last_runs_table_state
+      .borrow()
+      .selected()
+      .map_or(0, |selected| selected + 1);
+
     let block = Block::new()
       .title(self.get_title())
       .title_style(Style::new().fg(primary_color))
+      .title_bottom(Line::from(self.get_filter_pretty()).left_aligned())
+      .title_bottom(Line::from(format!(" {}/{} ", position, stats.len())).right_aligned())
       .borders(Borders::ALL)
       .border_style(Style::default().fg(primary_color))
       .border_type(BorderType::Rounded);
@@ -201,20 +657,25 @@ impl StatsScreen {
         let duration_pretty = stat.get_duration_pretty();
 
         Row::new(vec![
+          Cell::from(stat.get_created_at_pretty()).style(default_cell_style),
           Cell::from(duration_pretty),
           Cell::from(stat.get_average_wpm().to_string()).style(default_cell_style),
           Cell::from(format!("{}%", stat.get_accuracy())).style(default_cell_style),
           Cell::from(stat.get_raw_wpm().to_string())
             .style(Style::default().fg(text_color.to_dark())),
+          Cell::from(stat.get_consistency_pretty())
+            .style(Style::default().fg(text_color.to_dark())),
         ])
       })
       .collect::<Vec<Row>>();
 
     let widths = [
-      Constraint::Percentage(25),
-      Constraint::Percentage(25),
-      Constraint::Percentage(25),
-      Constraint::Percentage(25),
+      Constraint::Percentage(20),
+      Constraint::Percentage(16),
+      Constraint::Percentage(16),
+      Constraint::Percentage(16),
+      Constraint::Percentage(16),
+      Constraint::Percentage(16),
     ];
 
     let default_header_cell_style = Style::default().fg(primary_color).bold();
@@ -223,51 +684,118 @@ impl StatsScreen {
       .block(block)
       .column_spacing(1)
       .style(app_config.get_bg_color())
-      .highlight_symbol("X")
+      .row_highlight_style(
+        Style::default()
+          .fg(app_layout.get_text_current_color())
+          .bg(app_layout.get_text_current_bg_color()),
+      )
+      .highlight_symbol("» ")
       .header(
         Row::new(vec![
+          Cell::from("📅 Date").style(default_header_cell_style),
           Cell::from("⏳ Duration").style(default_header_cell_style),
           Cell::from("🔥 Average WPM").style(default_header_cell_style),
           Cell::from("🎯 Accuracy").style(default_header_cell_style),
           Cell::from("🥩 Raw WPM").style(default_header_cell_style),
+          Cell::from("📈 Consistency").style(default_header_cell_style),
         ])
         .bottom_margin(1),
       )
   }
 
+  /// Returns a label of the chart x-axis value
+  fn get_chart_x_label(chart_data: &ChartData, x: f64) -> String {
+    if chart_data.is_time_axis {
+      DateTime::from_timestamp(x as i64, 0)
+        .map(|date_time| date_time.with_timezone(&Local).format("%m-%d").to_string())
+        .unwrap_or_default()
+    } else {
+      format!("#{}", x as usize + 1)
+    }
+  }
+
   /// Gets the left bottom widget (Chart)
-  fn get_chart_widget<'a>(&self, chart_widget_data: &'a (usize, Vec<(f64, f64)>)) -> Chart<'a> {
+  ///
+  /// Axis are scaled to the displayed data.
+  fn get_chart_widget<'a>(&self, chart_data: &'a ChartData) -> Chart<'a> {
     let app_config = self.config.borrow();
     let app_layout = &app_config.get_layout();
 
     let primary_color = app_layout.get_primary_color();
     let text_color = app_layout.get_text_color();
+    let error_color = app_layout.get_error_color();
 
-    let (_best_wpm, chart_data) = chart_widget_data;
-
-    // Validate best_wpm
-    // let upper_x_bound = if *best_wpm < 25 { 50 } else { best_wpm + 10 };
+    let (short_window, long_window) = CHART_ROLLING_AVERAGE_WINDOWS;
 
     let datasets = vec![
       Dataset::default()
+        .name("WPM")
         .marker(symbols::Marker::Dot)
         .graph_type(GraphType::Scatter)
         .style(Style::default().fg(text_color))
-        .data(chart_data),
+        .data(&chart_data.wpm),
+      Dataset::default()
+        .name("Accuracy")
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(text_color.to_dark().to_dark()))
+        .data(&chart_data.accuracy),
+      Dataset::default()
+        .name(format!("Avg {long_window}"))
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(primary_color.to_dark()))
+        .data(&chart_data.rolling_average_long),
+      Dataset::default()
+        .name(format!("Avg {short_window}"))
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(primary_color))
+        .data(&chart_data.rolling_average_short),
+      Dataset::default()
+        .name("Personal best")
+        .marker(symbols::Marker::Block)
+        .graph_type(GraphType::Scatter)
+        .style(Style::default().fg(error_color))
+        .data(&chart_data.personal_bests),
     ];
 
-    let y_labels = (0..=125)
-      .step_by(25)
-      .map(|y| Span::from(y.to_string()).style(Style::default().fg(text_color)))
+    // Accuracy is plotted in percents, so the axis has at least 100
+    let upper_y_bound = ((chart_data.best_wpm.max(100) + 1) as f64 / 25.0).ceil() * 25.0;
+
+    let y_labels = (0..=4)
+      .map(|step| {
+        let y = upper_y_bound / 4.0 * step as f64;
+        Span::from(format!("{y:.0}")).style(Style::default().fg(text_color))
+      })
       .collect::<Vec<Span>>();
 
+    let [lower_x_bound, upper_x_bound] = chart_data.x_bounds;
+
+    let x_labels = if chart_data.wpm.is_empty() {
+      Vec::new()
+    } else {
+      [
+        lower_x_bound,
+        (lower_x_bound + upper_x_bound) / 2.0,
+        upper_x_bound,
+      ]
+      .iter()
+      .map(|x| {
+        Span::from(StatsScreen::get_chart_x_label(chart_data, *x))
+          .style(Style::default().fg(text_color))
+      })
+      .collect::<Vec<Span>>()
+    };
+
     let x_axis = Axis::default()
-      .style(Style::default().white())
-      .bounds([0.0, chart_data.len() as f64]);
+      .style(Style::default().fg(primary_color))
+      .bounds(chart_data.x_bounds)
+      .labels(x_labels);
 
     let y_axis = Axis::default()
       .style(Style::default().fg(primary_color))
-      .bounds([0.0, 125_f64])
+      .bounds([0.0, upper_y_bound])
       .labels(y_labels);
 
     let chart_block = Block::new()
@@ -280,6 +808,8 @@ impl StatsScreen {
     Chart::new(datasets)
       .block(chart_block)
       .style(app_config.get_bg_color())
+      .legend_position(Some(LegendPosition::BottomRight))
+      .hidden_legend_constraints((Constraint::Ratio(1, 3), Constraint::Ratio(1, 2)))
       .x_axis(x_axis)
       .y_axis(y_axis)
   }
@@ -308,6 +838,13 @@ impl StatsScreen {
         Span::from(format!("{}%", stat_overview.total_average_accuracy,))
           .style(Style::default().fg(primary_color).bold()),
       ]),
+      Line::from(vec![
+        Span::from(" Practice time: ").style(Style::default().fg(text_color)),
+        Span::from(StatsScreen::get_practice_time_pretty(
+          stat_overview.total_practice_secs,
+        ))
+        .style(Style::default().fg(primary_color).bold()),
+      ]),
     ];
 
     let block = Block::new()
@@ -322,4 +859,417 @@ impl StatsScreen {
       .style(app_config.get_bg_color())
       .alignment(Alignment::Left)
   }
+
+  /// Returns the practice time formatted for the UI (e.g. `2h 05m`)
+  fn get_practice_time_pretty(practice_secs: usize) -> String {
+    let minutes = practice_secs / 60;
+
+    if minutes < 60 {
+      format!("{minutes}m")
+    } else {
+      format!("{}h {:02}m", minutes / 60, minutes % 60)
+    }
+  }
+
+  /// Gets the practice calendar widget (heatmap of the practice days)
+  ///
+  /// Columns are weeks (the latest on the right), rows are weekdays.
+  /// The count of the weeks fits the area width.
+  fn get_practice_calendar_widget<'a>(
+    &self,
+    daily_progress: &BTreeMap<NaiveDate, DailyProgress>,
+    daily_goals: &DailyGoals,
+    area: Rect,
+  ) -> Paragraph<'a> {
+    let app_config = self.config.borrow();
+    let app_layout = &app_config.get_layout();
+
+    let primary_color = app_layout.get_primary_color();
+    let text_color = app_layout.get_text_color();
+
+    let met_style = Style::default().fg(primary_color);
+    let practiced_style = Style::default().fg(primary_color.to_dark().to_dark().to_dark());
+    let empty_style = Style::default().fg(text_color.to_dark().to_dark().to_dark());
+
+    let today = Local::now().date_naive();
+
+    // Area without borders and the weekday labels, every day has 2 columns
+    let weeks_count = (area.width.saturating_sub(2 + 5) / 2).max(1) as u64;
+
+    let first_week_start = today
+      .checked_sub_days(Days::new(
+        today.weekday().num_days_from_monday() as u64 + (weeks_count - 1) * 7,
+      ))
+      .unwrap_or(today);
+
+    let weekday_labels = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+    let mut lines = vec![Line::default()];
+
+    for (weekday_index, weekday_label) in weekday_labels.iter().enumerate() {
+      let mut spans =
+        vec![Span::from(format!(" {weekday_label} ")).style(Style::default().fg(text_color))];
+
+      for week_index in 0..weeks_count {
+        let Some(date) =
+          first_week_start.checked_add_days(Days::new(week_index * 7 + weekday_index as u64))
+        else {
+          continue;
+        };
+
+        if date > today {
+          break;
+        }
+
+        let style = match daily_progress.get(&date) {
+          Some(progress) if daily_goals.is_met(progress) => met_style,
+          Some(_) => practiced_style,
+          None => empty_style,
+        };
+
+        spans.push(Span::from("■ ").style(style));
+      }
+
+      lines.push(Line::from(spans));
+    }
+
+    let legend = Line::from(vec![
+      Span::from(" ■").style(met_style),
+      Span::from(" goal met "),
+      Span::from("■").style(practiced_style),
+      Span::from(" practiced "),
+    ])
+    .right_aligned();
+
+    let block = Block::new()
+      .title(" Practice calendar ")
+      .title_style(Style::new().fg(primary_color))
+      .title_bottom(legend)
+      .borders(Borders::ALL)
+      .border_style(Style::default().fg(primary_color))
+      .border_type(BorderType::Rounded);
+
+    Paragraph::new(lines)
+      .block(block)
+      .style(app_config.get_bg_color())
+      .alignment(Alignment::Left)
+  }
+
+  /// Gets the daily goals widget
+  ///
+  /// Shows the today progress against the goals and the streak.
+  fn get_daily_goals_widget<'a>(
+    &self,
+    daily_progress: &BTreeMap<NaiveDate, DailyProgress>,
+    daily_goals: &DailyGoals,
+  ) -> Paragraph<'a> {
+    let app_config = self.config.borrow();
+    let app_layout = &app_config.get_layout();
+
+    let primary_color = app_layout.get_primary_color();
+    let text_color = app_layout.get_text_color();
+
+    let today = Local::now().date_naive();
+    let today_progress = daily_progress.get(&today).cloned().unwrap_or_default();
+
+    let get_goal_line = |title: &'static str, value: usize, goal: usize| {
+      let (goal_text, is_met) = if goal == 0 {
+        (String::from("off"), false)
+      } else {
+        (goal.to_string(), value >= goal)
+      };
+
+      Line::from(vec![
+        Span::from(title).style(Style::default().fg(text_color)),
+        Span::from(format!("{value} / {goal_text}"))
+          .style(Style::default().fg(primary_color).bold()),
+        Span::from(if is_met { " ✓" } else { "" }).style(Style::default().fg(primary_color)),
+      ])
+    };
+
+    let streak = daily_goals.get_streak(daily_progress, today);
+
+    let text = vec![
+      Line::default(),
+      get_goal_line(
+        " ⏱️ Minutes today: ",
+        today_progress.practice_secs / 60,
+        daily_goals.minutes_per_day,
+      ),
+      get_goal_line(
+        " 🏃 Runs today: ",
+        today_progress.runs_count,
+        daily_goals.runs_per_day,
+      ),
+      get_goal_line(
+        " 🔥 Best WPM today: ",
+        today_progress.best_wpm,
+        daily_goals.target_wpm,
+      ),
+      Line::default(),
+      Line::from(vec![
+        Span::from(" 📆 Streak: ").style(Style::default().fg(text_color)),
+        Span::from(format!(
+          "{streak} {}",
+          if streak == 1 { "day" } else { "days" }
+        ))
+        .style(Style::default().fg(primary_color).bold()),
+      ]),
+    ];
+
+    let goals_state = if daily_goals.is_active() {
+      " Daily goals "
+    } else {
+      " Daily goals (none set) "
+    };
+
+    let block = Block::new()
+      .title(goals_state)
+      .title_style(Style::new().fg(primary_color))
+      .borders(Borders::ALL)
+      .border_style(Style::default().fg(primary_color))
+      .border_type(BorderType::Rounded);
+
+    Paragraph::new(text)
+      .block(block)
+      .style(app_config.get_bg_color())
+      .alignment(Alignment::Left)
+  }
+
+  /// Gets the achievements widget
+  ///
+  /// Lists all the achievements, the unlocked ones with the unlock date.
+  fn get_achievements_widget<'a>(
+    &self,
+    unlocked_achievements: &[UnlockedAchievement],
+  ) -> Table<'a> {
+    let app_config = self.config.borrow();
+    let app_layout = &app_config.get_layout();
+
+    let primary_color = app_layout.get_primary_color();
+    let text_color = app_layout.get_text_color();
+
+    let locked_style = Style::default().fg(text_color.to_dark().to_dark());
+
+    let rows = Achievement::all()
+      .iter()
+      .map(|achievement| {
+        let unlocked_achievement = unlocked_achievements
+          .iter()
+          .find(|unlocked| unlocked.achievement == *achievement);
+
+        match unlocked_achievement {
+          Some(unlocked_achievement) => Row::new(vec![
+            Cell::from("🏆"),
+            Cell::from(achievement.get_title()).style(Style::default().fg(primary_color).bold()),
+            Cell::from(achievement.get_description()).style(Style::default().fg(text_color)),
+            Cell::from(unlocked_achievement.get_unlocked_at_pretty())
+              .style(Style::default().fg(text_color)),
+          ]),
+          None => Row::new(vec![
+            Cell::from("🔒"),
+            Cell::from(achievement.get_title()),
+            Cell::from(achievement.get_description()),
+            Cell::from("-"),
+          ])
+          .style(locked_style),
+        }
+      })
+      .collect::<Vec<Row>>();
+
+    let widths = [
+      Constraint::Length(3),
+      Constraint::Percentage(25),
+      Constraint::Percentage(55),
+      Constraint::Percentage(20),
+    ];
+
+    let block = Block::new()
+      .title(format!(
+        " Achievements {}/{} ",
+        unlocked_achievements.len(),
+        Achievement::all().len()
+      ))
+      .title_style(Style::new().fg(primary_color))
+      .borders(Borders::ALL)
+      .border_style(Style::default().fg(primary_color))
+      .border_type(BorderType::Rounded)
+      .padding(Padding::top(1));
+
+    Table::new(rows, widths)
+      .block(block)
+      .column_spacing(1)
+      .style(app_config.get_bg_color())
+  }
+
+  /// Returns the heatmap color of the key accuracy (None for a key without any press)
+  fn get_key_accuracy_color(&self, accuracy: Option<f64>) -> Color {
+    let app_config = self.config.borrow();
+    let app_layout = &app_config.get_layout();
+
+    let (good_accuracy, fair_accuracy) = KEY_ACCURACY_THRESHOLDS;
+
+    match accuracy {
+      Some(accuracy) if accuracy >= good_accuracy => app_layout.get_primary_color(),
+      Some(accuracy) if accuracy >= fair_accuracy => app_layout.get_text_color(),
+      Some(_) => app_layout.get_error_color(),
+      None => app_layout.get_text_color().to_dark().to_dark().to_dark(),
+    }
+  }
+
+  /// Gets the key heatmap widget
+  ///
+  /// Keys of the keyboard layout are colored by their accuracy
+  /// (shifted characters are counted into their keys).
+  fn get_key_heatmap_widget<'a>(&self, key_stats: &KeyStats) -> Paragraph<'a> {
+    let key_counters = key_stats.get_key_counters();
+
+    let app_config = self.config.borrow();
+    let app_layout = &app_config.get_layout();
+
+    let primary_color = app_layout.get_primary_color();
+    let text_color = app_layout.get_text_color();
+    let background_color = app_layout.get_background_color();
+
+    let get_key_span = |label: String, key: char| {
+      let accuracy = key_counters.get(&key).and_then(KeyCounter::get_accuracy);
+
+      Span::from(label).style(
+        Style::default()
+          .bg(self.get_key_accuracy_color(accuracy))
+          .fg(background_color),
+      )
+    };
+
+    let keyboard_layout = key_stats.keyboard_layout;
+
+    let rows = [
+      NUMBER_ROW_KEYS,
+      keyboard_layout.get_row_keys(KeyRow::Top),
+      keyboard_layout.get_row_keys(KeyRow::Home),
+      keyboard_layout.get_row_keys(KeyRow::Bottom),
+    ];
+
+    let mut lines = vec![Line::default()];
+
+    // Rows are staggered as on the physical keyboard
+    for (row_index, keys) in rows.iter().enumerate() {
+      let mut spans = vec![Span::from(" ".repeat(2 + row_index * 2))];
+
+      for key in keys.chars() {
+        spans.push(get_key_span(format!(" {key} "), key));
+        spans.push(Span::from(" "));
+      }
+
+      lines.push(Line::from(spans));
+    }
+
+    lines.push(Line::from(vec![
+      Span::from(" ".repeat(14)),
+      get_key_span(format!("{:^19}", "space"), ' '),
+    ]));
+
+    let mut weakest_keys = key_counters
+      .iter()
+      .filter_map(|(key, counter)| Some((*key, counter.get_accuracy()?)))
+      .filter(|(key, accuracy)| *key != ' ' && *accuracy < 100.0)
+      .collect::<Vec<(char, f64)>>();
+
+    weakest_keys.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+    let mut weakest_keys_spans =
+      vec![Span::from("  Weakest keys: ").style(Style::default().fg(text_color))];
+
+    if weakest_keys.is_empty() {
+      weakest_keys_spans.push(Span::from("-").style(Style::default().fg(text_color)));
+    }
+
+    for (key, accuracy) in weakest_keys.into_iter().take(WEAKEST_KEYS_COUNT) {
+      weakest_keys_spans.push(
+        Span::from(format!("{key} {accuracy:.0}%  "))
+          .style(Style::default().fg(self.get_key_accuracy_color(Some(accuracy)))),
+      );
+    }
+
+    lines.push(Line::default());
+    lines.push(Line::from(weakest_keys_spans));
+
+    let (good_accuracy, fair_accuracy) = KEY_ACCURACY_THRESHOLDS;
+
+    let legend = Line::from(vec![
+      Span::from(" ■").style(Style::default().fg(self.get_key_accuracy_color(Some(good_accuracy)))),
+      Span::from(format!(" ≥{good_accuracy}% ")),
+      Span::from("■").style(Style::default().fg(self.get_key_accuracy_color(Some(fair_accuracy)))),
+      Span::from(format!(" ≥{fair_accuracy}% ")),
+      Span::from("■").style(Style::default().fg(self.get_key_accuracy_color(Some(0.0)))),
+      Span::from(format!(" <{fair_accuracy}% ")),
+    ])
+    .right_aligned();
+
+    let block = Block::new()
+      .title(format!(" Key heatmap ({keyboard_layout}) "))
+      .title_style(Style::new().fg(primary_color))
+      .title_bottom(legend)
+      .borders(Borders::ALL)
+      .border_style(Style::default().fg(primary_color))
+      .border_type(BorderType::Rounded);
+
+    Paragraph::new(lines)
+      .block(block)
+      .style(app_config.get_bg_color())
+      .alignment(Alignment::Left)
+  }
+
+  /// Gets the fingers widget
+  ///
+  /// Lists the presses, mistakes and accuracy of every finger.
+  fn get_fingers_widget<'a>(&self, key_stats: &KeyStats) -> Table<'a> {
+    let app_config = self.config.borrow();
+    let app_layout = &app_config.get_layout();
+
+    let primary_color = app_layout.get_primary_color();
+    let text_color = app_layout.get_text_color();
+
+    let header = Row::new(vec!["Finger", "Presses", "Mistakes", "Accuracy"])
+      .style(Style::default().fg(primary_color).bold())
+      .bottom_margin(1);
+
+    let rows = key_stats
+      .get_finger_counters()
+      .into_iter()
+      .map(|(finger, counter)| {
+        let accuracy = counter.get_accuracy();
+
+        Row::new(vec![
+          Cell::from(finger.to_string()),
+          Cell::from(counter.presses.to_string()),
+          Cell::from(counter.mistakes.to_string()),
+          Cell::from(accuracy.map_or(String::from("-"), |accuracy| format!("{accuracy:.2}%")))
+            .style(Style::default().fg(self.get_key_accuracy_color(accuracy))),
+        ])
+        .style(Style::default().fg(text_color))
+      })
+      .collect::<Vec<Row>>();
+
+    let widths = [
+      Constraint::Percentage(34),
+      Constraint::Percentage(22),
+      Constraint::Percentage(22),
+      Constraint::Percentage(22),
+    ];
+
+    let block = Block::new()
+      .title(" Fingers ")
+      .title_style(Style::new().fg(primary_color))
+      .borders(Borders::ALL)
+      .border_style(Style::default().fg(primary_color))
+      .border_type(BorderType::Rounded)
+      .padding(Padding::horizontal(1));
+
+    Table::new(rows, widths)
+      .header(header)
+      .block(block)
+      .column_spacing(1)
+      .style(app_config.get_bg_color())
+  }
 }
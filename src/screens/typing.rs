@@ -1,56 +1,77 @@
-use std::{cell::RefCell, collections::HashSet, rc::Rc};
+use std::{cell::RefCell, rc::Rc};
 
 use ratatui::{
   Frame,
   crossterm::event::{KeyCode, KeyEvent},
   layout::{Alignment, Constraint, Flex, Layout, Rect},
-  style::{Modifier, Style, Stylize},
+  style::{Color, Modifier, Style, Stylize},
   text::{Line, Span, Text},
   widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph, Wrap},
 };
 
 use crate::{
-  config::{TukaiConfig, TukaiLayout, TukaiLayoutColorTypeEnum},
+  config::{Difficulty, PaceCaret, TukaiConfig, TukaiLayout, TukaiLayoutColorTypeEnum, Visibility},
+  engine::TypingSession,
   helper::Generator,
-  screens::{Instruction, InstructionWidget, Screen, ToDark},
-  storage::{stats::Stat, storage_handler::StorageHandler},
+  screens::{Instruction, InstructionWidget, Screen, ScreenAction, ToDark},
+  storage::{
+    achievements::Achievement, keystroke_log::Keystroke, stats::Stat,
+    storage_handler::StorageHandler,
+  },
 };
 
 use super::ActiveScreenEnum;
 
-/// Handler for incorrect symbols
-///
-/// Inserts incorrect characters into a HashSet
-pub struct MistakeHandler {
-  mistakes_indexes: HashSet<usize>,
+/// Seconds of the run the upcoming words are visible in the memory mode
+const MEMORY_PREVIEW_SECS: u32 = 3;
+
+/// Symbol replacing the letters of the hidden words
+const HIDDEN_CHAR: &str = "·";
+
+/// Ghost caret replaying the keystroke timing of a previous run
+pub struct Ghost {
+  // Average WPM of the ghost run
+  wpm: usize,
+
+  // Language code of the ghost run
+  language: String,
+
+  // Generated text of the ghost run (raced again)
+  text: String,
+
+  // Cursor index after every keystroke of the ghost run
+  cursor_timeline: Vec<(u32, usize)>,
 }
 
-impl MistakeHandler {
-  /// Creates MistakeHandler with empty HashSet
-  fn new() -> Self {
-    Self {
-      mistakes_indexes: HashSet::new(),
-    }
-  }
+impl Ghost {
+  /// Creates the ghost of the run
+  ///
+  /// None if the keystrokes of the run weren't logged.
+  pub fn from_stat(stat: &Stat) -> Option<Self> {
+    let keystroke_log = stat.get_keystroke_log()?;
+    let cursor_timeline = keystroke_log.get_cursor_timeline();
 
-  /// Verifies if the character is mistaken
-  pub fn is_char_mistaken(&self, char_index: usize) -> bool {
-    self.mistakes_indexes.contains(&char_index)
-  }
+    if cursor_timeline.is_empty() {
+      return None;
+    }
 
-  /// Adds the typed character into the mistakes
-  pub fn add_to_mistakes_indexes(&mut self, char_index: usize) -> bool {
-    self.mistakes_indexes.insert(char_index)
+    Some(Self {
+      wpm: stat.get_average_wpm(),
+      language: stat.get_language().clone(),
+      text: keystroke_log.get_text().clone(),
+      cursor_timeline,
+    })
   }
 
-  /// Removes the typed character from mistakes
-  pub fn remove_from_mistakes_indexes(&mut self, char_index: usize) -> bool {
-    self.mistakes_indexes.remove(&char_index)
-  }
+  /// Returns the ghost cursor index after the elapsed milliseconds
+  pub fn get_cursor_index(&self, elapsed_ms: u32) -> usize {
+    let keystrokes_count = self
+      .cursor_timeline
+      .partition_point(|(keystroke_ms, _)| *keystroke_ms <= elapsed_ms);
 
-  /// Returns the current mistake count
-  pub fn get_mistakes_counter(&self) -> usize {
-    self.mistakes_indexes.len()
+    keystrokes_count
+      .checked_sub(1)
+      .map_or(0, |index| self.cursor_timeline[index].1)
   }
 }
 
@@ -58,64 +79,94 @@ pub struct TypingScreen {
   /// Application config
   config: Rc<RefCell<TukaiConfig>>,
 
-  /// Random generated text from a words list
-  pub generated_text: String,
-
-  /// User typed input
-  pub input: String,
-
-  /// Handle incorrect characters
-  pub mistake_handler: MistakeHandler,
+  /// Typing session of a random generated text from a words list
+  pub session: TypingSession,
 
   /// User statistics after the current run is completed
   pub stat: Option<Stat>,
 
-  /// Typing running
-  is_running: bool,
+  /// Achievements unlocked by the current run
+  pub unlocked_achievements: Vec<Achievement>,
 
   /// Popup is visible
   is_popup_visible: bool,
 
-  pub time_secs: u32,
-
-  /// The current cursor index withing generated_text
-  cursor_index: usize,
-
   /// Block motto
   motto: String,
+
+  /// Ghost of the raced run
+  ghost: Option<Ghost>,
+
+  /// Action requested by the user
+  action: Option<ScreenAction>,
+
+  /// Notice shown above the text until the run starts
+  notice: Option<String>,
 }
 
 impl TypingScreen {
   pub fn new(config: Rc<RefCell<TukaiConfig>>) -> Self {
-    let generated_text = Generator::generate_random_string(&config.borrow());
+    let session = TypingScreen::get_session(&config.borrow(), None);
+
+    let motto = Generator::generate_random_motto(&config.borrow());
 
     Self {
       config,
 
-      generated_text,
-
-      input: String::new(),
-
-      mistake_handler: MistakeHandler::new(),
+      session,
 
       stat: None,
 
-      is_running: false,
+      unlocked_achievements: Vec::new(),
 
       is_popup_visible: false,
 
-      time_secs: 0,
+      motto,
 
-      cursor_index: 0,
+      ghost: None,
 
-      motto: Generator::generate_random_motto(),
+      action: None,
+
+      notice: None,
     }
   }
+
+  /// Shows the notice above the text (e.g. why the requested action wasn't taken)
+  pub fn with_notice(mut self, notice: String) -> Self {
+    self.notice = Some(notice);
+    self
+  }
+
+  /// Races the ghost over the text of its run
+  pub fn with_ghost(mut self, ghost: Ghost) -> Self {
+    self.session = TypingScreen::get_session(&self.config.borrow(), Some(&ghost));
+    self.ghost = Some(ghost);
+    self
+  }
+
+  /// Creates the session of a new run
+  ///
+  /// The ghost is raced over the text of its run.
+  fn get_session(config: &TukaiConfig, ghost: Option<&Ghost>) -> TypingSession {
+    let text = match ghost {
+      Some(ghost) => ghost.text.clone(),
+      None => Generator::generate_random_string(config),
+    };
+
+    TypingSession::new(text, config.typing_duration.clone())
+      .with_difficulty(config.difficulty)
+      .with_visibility(config.visibility)
+  }
 }
 
 impl Screen for TypingScreen {
+  /// Counts a second, the idle run is paused
   fn increment_time_secs(&mut self) {
-    self.time_secs += 1;
+    self.session.tick();
+
+    if self.session.is_idle() {
+      self.session.pause();
+    }
   }
 
   fn get_config(&self) -> &Rc<RefCell<TukaiConfig>> {
@@ -123,12 +174,7 @@ impl Screen for TypingScreen {
   }
 
   fn get_remaining_time(&self) -> usize {
-    let app_config = &self.config.borrow();
-
-    app_config
-      .typing_duration
-      .as_seconds()
-      .saturating_sub(self.time_secs as usize)
+    self.session.get_remaining_time()
   }
 
   fn get_next_screen(&self) -> Option<ActiveScreenEnum> {
@@ -141,25 +187,42 @@ impl Screen for TypingScreen {
   ///
   /// Inserts the created stat into storage
   fn stop(&mut self, storage_handler: &mut StorageHandler) {
-    self.is_running = false;
     self.is_popup_visible = true;
 
     if self.stat.is_none() {
-      let stat = Stat::new(
-        self.config.borrow().typing_duration.clone(),
-        self.input.len(),
-        self.mistake_handler.get_mistakes_counter(),
+      let app_config = self.config.borrow();
+
+      // The ghost text may be in a different language than the active one
+      let language = self.ghost.as_ref().map_or_else(
+        || app_config.get_language().get_lang_code().clone(),
+        |ghost| ghost.language.clone(),
       );
 
+      let stat = self.session.finish(language, app_config.record_keystrokes);
+
+      storage_handler.add_key_counters(app_config.keyboard_layout, self.session.get_key_counters());
       storage_handler.insert_into_stats(&stat);
 
+      self.unlocked_achievements =
+        storage_handler.unlock_achievements(&app_config.get_language().get_lang_codes());
+
+      // Average and best WPM may be changed by the run
+      let pace_wpm =
+        storage_handler.get_pace_wpm(app_config.pace_caret, &app_config.typing_duration);
+      drop(app_config);
+      self.config.borrow_mut().pace_wpm = pace_wpm;
+
       self.stat = Some(stat);
     }
   }
 
   /// Returns whether typing has begun
   fn is_running(&self) -> bool {
-    self.is_running
+    self.session.is_running()
+  }
+
+  fn accepts_remapped_input(&self) -> bool {
+    true
   }
 
   fn is_popup_visible(&self) -> bool {
@@ -167,7 +230,19 @@ impl Screen for TypingScreen {
   }
 
   fn get_screen_name(&self) -> String {
-    String::from("Typing")
+    if self.ghost.is_some() {
+      String::from("Ghost race")
+    } else {
+      String::from("Typing")
+    }
+  }
+
+  fn is_animated(&self) -> bool {
+    (self.ghost.is_some() || self.config.borrow().pace_wpm.is_some()) && self.session.is_running()
+  }
+
+  fn take_action(&mut self) -> Option<ScreenAction> {
+    self.action.take()
   }
 
   fn handle_control_events(&mut self, key_event: KeyEvent) -> bool {
@@ -178,7 +253,16 @@ impl Screen for TypingScreen {
     match key_event.code {
       KeyCode::Char('w') | KeyCode::Char('h') => {
         //KeyCode::Char('w') | KeyCode::Backspace => {
-        self.delete_last_word();
+        self.session.press(Keystroke::DeleteWord);
+        true
+      }
+      KeyCode::Char('g') if !self.session.is_running() => {
+        if self.ghost.take().is_some() {
+          self.reset();
+        } else {
+          self.action = Some(ScreenAction::RaceBestGhost);
+        }
+
         true
       }
       _ => false,
@@ -187,42 +271,53 @@ impl Screen for TypingScreen {
 
   /// Resets all necessary properties
   fn reset(&mut self) {
-    self.is_running = false;
-    self.time_secs = 0;
-
-    self.mistake_handler = MistakeHandler::new();
-    self.cursor_index = 0;
-    self.input = String::new();
+    self.session = TypingScreen::get_session(&self.config.borrow(), self.ghost.as_ref());
     self.is_popup_visible = false;
+  }
 
-    let app_config = self.config.borrow();
-    self.generated_text = Generator::generate_random_string(&app_config);
+  fn handle_paste(&mut self, _text: &str) {
+    self.session.reject_paste();
+  }
+
+  /// Flags and pauses the running run
+  fn on_focus_lost(&mut self) {
+    self.session.lose_focus();
+    self.session.pause();
   }
 
   fn handle_events(&mut self, key_event: KeyEvent) -> bool {
-    if self.cursor_index > 0 && !self.is_running() {
+    if self.session.get_cursor_index() > 0 && !self.is_running() {
       return false;
     }
 
+    // Any key resumes the paused run
+    if self.session.is_paused() {
+      self.session.resume();
+      return true;
+    }
+
     match key_event.code {
-      KeyCode::Esc => {
-        if self.is_popup_visible() {
-          self.is_popup_visible = false;
-          true
-        } else {
-          false
-        }
+      KeyCode::Esc if self.is_popup_visible() => {
+        self.is_popup_visible = false;
+        true
+      }
+      KeyCode::Tab if self.session.is_running() => {
+        self.session.pause();
+        true
       }
       KeyCode::Char(c) => {
-        if self.cursor_index == 0 {
+        let was_running = self.session.is_running();
+        self.session.press(Keystroke::Char(c));
+
+        // Keystroke changing the input starts a new run
+        if !was_running && self.session.is_running() {
           self.run();
         }
 
-        self.move_cursor_forward_with(c);
         true
       }
       KeyCode::Backspace => {
-        self.move_cursor_backward();
+        self.session.press(Keystroke::Backspace);
         true
       }
       _ => false,
@@ -262,6 +357,10 @@ impl Screen for TypingScreen {
       .alignment(Alignment::Left);
 
     frame.render_widget(p, area);
+
+    if self.session.is_paused() {
+      self.render_paused_overlay(frame, area);
+    }
   }
 
   fn render_instructions(&self, frame: &mut Frame, area: Rect) {
@@ -280,6 +379,11 @@ impl Screen for TypingScreen {
       "ctrl-r",
       TukaiLayoutColorTypeEnum::Secondary,
     ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Pause",
+      "tab",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
     instruction_widget.add_instruction(Instruction::new(
       "Duration",
       "ctrl-d",
@@ -305,6 +409,60 @@ impl Screen for TypingScreen {
       "ctrl-p",
       TukaiLayoutColorTypeEnum::Secondary,
     ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Profile",
+      "ctrl-o",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Zen",
+      "ctrl-z",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Lessons",
+      "ctrl-n",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Pace",
+      "ctrl-k",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Difficulty",
+      "ctrl-f",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Visibility",
+      "ctrl-b",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Keyboard",
+      "ctrl-y",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Remap",
+      "ctrl-u",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Record",
+      "ctrl-e",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+    instruction_widget.add_instruction(Instruction::new(
+      if self.ghost.is_some() {
+        "Stop ghost"
+      } else {
+        "Race best"
+      },
+      "ctrl-g",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
 
     let block = Block::new().padding(Padding::new(0, 0, area.height / 2, 0));
 
@@ -330,36 +488,104 @@ impl Screen for TypingScreen {
       .border_type(BorderType::Rounded)
       .border_style(Style::new().fg(app_layout.get_primary_color()));
 
-    let text = Text::from(vec![
+    let primary_color = app_layout.get_primary_color();
+
+    let mut lines = vec![
       Line::from(vec![
         Span::from("🔥 Average WPM: "),
         Span::from(format!("{}", self.get_calculated_wpm())).bold(),
       ])
-      .style(Style::default().fg(app_layout.get_primary_color())),
+      .style(Style::default().fg(primary_color)),
       Line::from(vec![
         Span::from("🎯 Accuracy: "),
         Span::from(format!("{}%", self.get_calculated_accuracy())).bold(),
       ])
-      .style(Style::default().fg(app_layout.get_primary_color())),
+      .style(Style::default().fg(primary_color)),
       Line::from(vec![
         Span::from("🥩 Raw WPM: "),
         Span::from(format!("{}", self.get_calculated_raw_wpm())).bold(),
       ])
-      .style(Style::default().fg(app_layout.get_primary_color().to_dark())),
+      .style(Style::default().fg(primary_color.to_dark())),
+    ];
+
+    if let Some(metrics) = self.stat.as_ref().and_then(|stat| stat.get_metrics()) {
+      let get_metric_line = |title: &'static str, value: String| {
+        Line::from(vec![Span::from(title), Span::from(value).bold()])
+          .style(Style::default().fg(primary_color.to_dark()))
+      };
+
+      lines.extend([
+        get_metric_line("📈 Consistency: ", format!("{:.0}%", metrics.consistency)),
+        get_metric_line(
+          "✏️ Errors: ",
+          format!(
+            "{} corrected / {} uncorrected",
+            metrics.corrected_errors, metrics.uncorrected_errors
+          ),
+        ),
+        get_metric_line(
+          "🔤 Chars: ",
+          format!(
+            "{} extra / {} missed",
+            metrics.extra_chars, metrics.missed_chars
+          ),
+        ),
+        get_metric_line("⌨️ Keystrokes: ", metrics.keystrokes.to_string()),
+        get_metric_line("⏱️ First error: ", metrics.get_time_to_first_error_pretty()),
+      ]);
+    }
+
+    if self.session.is_failed() {
+      lines.extend([
+        Line::from(""),
+        Line::from("💀 Failed: sudden death")
+          .style(Style::default().fg(app_layout.get_error_color())),
+      ]);
+    }
+
+    if let Some(flags) = self
+      .stat
+      .as_ref()
+      .map(|stat| stat.get_flags())
+      .filter(|flags| flags.is_flagged())
+    {
+      lines.extend([
+        Line::from(""),
+        Line::from(format!("⚠️ Flagged: {}", flags.get_pretty()))
+          .style(Style::default().fg(app_layout.get_error_color())),
+      ]);
+    }
+
+    if !self.unlocked_achievements.is_empty() {
+      lines.push(Line::from(""));
+
+      lines.extend(self.unlocked_achievements.iter().map(|achievement| {
+        Line::from(vec![
+          Span::from("🏆 Unlocked: "),
+          Span::from(achievement.get_title()).bold(),
+        ])
+        .style(Style::default().fg(primary_color))
+      }));
+    }
+
+    lines.extend([
       Line::from(""),
       Line::from(vec![
-        Span::from("Try again").style(Style::default().fg(app_layout.get_primary_color())),
-        Span::from(" ctrl-r").style(Style::default().fg(app_layout.get_primary_color()).bold()),
+        Span::from("Try again").style(Style::default().fg(primary_color)),
+        Span::from(" ctrl-r").style(Style::default().fg(primary_color).bold()),
       ]),
     ]);
 
-    let p = Paragraph::new(text)
-      .block(block)
+    // Text lines with the vertical padding and borders
+    let popup_height = lines.len() as u16 + 4;
+
+    let p = Paragraph::new(Text::from(lines))
+      .block(block.padding(Padding::vertical(1)))
       .alignment(Alignment::Center)
       .centered();
 
-    let vertical = Layout::vertical([Constraint::Percentage(22)]).flex(Flex::Center);
-    let horizontal = Layout::horizontal([Constraint::Percentage(22)]).flex(Flex::Center);
+    let vertical = Layout::vertical([Constraint::Length(popup_height)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Length(44)]).flex(Flex::Center);
     let [area] = vertical.areas(area);
     let [area] = horizontal.areas(area);
 
@@ -372,84 +598,11 @@ impl TypingScreen {
   /// Starts the running typing process
   ///
   /// Unsets last stat
-  fn run(&mut self) {
-    self.is_running = true;
+  pub fn run(&mut self) {
+    self.session.start();
     self.stat = None;
-  }
-
-  /// Validates an inserted char
-  ///
-  /// If it is not valid, insert it into the set of mistakes
-  fn validate_input_char(&mut self, inserted_char: char) {
-    if let Some(generated_char) = self.generated_text.chars().nth(self.cursor_index) {
-      if generated_char != inserted_char {
-        self
-          .mistake_handler
-          .add_to_mistakes_indexes(self.cursor_index);
-      }
-    }
-  }
-
-  /// Moves the cursor position forward
-  ///
-  /// Also validates a char
-  fn move_cursor_forward_with(&mut self, c: char) {
-    self.validate_input_char(c);
-    self.input.push(c);
-    self.cursor_index += 1;
-  }
-
-  /// Moves the cursor position backward
-  ///
-  /// Remove the incorrect symbol from the set if its exists
-  fn move_cursor_backward(&mut self) {
-    if self.input.pop().is_none() {
-      return;
-    }
-
-    self.cursor_index -= 1;
-
-    if self.mistake_handler.is_char_mistaken(self.cursor_index) {
-      self
-        .mistake_handler
-        .remove_from_mistakes_indexes(self.cursor_index);
-    }
-  }
-
-  // Deletes the last word form the input.
-  // Handles trailing spaces and updates mistakes.
-  pub fn delete_last_word(&mut self) {
-    if self.input.is_empty() {
-      return;
-    }
-
-    let original_input_len = self.input.len();
-
-    // Find the end of the actual contnet (trim trailing whitespace for logic)
-    let trimmed_end_len = self.input.trim_end().len();
-
-    if trimmed_end_len == 0 {
-      // Input was all spaces
-      for i in 0..original_input_len {
-        self.mistake_handler.remove_from_mistakes_indexes(i);
-      }
-      self.input.clear();
-      self.cursor_index = 0;
-      return;
-    }
-
-    // Find the last space before the last word in the trimmed part
-    let last_word_start_idx = match self.input[..trimmed_end_len].rfind(' ') {
-      Some(space_idx) => space_idx + 1, // Word starts after the space
-      None => 0,                        // No space found, word starts at the beginning
-    };
-
-    for i in last_word_start_idx..original_input_len {
-      self.mistake_handler.remove_from_mistakes_indexes(i);
-    }
-
-    self.input.truncate(last_word_start_idx);
-    self.cursor_index = self.input.len();
+    self.notice = None;
+    self.unlocked_achievements.clear();
   }
 
   /// Returns the raw WPM
@@ -479,64 +632,112 @@ impl TypingScreen {
     }
   }
 
+  /// Returns the ghost cursor index
+  ///
+  /// None before the run starts or without a ghost.
+  fn get_ghost_cursor_index(&self) -> Option<usize> {
+    let ghost = self.ghost.as_ref()?;
+
+    if self.session.is_running() {
+      Some(ghost.get_cursor_index(self.session.get_elapsed_ms()))
+    } else if self.is_popup_visible {
+      // Ghost position at the end of the run
+      let typing_duration_ms = self.session.get_typing_duration().as_seconds() as u32 * 1000;
+      Some(ghost.get_cursor_index(typing_duration_ms))
+    } else {
+      None
+    }
+  }
+
+  /// Returns the pace caret index
+  ///
+  /// The pace caret moves at the target WPM since the run start.
+  fn get_pace_cursor_index(&self) -> Option<usize> {
+    let app_config = self.config.borrow();
+    let pace_wpm = app_config.pace_wpm?;
+
+    let elapsed_ms = if self.session.is_running() {
+      self.session.get_elapsed_ms()
+    } else if self.is_popup_visible {
+      self.session.get_typing_duration().as_seconds() as u32 * 1000
+    } else {
+      return None;
+    };
+
+    // A word is 5 characters
+    Some(elapsed_ms as usize * pace_wpm * 5 / 60_000)
+  }
+
   /// Prepares and returns a paragraph.
   ///
   /// If popup window is showed then colors converts to dark.
-  pub fn get_paragraph(&self, layout: &TukaiLayout) -> Paragraph {
+  pub fn get_paragraph(&self, layout: &TukaiLayout) -> Paragraph<'_> {
     let mut lines = Vec::new();
 
-    let (primary_color, error_color, text_color) = {
-      let colors = {
-        (
-          layout.get_primary_color(),
-          layout.get_error_color(),
-          layout.get_text_color(),
-        )
+    let (primary_color, _, text_color) = self.get_colors(layout);
+
+    let mut remaining_time_spans = vec![
+      Span::from(format!("⏳{}", self.get_remaining_time(),))
+        .style(Style::default().fg(primary_color).bold()),
+    ];
+
+    if let Some(ghost) = &self.ghost {
+      remaining_time_spans
+        .push(Span::from(format!("  👻 {} WPM", ghost.wpm)).style(Style::default().fg(text_color)));
+    }
+
+    let (pace_caret, pace_wpm) = {
+      let app_config = self.config.borrow();
+      (app_config.pace_caret, app_config.pace_wpm)
+    };
+
+    if let Some(pace_wpm) = pace_wpm {
+      let pace_text = match pace_caret {
+        PaceCaret::Fixed(_) => format!("  🐇 {pace_wpm} WPM"),
+        _ => format!("  🐇 {pace_wpm} WPM ({pace_caret})"),
       };
 
-      if self.is_popup_visible() {
-        (colors.0.to_dark(), colors.1.to_dark(), colors.2.to_dark())
-      } else {
-        colors
-      }
+      remaining_time_spans.push(Span::from(pace_text).style(Style::default().fg(text_color)));
+    }
+
+    let difficulty = self.session.get_difficulty();
+
+    if difficulty != Difficulty::Normal {
+      remaining_time_spans
+        .push(Span::from(format!("  🎚 {difficulty}")).style(Style::default().fg(text_color)));
+    }
+
+    let visibility = self.session.get_visibility();
+
+    if visibility != Visibility::Normal {
+      remaining_time_spans
+        .push(Span::from(format!("  👁 {visibility}")).style(Style::default().fg(text_color)));
+    }
+
+    let (keyboard_layout, remap_keys) = {
+      let app_config = self.config.borrow();
+      (app_config.keyboard_layout, app_config.remap_keys)
     };
 
-    let remaining_time_line = Line::from(vec![
-      Span::from(format!("⏳{}", self.get_remaining_time(),))
-        .style(Style::default().fg(primary_color).bold()),
-    ]);
+    if remap_keys {
+      remaining_time_spans.push(
+        Span::from(format!("  ⌨ {keyboard_layout} (remapped)"))
+          .style(Style::default().fg(text_color)),
+      );
+    }
 
-    let text_line = self
-      .generated_text
-      .chars()
-      .enumerate()
-      .map(|(i, c)| {
-        if i == self.cursor_index {
-          Span::from(c.to_string()).style(
-            Style::default()
-              .fg(layout.get_text_current_color())
-              .bg(layout.get_text_current_bg_color()),
-          )
-        } else if i < self.cursor_index {
-          if self.input.chars().nth(i) == Some(c) {
-            Span::from(c.to_string()).style(Style::default().fg(primary_color))
-          } else {
-            Span::from(c.to_string()).style(
-              Style::default()
-                .fg(error_color)
-                .add_modifier(Modifier::CROSSED_OUT),
-            )
-          }
-        } else {
-          Span::from(c.to_string()).style(Style::default().fg(text_color))
-        }
-      })
-      .collect::<Line>();
+    let remaining_time_line = Line::from(remaining_time_spans);
+
+    let text_line = self.get_text_line(layout);
 
     let empty_line = Line::from(Vec::new());
 
     lines.push(remaining_time_line);
 
+    if let Some(notice) = &self.notice {
+      lines.push(Line::from(notice.as_str()).style(Style::default().fg(layout.get_error_color())));
+    }
+
     lines.push(empty_line.clone());
 
     lines.push(text_line);
@@ -547,4 +748,184 @@ impl TypingScreen {
 
     Paragraph::new(text).wrap(Wrap { trim: true })
   }
+
+  /// Renders the overlay of the paused run
+  fn render_paused_overlay(&self, frame: &mut Frame, area: Rect) {
+    let app_config = self.config.borrow();
+    let primary_color = app_config.get_layout().get_primary_color();
+
+    let block = Block::bordered()
+      .style(app_config.get_bg_color())
+      .border_type(BorderType::Rounded)
+      .border_style(Style::new().fg(primary_color));
+
+    let p = Paragraph::new(vec![
+      Line::from("⏸ Paused").style(Style::default().fg(primary_color).bold()),
+      Line::from("Press any key to resume").style(Style::default().fg(primary_color.to_dark())),
+    ])
+    .block(block)
+    .alignment(Alignment::Center);
+
+    let vertical = Layout::vertical([Constraint::Length(4)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Length(30)]).flex(Flex::Center);
+    let [overlay_area] = vertical.areas(area);
+    let [overlay_area] = horizontal.areas(overlay_area);
+
+    frame.render_widget(Clear, overlay_area);
+    frame.render_widget(p, overlay_area);
+  }
+
+  /// Returns the primary, error and text colors
+  ///
+  /// If popup window is showed or the run is paused then colors converts to dark.
+  fn get_colors(&self, layout: &TukaiLayout) -> (Color, Color, Color) {
+    let colors = (
+      layout.get_primary_color(),
+      layout.get_error_color(),
+      layout.get_text_color(),
+    );
+
+    if self.is_popup_visible() || self.session.is_paused() {
+      (colors.0.to_dark(), colors.1.to_dark(), colors.2.to_dark())
+    } else {
+      colors
+    }
+  }
+
+  /// Verifies if the errors are hidden (blind mode)
+  ///
+  /// Errors are revealed when the run ends.
+  fn is_feedback_hidden(&self) -> bool {
+    self.session.get_visibility() == Visibility::Blind && self.session.is_running()
+  }
+
+  /// Verifies if the word of the text is hidden (memory and masked modes)
+  ///
+  /// The whole text is revealed when the run ends.
+  fn is_word_hidden(&self, word_index: usize) -> bool {
+    if !self.session.is_running() {
+      return false;
+    }
+
+    let current_word_index = self.session.get_typed_text().get_current_word_index();
+
+    match self.session.get_visibility() {
+      Visibility::Memory => {
+        word_index > current_word_index && self.session.get_time_secs() >= MEMORY_PREVIEW_SECS
+      }
+      Visibility::Masked => word_index != current_word_index,
+      _ => false,
+    }
+  }
+
+  /// Returns the line of the generated text
+  ///
+  /// Highlights the typed characters, mistakes, extra and missed letters and the carets.
+  /// Errors and words are hidden by the visibility of the run.
+  pub fn get_text_line(&self, layout: &TukaiLayout) -> Line<'_> {
+    let (primary_color, mut error_color, text_color) = self.get_colors(layout);
+
+    let is_feedback_hidden = self.is_feedback_hidden();
+
+    if is_feedback_hidden {
+      error_color = primary_color;
+    }
+
+    let ghost_cursor_index = self.get_ghost_cursor_index();
+    let pace_cursor_index = self.get_pace_cursor_index();
+
+    let cursor_index = self.session.get_cursor_index();
+    let typed_text = self.session.get_typed_text();
+    let current_word_index = typed_text.get_current_word_index();
+
+    let get_char_span = |i: usize, c: char, typed_char: Option<char>, is_passed: bool| {
+      let span = Span::from(c.to_string());
+
+      if i == cursor_index {
+        span.style(
+          Style::default()
+            .fg(layout.get_text_current_color())
+            .bg(layout.get_text_current_bg_color()),
+        )
+      } else if Some(i) == ghost_cursor_index {
+        span.style(
+          Style::default()
+            .fg(layout.get_text_current_color())
+            .bg(primary_color.to_dark().to_dark())
+            .add_modifier(Modifier::UNDERLINED),
+        )
+      } else if Some(i) == pace_cursor_index {
+        span.style(
+          Style::default()
+            .fg(layout.get_text_current_color())
+            .bg(layout.get_text_color().to_dark().to_dark())
+            .add_modifier(Modifier::ITALIC),
+        )
+      } else {
+        match typed_char {
+          Some(typed_char) if typed_char == c || is_feedback_hidden => {
+            span.style(Style::default().fg(primary_color))
+          }
+          Some(_) => span.style(
+            Style::default()
+              .fg(error_color)
+              .add_modifier(Modifier::CROSSED_OUT),
+          ),
+          // Letters skipped by the space
+          None if is_passed && !is_feedback_hidden => span.style(
+            Style::default()
+              .fg(error_color)
+              .add_modifier(Modifier::UNDERLINED),
+          ),
+          None => span.style(Style::default().fg(text_color)),
+        }
+      }
+    };
+
+    let mut spans = Vec::new();
+    let mut char_index = 0;
+
+    for (word_index, word) in typed_text.get_words().iter().enumerate() {
+      let typed_word = typed_text.get_typed_word(word_index).unwrap_or_default();
+      let is_passed = word_index < current_word_index;
+      let is_hidden = self.is_word_hidden(word_index);
+
+      let mut typed_chars = typed_word.chars();
+
+      for c in word.chars() {
+        let span = get_char_span(char_index, c, typed_chars.next(), is_passed);
+
+        spans.push(if is_hidden {
+          span.content(HIDDEN_CHAR)
+        } else {
+          span
+        });
+
+        char_index += 1;
+      }
+
+      // Letters typed beyond the word
+      let extra_chars = typed_chars
+        .map(|c| {
+          if is_hidden {
+            HIDDEN_CHAR.to_string()
+          } else {
+            c.to_string()
+          }
+        })
+        .collect::<String>();
+
+      if !extra_chars.is_empty() {
+        spans.push(Span::from(extra_chars).style(Style::default().fg(error_color.to_dark())));
+      }
+
+      if word_index + 1 < typed_text.get_words().len() {
+        let typed_space = is_passed.then_some(' ');
+        spans.push(get_char_span(char_index, ' ', typed_space, false));
+        char_index += 1;
+      }
+    }
+
+    Line::from(spans)
+  }
 }
@@ -0,0 +1,359 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::{Alignment, Constraint, Flex, Layout, Rect},
+  style::{Style, Stylize},
+  text::{Line, Span, Text},
+  widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph, Wrap},
+};
+
+use crate::{
+  config::{TukaiConfig, TukaiLayout, TukaiLayoutColorTypeEnum},
+  engine::ZenSession,
+  helper::Generator,
+  screens::{Instruction, InstructionWidget, Screen, ToDark},
+  storage::{keystroke_log::Keystroke, stats::Stat, storage_handler::StorageHandler},
+};
+
+use super::ActiveScreenEnum;
+
+/// Free typing without a text
+///
+/// The run is stopped by the `Enter`, the stat is stored with the `Zen` mode.
+pub struct ZenScreen {
+  /// Application config
+  config: Rc<RefCell<TukaiConfig>>,
+
+  /// Free typing session
+  session: ZenSession,
+
+  /// The user stopped the run (stored by the next `stop`)
+  is_stop_requested: bool,
+
+  /// Stat of the stopped run
+  stat: Option<Stat>,
+
+  is_popup_visible: bool,
+
+  /// Block motto
+  motto: String,
+}
+
+impl ZenScreen {
+  pub fn new(config: Rc<RefCell<TukaiConfig>>) -> Self {
+    let motto = Generator::generate_random_motto(&config.borrow());
+
+    Self {
+      config,
+      session: ZenSession::new(),
+      is_stop_requested: false,
+      stat: None,
+      is_popup_visible: false,
+      motto,
+    }
+  }
+}
+
+impl Screen for ZenScreen {
+  fn increment_time_secs(&mut self) {
+    self.session.tick();
+  }
+
+  fn get_config(&self) -> &Rc<RefCell<TukaiConfig>> {
+    &self.config
+  }
+
+  /// Zen run has no time limit, no time remains once the user stops it
+  fn get_remaining_time(&self) -> usize {
+    if self.is_stop_requested { 0 } else { 1 }
+  }
+
+  fn get_screen_name(&self) -> String {
+    String::from("Zen")
+  }
+
+  fn get_previous_screen(&self) -> Option<ActiveScreenEnum> {
+    Some(ActiveScreenEnum::Typing)
+  }
+
+  fn get_next_screen(&self) -> Option<ActiveScreenEnum> {
+    Some(ActiveScreenEnum::Repeat)
+  }
+
+  /// Stops the run and inserts its stat into the storage
+  fn stop(&mut self, storage_handler: &mut StorageHandler) {
+    self.is_stop_requested = false;
+
+    if !self.session.is_running() {
+      return;
+    }
+
+    let language = self.config.borrow().get_language().get_lang_code().clone();
+    let stat = self.session.finish(language);
+
+    storage_handler.insert_into_stats(&stat);
+
+    self.stat = Some(stat);
+    self.is_popup_visible = true;
+  }
+
+  fn is_running(&self) -> bool {
+    self.session.is_running()
+  }
+
+  fn accepts_remapped_input(&self) -> bool {
+    self.stat.is_none()
+  }
+
+  fn is_popup_visible(&self) -> bool {
+    self.is_popup_visible
+  }
+
+  fn reset(&mut self) {
+    self.session = ZenSession::new();
+    self.is_stop_requested = false;
+    self.stat = None;
+    self.is_popup_visible = false;
+  }
+
+  fn handle_control_events(&mut self, key_event: KeyEvent) -> bool {
+    match key_event.code {
+      KeyCode::Char('w') | KeyCode::Char('h') if self.session.is_running() => {
+        self.session.press(Keystroke::DeleteWord);
+        true
+      }
+      _ => false,
+    }
+  }
+
+  fn handle_events(&mut self, key_event: KeyEvent) -> bool {
+    if self.stat.is_some() {
+      if key_event.code == KeyCode::Esc && self.is_popup_visible {
+        self.is_popup_visible = false;
+        return true;
+      }
+
+      return false;
+    }
+
+    match key_event.code {
+      KeyCode::Enter if self.session.is_running() => {
+        self.is_stop_requested = true;
+        true
+      }
+      KeyCode::Char(c) => {
+        self.session.press(Keystroke::Char(c));
+        true
+      }
+      KeyCode::Backspace => {
+        self.session.press(Keystroke::Backspace);
+        true
+      }
+      _ => false,
+    }
+  }
+
+  fn render(&self, frame: &mut Frame, area: Rect) {
+    let app_config = self.config.borrow();
+    let app_layout = app_config.get_layout();
+
+    let horizontal_padding = if (area.width / 3) < 8 {
+      2
+    } else {
+      area.width / 3 - 8
+    };
+
+    let block = Block::new()
+      .title(self.get_title())
+      .title_alignment(Alignment::Left)
+      .title_bottom(self.motto.as_ref())
+      .title_style(Style::default().fg(app_layout.get_primary_color()))
+      .title_alignment(Alignment::Center)
+      .style(app_config.get_bg_color())
+      .borders(Borders::ALL)
+      .border_type(BorderType::Rounded)
+      .border_style(Style::default().fg(app_layout.get_primary_color()))
+      .padding(Padding::new(
+        horizontal_padding,
+        horizontal_padding,
+        (area.height / 2) - 5,
+        0,
+      ));
+
+    let p = self
+      .get_paragraph(&app_layout)
+      .block(block)
+      .alignment(Alignment::Left);
+
+    frame.render_widget(p, area);
+  }
+
+  fn render_instructions(&self, frame: &mut Frame, area: Rect) {
+    let app_config = self.config.borrow();
+    let app_layout = app_config.get_layout();
+
+    let mut instruction_widget = InstructionWidget::new(&app_layout);
+
+    instruction_widget.add_instruction(Instruction::new(
+      "Exit",
+      "esc",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Finish",
+      "enter",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Reset",
+      "ctrl-r",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Typing",
+      "ctrl-h",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Layout",
+      "ctrl-s",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Transparent",
+      "ctrl-t",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Language",
+      "ctrl-p",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+
+    let block = Block::new().padding(Padding::new(0, 0, area.height / 2, 0));
+
+    let instructions = instruction_widget
+      .get_paragraph()
+      .block(block)
+      .alignment(Alignment::Center)
+      .style(app_config.get_bg_color());
+
+    frame.render_widget(instructions, area);
+  }
+
+  /// Renders a popup screen
+  ///
+  /// Used after the run is stopped
+  fn render_popup(&self, frame: &mut Frame) {
+    let Some(stat) = &self.stat else {
+      return;
+    };
+
+    let app_config = self.config.borrow();
+    let app_layout = app_config.get_layout();
+    let area = frame.area();
+
+    let block = Block::bordered()
+      .style(app_config.get_bg_color())
+      .border_type(BorderType::Rounded)
+      .border_style(Style::new().fg(app_layout.get_primary_color()));
+
+    let primary_color = app_layout.get_primary_color();
+
+    let get_line = |title: &'static str, value: String| {
+      Line::from(vec![Span::from(title), Span::from(value).bold()])
+        .style(Style::default().fg(primary_color.to_dark()))
+    };
+
+    let mut lines = vec![
+      Line::from(vec![
+        Span::from("🔥 Average WPM: "),
+        Span::from(stat.get_average_wpm().to_string()).bold(),
+      ])
+      .style(Style::default().fg(primary_color)),
+      get_line("⏳ Time: ", format!("{}s", stat.get_practice_secs())),
+      get_line("📝 Words: ", self.session.get_words_count().to_string()),
+      get_line(
+        "🔤 Chars: ",
+        self.session.get_input().chars().count().to_string(),
+      ),
+    ];
+
+    if let Some(metrics) = stat.get_metrics() {
+      lines.extend([
+        get_line("📈 Consistency: ", format!("{:.0}%", metrics.consistency)),
+        get_line("⌨️ Keystrokes: ", metrics.keystrokes.to_string()),
+      ]);
+    }
+
+    lines.extend([
+      Line::from(""),
+      Line::from(vec![
+        Span::from("Try again").style(Style::default().fg(primary_color)),
+        Span::from(" ctrl-r").style(Style::default().fg(primary_color).bold()),
+      ]),
+    ]);
+
+    // Text lines with the vertical padding and borders
+    let popup_height = lines.len() as u16 + 4;
+
+    let p = Paragraph::new(Text::from(lines))
+      .block(block.padding(Padding::vertical(1)))
+      .alignment(Alignment::Center)
+      .centered();
+
+    let vertical = Layout::vertical([Constraint::Length(popup_height)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Length(44)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(p, area);
+  }
+}
+
+impl ZenScreen {
+  /// Prepares and returns a paragraph.
+  ///
+  /// If popup window is showed then colors converts to dark.
+  pub fn get_paragraph(&self, layout: &TukaiLayout) -> Paragraph<'_> {
+    let (primary_color, text_color) = if self.is_popup_visible {
+      (
+        layout.get_primary_color().to_dark(),
+        layout.get_text_color().to_dark(),
+      )
+    } else {
+      (layout.get_primary_color(), layout.get_text_color())
+    };
+
+    let status_line = Line::from(vec![
+      Span::from("☯ Zen").style(Style::default().fg(primary_color).bold()),
+      Span::from(format!(
+        "  ⏱ {}s  🔥 {} WPM",
+        self.session.get_time_secs(),
+        self.session.get_current_wpm()
+      ))
+      .style(Style::default().fg(text_color)),
+    ]);
+
+    let input_line = if self.session.get_input().is_empty() && !self.session.is_running() {
+      Line::from("Type anything, press enter to finish").style(Style::default().fg(text_color))
+    } else {
+      Line::from(vec![
+        Span::from(self.session.get_input()).style(Style::default().fg(primary_color)),
+        Span::from(" ").style(Style::default().bg(layout.get_text_current_bg_color())),
+      ])
+    };
+
+    let text = Text::from(vec![
+      status_line,
+      Line::from(Vec::new()),
+      input_line,
+      Line::from(Vec::new()),
+    ]);
+
+    Paragraph::new(text).wrap(Wrap { trim: true })
+  }
+}
@@ -0,0 +1,199 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use super::stats::{RunMode, Stat};
+
+/// Milestone unlocked by the user's runs
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub enum Achievement {
+  FirstRun,
+  Wpm60,
+  Wpm80,
+  Wpm100,
+  Wpm150,
+  PerfectAccuracy,
+  Streak3,
+  Streak7,
+  Streak30,
+  Runs100,
+  Runs1000,
+  Polyglot,
+}
+
+/// Data the achievements are evaluated against
+pub struct AchievementContext<'a> {
+  // Stats earning the achievements (see `StorageHandler::unlock_achievements`)
+  pub stats: &'a [&'a Stat],
+
+  // Current streak of the days meeting the daily goals
+  pub streak: usize,
+
+  // Language codes of all the available languages
+  pub lang_codes: &'a [String],
+}
+
+/// Achievement with the time it was unlocked
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct UnlockedAchievement {
+  pub achievement: Achievement,
+
+  // Unix timestamp of the unlock
+  pub unlocked_at: i64,
+}
+
+impl UnlockedAchievement {
+  /// Returns the unlock date formatted for the UI
+  pub fn get_unlocked_at_pretty(&self) -> String {
+    DateTime::from_timestamp(self.unlocked_at, 0)
+      .map(|date_time| {
+        date_time
+          .with_timezone(&Local)
+          .format("%Y-%m-%d")
+          .to_string()
+      })
+      .unwrap_or(String::from("-"))
+  }
+}
+
+impl Achievement {
+  /// Returns all the achievements
+  pub fn all() -> &'static [Achievement] {
+    use Achievement::*;
+
+    &[
+      FirstRun,
+      Wpm60,
+      Wpm80,
+      Wpm100,
+      Wpm150,
+      PerfectAccuracy,
+      Streak3,
+      Streak7,
+      Streak30,
+      Runs100,
+      Runs1000,
+      Polyglot,
+    ]
+  }
+
+  /// Returns the title
+  pub fn get_title(&self) -> &'static str {
+    use Achievement::*;
+
+    match self {
+      FirstRun => "First steps",
+      Wpm60 => "Warming up",
+      Wpm80 => "Swift fingers",
+      Wpm100 => "Centurion",
+      Wpm150 => "Lightning",
+      PerfectAccuracy => "Flawless",
+      Streak3 => "Habit",
+      Streak7 => "Full week",
+      Streak30 => "Unstoppable",
+      Runs100 => "Dedicated",
+      Runs1000 => "Veteran",
+      Polyglot => "Polyglot",
+    }
+  }
+
+  /// Returns the description of the milestone
+  pub fn get_description(&self) -> &'static str {
+    use Achievement::*;
+
+    match self {
+      FirstRun => "Complete the first run",
+      Wpm60 => "Reach 60 WPM",
+      Wpm80 => "Reach 80 WPM",
+      Wpm100 => "Reach 100 WPM",
+      Wpm150 => "Reach 150 WPM",
+      PerfectAccuracy => "Complete a run with 100% accuracy",
+      Streak3 => "Meet the daily goals 3 days in a row",
+      Streak7 => "Meet the daily goals 7 days in a row",
+      Streak30 => "Meet the daily goals 30 days in a row",
+      Runs100 => "Complete 100 runs",
+      Runs1000 => "Complete 1000 runs",
+      Polyglot => "Complete a run in every language",
+    }
+  }
+
+  /// Verifies if the milestone is reached
+  pub fn is_reached(&self, context: &AchievementContext) -> bool {
+    use Achievement::*;
+
+    // Zen runs have no text, so their WPM and accuracy aren't scored
+    let scored_stats = || {
+      context
+        .stats
+        .iter()
+        .filter(|stat| stat.get_mode() != RunMode::Zen)
+    };
+
+    let has_wpm = |wpm: usize| scored_stats().any(|stat| stat.get_average_wpm() >= wpm);
+
+    match self {
+      FirstRun => !context.stats.is_empty(),
+      Wpm60 => has_wpm(60),
+      Wpm80 => has_wpm(80),
+      Wpm100 => has_wpm(100),
+      Wpm150 => has_wpm(150),
+      PerfectAccuracy => {
+        scored_stats().any(|stat| stat.get_average_wpm() > 0 && stat.get_accuracy() == 100.0)
+      }
+      Streak3 => context.streak >= 3,
+      Streak7 => context.streak >= 7,
+      Streak30 => context.streak >= 30,
+      Runs100 => context.stats.len() >= 100,
+      Runs1000 => context.stats.len() >= 1000,
+      Polyglot => {
+        !context.lang_codes.is_empty()
+          && context.lang_codes.iter().all(|lang_code| {
+            context
+              .stats
+              .iter()
+              .any(|stat| stat.get_language() == lang_code)
+          })
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config::TypingDuration;
+
+  #[test]
+  fn achievements_reached_by_stats() {
+    let stats = [
+      Stat::new(TypingDuration::Minute, String::from("en"), 420, 0),
+      Stat::new(TypingDuration::Minute, String::from("cz"), 100, 5),
+    ];
+
+    let lang_codes = vec![String::from("en"), String::from("cz")];
+
+    let stats = stats.iter().collect::<Vec<&Stat>>();
+
+    let context = AchievementContext {
+      stats: &stats,
+      streak: 1,
+      lang_codes: &lang_codes,
+    };
+
+    let reached = Achievement::all()
+      .iter()
+      .filter(|achievement| achievement.is_reached(&context))
+      .copied()
+      .collect::<Vec<Achievement>>();
+
+    assert_eq!(
+      reached,
+      vec![
+        Achievement::FirstRun,
+        Achievement::Wpm60,
+        Achievement::Wpm80,
+        Achievement::PerfectAccuracy,
+        Achievement::Polyglot,
+      ]
+    );
+  }
+}
@@ -0,0 +1,77 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// Typed repetition of the drilled phrase
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Default)]
+pub struct DrillRepetition {
+  pub wpm: usize,
+
+  // Correctly typed characters of all the typed ones (including the corrected)
+  pub accuracy: f64,
+}
+
+/// Result of a finished drill
+///
+/// Stored separately from the stats of the runs.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct DrillResult {
+  // Drilled word or phrase
+  pub phrase: String,
+
+  pub repetitions: Vec<DrillRepetition>,
+
+  // Unix timestamp of the drill completion
+  pub created_at: i64,
+}
+
+impl DrillResult {
+  /// Returns the local date time of the drill formatted for the UI
+  pub fn get_created_at_pretty(&self) -> String {
+    DateTime::from_timestamp(self.created_at, 0)
+      .map(|date_time| {
+        date_time
+          .with_timezone(&Local)
+          .format("%Y-%m-%d %H:%M")
+          .to_string()
+      })
+      .unwrap_or(String::from("-"))
+  }
+
+  /// Returns the average WPM of the repetitions
+  pub fn get_average_wpm(&self) -> usize {
+    if self.repetitions.is_empty() {
+      return 0;
+    }
+
+    self
+      .repetitions
+      .iter()
+      .map(|repetition| repetition.wpm)
+      .sum::<usize>()
+      / self.repetitions.len()
+  }
+
+  /// Returns the average accuracy of the repetitions
+  pub fn get_average_accuracy(&self) -> f64 {
+    if self.repetitions.is_empty() {
+      return 0.0;
+    }
+
+    let accuracy = self
+      .repetitions
+      .iter()
+      .map(|repetition| repetition.accuracy)
+      .sum::<f64>()
+      / self.repetitions.len() as f64;
+
+    (accuracy * 100.0).round() / 100.0
+  }
+
+  /// Returns the WPM gained from the first to the last repetition
+  pub fn get_wpm_improvement(&self) -> i64 {
+    match (self.repetitions.first(), self.repetitions.last()) {
+      (Some(first), Some(last)) => last.wpm as i64 - first.wpm as i64,
+      _ => 0,
+    }
+  }
+}
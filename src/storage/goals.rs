@@ -0,0 +1,193 @@
+use std::collections::BTreeMap;
+
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use super::stats::Stat;
+
+/// Presets of the practice minutes per day goal (0 disables the goal)
+const MINUTES_PER_DAY_PRESETS: [usize; 6] = [0, 5, 10, 15, 30, 60];
+
+/// Presets of the runs per day goal (0 disables the goal)
+const RUNS_PER_DAY_PRESETS: [usize; 5] = [0, 3, 5, 10, 20];
+
+/// Presets of the target WPM goal (0 disables the goal)
+const TARGET_WPM_PRESETS: [usize; 6] = [0, 40, 60, 80, 100, 120];
+
+/// Daily practice goals
+///
+/// A disabled goal has the value 0.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Default)]
+pub struct DailyGoals {
+  pub minutes_per_day: usize,
+
+  pub runs_per_day: usize,
+
+  // WPM reached by at least one run of the day
+  pub target_wpm: usize,
+}
+
+/// Practice of a single day
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct DailyProgress {
+  pub practice_secs: usize,
+
+  pub runs_count: usize,
+
+  pub best_wpm: usize,
+}
+
+impl DailyGoals {
+  /// Switches the minutes per day goal through the presets
+  pub fn switch_minutes_per_day(&mut self) {
+    self.minutes_per_day =
+      DailyGoals::get_next_preset(self.minutes_per_day, &MINUTES_PER_DAY_PRESETS);
+  }
+
+  /// Switches the runs per day goal through the presets
+  pub fn switch_runs_per_day(&mut self) {
+    self.runs_per_day = DailyGoals::get_next_preset(self.runs_per_day, &RUNS_PER_DAY_PRESETS);
+  }
+
+  /// Switches the target WPM goal through the presets
+  pub fn switch_target_wpm(&mut self) {
+    self.target_wpm = DailyGoals::get_next_preset(self.target_wpm, &TARGET_WPM_PRESETS);
+  }
+
+  /// Verifies if any goal is set
+  pub fn is_active(&self) -> bool {
+    *self != DailyGoals::default()
+  }
+
+  /// Verifies if the day progress meets all the set goals
+  ///
+  /// Without goals any practice meets the day.
+  pub fn is_met(&self, progress: &DailyProgress) -> bool {
+    progress.runs_count > 0
+      && progress.practice_secs >= self.minutes_per_day * 60
+      && progress.runs_count >= self.runs_per_day
+      && progress.best_wpm >= self.target_wpm
+  }
+
+  /// Returns the count of the consecutive days meeting the goals
+  ///
+  /// The streak isn't broken by the today, until the day is over.
+  pub fn get_streak(
+    &self,
+    daily_progress: &BTreeMap<NaiveDate, DailyProgress>,
+    today: NaiveDate,
+  ) -> usize {
+    let is_day_met = |date: &NaiveDate| {
+      daily_progress
+        .get(date)
+        .is_some_and(|progress| self.is_met(progress))
+    };
+
+    let mut date = if is_day_met(&today) {
+      today
+    } else {
+      match today.checked_sub_days(Days::new(1)) {
+        Some(yesterday) => yesterday,
+        None => return 0,
+      }
+    };
+
+    let mut streak = 0;
+
+    while is_day_met(&date) {
+      streak += 1;
+
+      match date.checked_sub_days(Days::new(1)) {
+        Some(previous_date) => date = previous_date,
+        None => break,
+      }
+    }
+
+    streak
+  }
+
+  /// Returns the preset following the current value
+  fn get_next_preset(current: usize, presets: &[usize]) -> usize {
+    presets
+      .iter()
+      .find(|preset| **preset > current)
+      .copied()
+      .unwrap_or(presets[0])
+  }
+}
+
+/// Groups the stats by the local date of the run
+///
+/// Stats without a known time are skipped.
+pub fn get_daily_progress<'a, I: IntoIterator<Item = &'a Stat>>(
+  stats: I,
+) -> BTreeMap<NaiveDate, DailyProgress> {
+  let mut daily_progress = BTreeMap::<NaiveDate, DailyProgress>::new();
+
+  for stat in stats {
+    let Some(created_at) = stat.get_created_at() else {
+      continue;
+    };
+
+    let progress = daily_progress.entry(created_at.date_naive()).or_default();
+
+    progress.practice_secs += stat.get_practice_secs();
+    progress.runs_count += 1;
+    progress.best_wpm = progress.best_wpm.max(stat.get_average_wpm());
+  }
+
+  daily_progress
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn get_progress(practice_secs: usize, runs_count: usize, best_wpm: usize) -> DailyProgress {
+    DailyProgress {
+      practice_secs,
+      runs_count,
+      best_wpm,
+    }
+  }
+
+  #[test]
+  fn goals_are_met() {
+    let mut goals = DailyGoals::default();
+    assert!(goals.is_met(&get_progress(15, 1, 20)));
+    assert!(!goals.is_met(&DailyProgress::default()));
+
+    goals.switch_minutes_per_day();
+    goals.switch_target_wpm();
+    assert_eq!(goals.minutes_per_day, 5);
+    assert_eq!(goals.target_wpm, 40);
+
+    assert!(goals.is_met(&get_progress(300, 5, 45)));
+    assert!(!goals.is_met(&get_progress(240, 4, 45)));
+    assert!(!goals.is_met(&get_progress(300, 5, 35)));
+  }
+
+  #[test]
+  fn streak_counts_consecutive_days() {
+    let goals = DailyGoals::default();
+    let today = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+
+    let mut daily_progress = BTreeMap::new();
+
+    for day in [6, 8, 9] {
+      daily_progress.insert(
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap(),
+        get_progress(60, 1, 50),
+      );
+    }
+
+    // Today isn't practiced yet, so the streak continues from yesterday
+    assert_eq!(goals.get_streak(&daily_progress, today), 2);
+
+    daily_progress.insert(today, get_progress(60, 1, 50));
+    assert_eq!(goals.get_streak(&daily_progress, today), 3);
+
+    let next_week = NaiveDate::from_ymd_opt(2025, 3, 17).unwrap();
+    assert_eq!(goals.get_streak(&daily_progress, next_week), 0);
+  }
+}
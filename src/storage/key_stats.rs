@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::stat_helper::StatHelper;
+use crate::keyboard::{Finger, KeyboardLayout};
+
+/// Presses and mistakes of a key
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Default)]
+pub struct KeyCounter {
+  pub presses: usize,
+
+  // Presses of a different character than the expected one
+  pub mistakes: usize,
+}
+
+impl KeyCounter {
+  /// Adds the press of the key
+  pub fn add_press(&mut self, is_mistaken: bool) {
+    self.presses += 1;
+    self.mistakes += is_mistaken as usize;
+  }
+
+  /// Adds the counts of the other counter
+  pub fn add(&mut self, other: &KeyCounter) {
+    self.presses += other.presses;
+    self.mistakes += other.mistakes;
+  }
+
+  /// Returns the accuracy of the presses (None without any press)
+  pub fn get_accuracy(&self) -> Option<f64> {
+    (self.presses > 0).then(|| StatHelper::get_calculated_accuracy(self.presses, self.mistakes))
+  }
+}
+
+/// Persisted key counters of the runs typed on the keyboard layout
+///
+/// Counters are kept by the expected characters, so the mistakes
+/// are counted into the key which should have been pressed.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct KeyStats {
+  // Every keyboard layout has its own key stats (the fingers differ)
+  pub keyboard_layout: KeyboardLayout,
+
+  pub counters: BTreeMap<char, KeyCounter>,
+}
+
+impl KeyStats {
+  pub fn new(keyboard_layout: KeyboardLayout) -> Self {
+    Self {
+      keyboard_layout,
+      counters: BTreeMap::new(),
+    }
+  }
+
+  /// Adds the key counters of a run
+  pub fn add_counters(&mut self, counters: &BTreeMap<char, KeyCounter>) {
+    for (c, counter) in counters {
+      self.counters.entry(*c).or_default().add(counter);
+    }
+  }
+
+  /// Returns the counters of the keys (shifted characters counted into their keys)
+  ///
+  /// Characters outside of the keyboard model are skipped.
+  pub fn get_key_counters(&self) -> BTreeMap<char, KeyCounter> {
+    let mut key_counters = BTreeMap::<char, KeyCounter>::new();
+
+    for (c, counter) in &self.counters {
+      if let Some(key) = self.keyboard_layout.get_key(*c) {
+        key_counters.entry(key).or_default().add(counter);
+      }
+    }
+
+    key_counters
+  }
+
+  /// Returns the counters of all the fingers (from the left to the right)
+  pub fn get_finger_counters(&self) -> Vec<(Finger, KeyCounter)> {
+    Finger::all()
+      .iter()
+      .map(|finger| {
+        let mut finger_counter = KeyCounter::default();
+
+        self
+          .counters
+          .iter()
+          .filter(|(c, _)| self.keyboard_layout.get_finger(**c) == Some(*finger))
+          .for_each(|(_, counter)| finger_counter.add(counter));
+
+        (*finger, finger_counter)
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn key_and_finger_counters() {
+    let mut run_counters = BTreeMap::<char, KeyCounter>::new();
+
+    for (c, is_mistaken) in [
+      ('a', false),
+      ('A', true),
+      ('r', false),
+      ('r', true),
+      (' ', false),
+    ] {
+      run_counters.entry(c).or_default().add_press(is_mistaken);
+    }
+
+    let mut key_stats = KeyStats::new(KeyboardLayout::Colemak);
+    key_stats.add_counters(&run_counters);
+    key_stats.add_counters(&run_counters);
+
+    let key_counters = key_stats.get_key_counters();
+    assert_eq!(
+      key_counters.get(&'a'),
+      Some(&KeyCounter {
+        presses: 4,
+        mistakes: 2
+      })
+    );
+    assert_eq!(key_counters.get(&'r').unwrap().get_accuracy(), Some(50.0));
+    assert_eq!(KeyCounter::default().get_accuracy(), None);
+
+    let finger_counters = key_stats.get_finger_counters();
+    assert_eq!(finger_counters.len(), Finger::all().len());
+    assert_eq!(
+      finger_counters[0],
+      (
+        Finger::LeftPinky,
+        KeyCounter {
+          presses: 4,
+          mistakes: 2
+        }
+      )
+    );
+    assert_eq!(finger_counters[1].1.presses, 4);
+    assert_eq!(finger_counters[8].1.presses, 2);
+  }
+}
@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+
+use crate::engine::TypedText;
+
+/// Key pressed during the run
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Keystroke {
+  Char(char),
+  Backspace,
+  DeleteWord,
+}
+
+/// Control character logged for the backspace
+const BACKSPACE_CHAR: char = '\u{8}';
+
+/// Control character logged for the word deletion (`ctrl-w`)
+const DELETE_WORD_CHAR: char = '\u{17}';
+
+impl Keystroke {
+  /// Returns the character the keystroke is logged as
+  fn to_char(self) -> char {
+    match self {
+      Keystroke::Char(c) => c,
+      Keystroke::Backspace => BACKSPACE_CHAR,
+      Keystroke::DeleteWord => DELETE_WORD_CHAR,
+    }
+  }
+
+  /// Returns the keystroke from the logged character
+  fn from_char(c: char) -> Self {
+    match c {
+      BACKSPACE_CHAR => Keystroke::Backspace,
+      DELETE_WORD_CHAR => Keystroke::DeleteWord,
+      c => Keystroke::Char(c),
+    }
+  }
+}
+
+/// Keystroke of the run with its timing
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct LoggedKeystroke {
+  // Milliseconds from the run start
+  pub elapsed_ms: u32,
+
+  pub keystroke: Keystroke,
+
+  // Typed character matches the text (corrections are always correct)
+  pub is_correct: bool,
+}
+
+/// Keystrokes of the run with their timing
+///
+/// Used to replay the run (e.g. the ghost caret or the replay screen).
+///
+/// Stored compactly, keys are a string (control characters
+/// for the corrections) and the timing are delays between keystrokes.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct KeystrokeLog {
+  // Generated text of the run
+  text: String,
+
+  // Pressed keys
+  keys: String,
+
+  // Milliseconds since the previous keystroke (capped to the u16 max)
+  delays_ms: Vec<u16>,
+
+  // Milliseconds from the run start of the last keystroke (recording only)
+  #[serde(skip)]
+  last_elapsed_ms: u32,
+}
+
+impl KeystrokeLog {
+  /// Creates an empty log of the text
+  pub fn new(text: String) -> Self {
+    Self {
+      text,
+      ..Default::default()
+    }
+  }
+
+  /// Adds the keystroke pressed after the elapsed milliseconds
+  pub fn push(&mut self, elapsed_ms: u32, keystroke: Keystroke) {
+    let delay_ms = elapsed_ms.saturating_sub(self.last_elapsed_ms);
+
+    self.keys.push(keystroke.to_char());
+    self.delays_ms.push(delay_ms.min(u16::MAX as u32) as u16);
+    self.last_elapsed_ms = elapsed_ms;
+  }
+
+  /// Returns the keystrokes with the time from the run start
+  ///
+  /// Also validates the typed characters against the text.
+  pub fn get_keystrokes(&self) -> Vec<LoggedKeystroke> {
+    let mut typed_text = TypedText::new(&self.text);
+    let mut elapsed_ms = 0_u32;
+
+    self
+      .keys
+      .chars()
+      .zip(self.delays_ms.iter())
+      .map(|(key, delay_ms)| {
+        elapsed_ms += *delay_ms as u32;
+
+        let keystroke = Keystroke::from_char(key);
+
+        LoggedKeystroke {
+          elapsed_ms,
+          keystroke,
+          is_correct: typed_text.apply(keystroke),
+        }
+      })
+      .collect()
+  }
+
+  /// Returns the generated text of the run
+  pub fn get_text(&self) -> &String {
+    &self.text
+  }
+
+  /// Returns the cursor index after every keystroke
+  ///
+  /// Items are the milliseconds from the run start and the cursor index.
+  pub fn get_cursor_timeline(&self) -> Vec<(u32, usize)> {
+    let mut typed_text = TypedText::new(&self.text);
+
+    self
+      .get_keystrokes()
+      .iter()
+      .map(|logged_keystroke| {
+        typed_text.apply(logged_keystroke.keystroke);
+        (logged_keystroke.elapsed_ms, typed_text.get_cursor_index())
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn cursor_timeline_follows_corrections() {
+    let mut keystroke_log = KeystrokeLog::new(String::from("ab cd"));
+
+    for (elapsed_ms, keystroke) in [
+      (100, Keystroke::Char('a')),
+      (200, Keystroke::Char('b')),
+      (300, Keystroke::Char(' ')),
+      (400, Keystroke::Char('x')),
+      (500, Keystroke::Backspace),
+      (600, Keystroke::Char('c')),
+      (700, Keystroke::DeleteWord),
+    ] {
+      keystroke_log.push(elapsed_ms, keystroke);
+    }
+
+    let cursor_indexes = keystroke_log
+      .get_cursor_timeline()
+      .iter()
+      .map(|(_, cursor_index)| *cursor_index)
+      .collect::<Vec<usize>>();
+
+    assert_eq!(cursor_indexes, vec![1, 2, 3, 4, 3, 4, 3]);
+
+    let keystrokes = keystroke_log.get_keystrokes();
+    assert_eq!(keystrokes[4].keystroke, Keystroke::Backspace);
+    assert_eq!(keystrokes[6].elapsed_ms, 700);
+
+    let incorrect_count = keystrokes
+      .iter()
+      .filter(|logged_keystroke| !logged_keystroke.is_correct)
+      .count();
+
+    assert_eq!(incorrect_count, 1);
+  }
+}
@@ -0,0 +1,252 @@
+use serde::Deserialize;
+
+use crate::config::{Difficulty, PaceCaret, TukaiLayoutName, TypingDuration, Visibility};
+use crate::keyboard::KeyboardLayout;
+
+use super::{
+  achievements::UnlockedAchievement,
+  drills::DrillResult,
+  goals::DailyGoals,
+  lessons::{Lesson, LessonProgress},
+  stats::{RunMode, Stat},
+  storage_handler::StorageData,
+};
+
+/// Stat stored by the versions without the storage file header
+///
+/// bincode isn't a self-describing format, so the older data
+/// can't be decoded into the current types directly.
+#[derive(Deserialize)]
+pub struct LegacyStat {
+  typing_duration: TypingDuration,
+
+  average_wpm: usize,
+
+  raw_wpm: usize,
+
+  accuracy: f64,
+}
+
+/// Storage data stored by the versions without the storage file header
+pub type LegacyStorageData = (
+  Vec<LegacyStat>,
+  TypingDuration,
+  TukaiLayoutName,
+  bool,
+  usize,
+);
+
+/// Stat stored by the storage format version 1
+#[derive(Deserialize)]
+pub struct StatV1 {
+  typing_duration: TypingDuration,
+
+  average_wpm: usize,
+
+  raw_wpm: usize,
+
+  accuracy: f64,
+
+  language: String,
+
+  mode: RunMode,
+
+  created_at: Option<i64>,
+}
+
+/// Storage data stored by the storage format version 1
+pub type StorageDataV1 = (Vec<StatV1>, TypingDuration, TukaiLayoutName, bool, usize);
+
+/// Lesson progress stored by the storage format version 2 (shared by the keyboard layouts)
+#[derive(Deserialize)]
+pub struct LessonProgressV2 {
+  lesson: Lesson,
+
+  attempts: usize,
+
+  best_wpm: usize,
+
+  best_accuracy: f64,
+
+  is_passed: bool,
+}
+
+/// Storage data stored by the storage format version 2
+pub type StorageDataV2 = (
+  Vec<Stat>,
+  TypingDuration,
+  TukaiLayoutName,
+  bool,
+  usize,
+  DailyGoals,
+  Vec<UnlockedAchievement>,
+  PaceCaret,
+  bool,
+  Difficulty,
+  Visibility,
+  Vec<DrillResult>,
+  Vec<LessonProgressV2>,
+  KeyboardLayout,
+  bool,
+);
+
+/// Storage data stored by the storage format version 3
+pub type StorageDataV3 = (
+  Vec<Stat>,
+  TypingDuration,
+  TukaiLayoutName,
+  bool,
+  usize,
+  DailyGoals,
+  Vec<UnlockedAchievement>,
+  PaceCaret,
+  bool,
+  Difficulty,
+  Visibility,
+  Vec<DrillResult>,
+  Vec<LessonProgress>,
+  KeyboardLayout,
+  bool,
+);
+
+/// Converts the legacy storage data into the current storage data
+pub fn migrate_legacy_data(legacy_data: LegacyStorageData) -> StorageData {
+  let (legacy_stats, typing_duration, layout_name, has_transparent_bg, language_index) =
+    legacy_data;
+
+  let stats = legacy_stats
+    .into_iter()
+    .map(|legacy_stat| {
+      Stat::from_legacy(
+        legacy_stat.typing_duration,
+        legacy_stat.average_wpm,
+        legacy_stat.raw_wpm,
+        legacy_stat.accuracy,
+      )
+    })
+    .collect::<Vec<Stat>>();
+
+  with_default_preferences(
+    stats,
+    typing_duration,
+    layout_name,
+    has_transparent_bg,
+    language_index,
+  )
+}
+
+/// Converts the storage data of the format version 1 into the current storage data
+pub fn migrate_v1_data(data: StorageDataV1) -> StorageData {
+  let (stats_v1, typing_duration, layout_name, has_transparent_bg, language_index) = data;
+
+  let stats = stats_v1
+    .into_iter()
+    .map(|stat| {
+      Stat::from_v1(
+        stat.typing_duration,
+        stat.average_wpm,
+        stat.raw_wpm,
+        stat.accuracy,
+        stat.language,
+        stat.mode,
+        stat.created_at,
+      )
+    })
+    .collect::<Vec<Stat>>();
+
+  with_default_preferences(
+    stats,
+    typing_duration,
+    layout_name,
+    has_transparent_bg,
+    language_index,
+  )
+}
+
+/// Converts the storage data of the format version 2 into the format version 3
+///
+/// The lesson progress is kept for the keyboard layout selected in the data.
+pub fn migrate_v2_data(data: StorageDataV2) -> StorageDataV3 {
+  let keyboard_layout = data.13;
+
+  let lessons_progress = data
+    .12
+    .into_iter()
+    .map(|progress| LessonProgress {
+      keyboard_layout,
+      lesson: progress.lesson,
+      attempts: progress.attempts,
+      best_wpm: progress.best_wpm,
+      best_accuracy: progress.best_accuracy,
+      is_passed: progress.is_passed,
+    })
+    .collect::<Vec<LessonProgress>>();
+
+  (
+    data.0,
+    data.1,
+    data.2,
+    data.3,
+    data.4,
+    data.5,
+    data.6,
+    data.7,
+    data.8,
+    data.9,
+    data.10,
+    data.11,
+    lessons_progress,
+    data.13,
+    data.14,
+  )
+}
+
+/// Converts the storage data of the format version 3 into the current storage data
+pub fn migrate_v3_data(data: StorageDataV3) -> StorageData {
+  (
+    data.0,
+    data.1,
+    data.2,
+    data.3,
+    data.4,
+    data.5,
+    data.6,
+    data.7,
+    data.8,
+    data.9,
+    data.10,
+    data.11,
+    data.12,
+    data.13,
+    data.14,
+    Vec::new(),
+  )
+}
+
+/// Creates the current storage data with the defaults of the data missing in the older formats
+fn with_default_preferences(
+  stats: Vec<Stat>,
+  typing_duration: TypingDuration,
+  layout_name: TukaiLayoutName,
+  has_transparent_bg: bool,
+  language_index: usize,
+) -> StorageData {
+  (
+    stats,
+    typing_duration,
+    layout_name,
+    has_transparent_bg,
+    language_index,
+    DailyGoals::default(),
+    Vec::new(),
+    PaceCaret::default(),
+    false,
+    Difficulty::default(),
+    Visibility::default(),
+    Vec::new(),
+    Vec::new(),
+    KeyboardLayout::default(),
+    false,
+    Vec::new(),
+  )
+}
@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+
+use super::stat_helper::StatHelper;
+use crate::keyboard::{KeyRow, KeyboardLayout};
+
+/// Lesson of the touch typing curriculum
+///
+/// Every lesson adds new keys to the keys of the previous lessons.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub enum Lesson {
+  HomeRow,
+  TopRow,
+  BottomRow,
+  Numbers,
+  Symbols,
+}
+
+impl Lesson {
+  /// Returns all the lessons in the order of the progression
+  pub fn all() -> &'static [Lesson] {
+    use Lesson::*;
+
+    &[HomeRow, TopRow, BottomRow, Numbers, Symbols]
+  }
+
+  pub fn get_title(&self) -> &'static str {
+    use Lesson::*;
+
+    match self {
+      HomeRow => "Home row",
+      TopRow => "Top row",
+      BottomRow => "Bottom row",
+      Numbers => "Numbers",
+      Symbols => "Symbols",
+    }
+  }
+
+  /// Returns the keys introduced by the lesson
+  ///
+  /// Letters of the rows depend on the physical keyboard layout.
+  pub fn get_new_keys(&self, keyboard_layout: KeyboardLayout) -> String {
+    use Lesson::*;
+
+    match self {
+      HomeRow => keyboard_layout.get_row_letters(KeyRow::Home),
+      TopRow => keyboard_layout.get_row_letters(KeyRow::Top),
+      BottomRow => keyboard_layout.get_row_letters(KeyRow::Bottom),
+      Numbers => String::from("1234567890"),
+      Symbols => String::from(",.;:'!?-()/"),
+    }
+  }
+
+  /// Returns the keys unlocked by the lesson (including the previous lessons)
+  pub fn get_unlocked_keys(&self, keyboard_layout: KeyboardLayout) -> String {
+    Lesson::all()
+      .iter()
+      .take_while(|lesson| *lesson != self)
+      .chain([self])
+      .map(|lesson| lesson.get_new_keys(keyboard_layout))
+      .collect()
+  }
+
+  /// Returns the minimal WPM and accuracy passing the lesson
+  pub fn get_pass_thresholds(&self) -> (usize, f64) {
+    use Lesson::*;
+
+    match self {
+      HomeRow => (15, 90.0),
+      TopRow => (20, 90.0),
+      BottomRow => (20, 92.0),
+      Numbers => (15, 90.0),
+      Symbols => (15, 90.0),
+    }
+  }
+
+  /// Verifies if the result passes the lesson
+  pub fn is_passed_by(&self, result: &LessonResult) -> bool {
+    let (pass_wpm, pass_accuracy) = self.get_pass_thresholds();
+
+    result.wpm >= pass_wpm && result.accuracy >= pass_accuracy
+  }
+
+  /// Verifies if the lesson is unlocked (the previous lesson is passed)
+  pub fn is_unlocked(&self, lessons_progress: &[LessonProgress]) -> bool {
+    let Some(index) = Lesson::all().iter().position(|lesson| lesson == self) else {
+      return false;
+    };
+
+    index == 0
+      || lessons_progress
+        .iter()
+        .any(|progress| progress.lesson == Lesson::all()[index - 1] && progress.is_passed)
+  }
+}
+
+/// Result of a typed lesson exercise
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct LessonResult {
+  pub wpm: usize,
+
+  // Corrected errors lower the accuracy too
+  pub accuracy: f64,
+}
+
+impl LessonResult {
+  /// Creates the result of the exercise typed in the milliseconds
+  pub fn new(
+    chars_counter: usize,
+    mistakes_counter: usize,
+    corrected_errors: usize,
+    elapsed_ms: u32,
+  ) -> Self {
+    let correct_chars = chars_counter.saturating_sub(mistakes_counter);
+    let wpm = correct_chars as f64 / 5.0 * 60_000.0 / elapsed_ms.max(1) as f64;
+
+    let typed_chars = chars_counter + corrected_errors;
+
+    let accuracy = if typed_chars == 0 {
+      0.0
+    } else {
+      StatHelper::get_calculated_accuracy(typed_chars, mistakes_counter + corrected_errors).max(0.0)
+    };
+
+    Self {
+      wpm: wpm.round() as usize,
+      accuracy,
+    }
+  }
+}
+
+/// Persisted progress of the lesson
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct LessonProgress {
+  // Every keyboard layout has its own progress (the rows differ)
+  pub keyboard_layout: KeyboardLayout,
+
+  pub lesson: Lesson,
+
+  pub attempts: usize,
+
+  pub best_wpm: usize,
+
+  pub best_accuracy: f64,
+
+  pub is_passed: bool,
+}
+
+impl LessonProgress {
+  pub fn new(keyboard_layout: KeyboardLayout, lesson: Lesson) -> Self {
+    Self {
+      keyboard_layout,
+      lesson,
+      attempts: 0,
+      best_wpm: 0,
+      best_accuracy: 0.0,
+      is_passed: false,
+    }
+  }
+
+  /// Adds the result of the exercise
+  ///
+  /// Returns whether the result passes the lesson.
+  pub fn add_result(&mut self, result: &LessonResult) -> bool {
+    let is_passed = self.lesson.is_passed_by(result);
+
+    self.attempts += 1;
+    self.best_wpm = self.best_wpm.max(result.wpm);
+    self.best_accuracy = self.best_accuracy.max(result.accuracy);
+    self.is_passed |= is_passed;
+
+    is_passed
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn lessons_unlocked_by_progress() {
+    assert_eq!(
+      Lesson::TopRow.get_unlocked_keys(KeyboardLayout::Qwerty),
+      "asdfghjklqwertyuiop"
+    );
+    assert_eq!(
+      Lesson::TopRow.get_unlocked_keys(KeyboardLayout::Colemak),
+      "arstdhneioqwfpgjluy"
+    );
+
+    let mut progress = LessonProgress::new(KeyboardLayout::Qwerty, Lesson::HomeRow);
+
+    assert!(Lesson::HomeRow.is_unlocked(&[]));
+    assert!(!Lesson::TopRow.is_unlocked(std::slice::from_ref(&progress)));
+
+    // 100 correct characters in a minute, 20 corrected errors
+    let failed = LessonResult::new(100, 0, 20, 60_000);
+    assert_eq!(failed.wpm, 20);
+    assert_eq!(failed.accuracy, 83.33);
+    assert!(!progress.add_result(&failed));
+
+    let passed = LessonResult::new(100, 2, 2, 60_000);
+    assert!(progress.add_result(&passed));
+
+    assert_eq!(progress.attempts, 2);
+    assert!(Lesson::TopRow.is_unlocked(&[progress]));
+  }
+}
@@ -1,3 +1,11 @@
+pub mod achievements;
+pub mod drills;
+pub mod goals;
+pub mod key_stats;
+pub mod keystroke_log;
+pub mod legacy;
+pub mod lessons;
 pub mod stat_helper;
 pub mod stats;
+pub mod stats_filter;
 pub mod storage_handler;
@@ -22,4 +22,70 @@ impl StatHelper {
       ((chars_counter as f64 - mistakes_counter as f64) / chars_counter as f64) * 100.0;
     (accuracy * 100.0).round() / 100.0
   }
+
+  /// Calculates the rolling average of the values
+  ///
+  /// Every value is averaged with up to `window - 1` preceding values.
+  pub fn get_rolling_average(values: &[f64], window: usize) -> Vec<f64> {
+    let window = window.max(1);
+    let mut sum = 0.0;
+
+    values
+      .iter()
+      .enumerate()
+      .map(|(index, value)| {
+        sum += value;
+
+        if index >= window {
+          sum -= values[index - window];
+        }
+
+        sum / (index + 1).min(window) as f64
+      })
+      .collect()
+  }
+
+  /// Calculates consistency of the per-second WPM
+  ///
+  /// Consistency is `100 * (1 - CV)` where CV is the coefficient
+  /// of variation (standard deviation / mean), clamped to 0..=100.
+  pub fn get_calculated_consistency(per_second_wpm: &[f64]) -> f64 {
+    if per_second_wpm.is_empty() {
+      return 0.0;
+    }
+
+    let count = per_second_wpm.len() as f64;
+    let mean = per_second_wpm.iter().sum::<f64>() / count;
+
+    if mean == 0.0 {
+      return 0.0;
+    }
+
+    let variance = per_second_wpm
+      .iter()
+      .map(|wpm| (wpm - mean).powi(2))
+      .sum::<f64>()
+      / count;
+
+    let consistency = ((1.0 - variance.sqrt() / mean) * 100.0).clamp(0.0, 100.0);
+    (consistency * 100.0).round() / 100.0
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn consistency_of_steady_and_uneven_pace() {
+    assert_eq!(
+      StatHelper::get_calculated_consistency(&[60.0, 60.0, 60.0]),
+      100.0
+    );
+    assert_eq!(StatHelper::get_calculated_consistency(&[0.0, 120.0]), 0.0);
+    assert_eq!(StatHelper::get_calculated_consistency(&[]), 0.0);
+
+    let consistency = StatHelper::get_calculated_consistency(&[48.0, 60.0, 72.0]);
+    assert!(consistency > 80.0 && consistency < 90.0);
+  }
 }
@@ -1,11 +1,139 @@
-use super::stat_helper::StatHelper;
-use crate::config::TypingDuration;
+use std::fmt::Display;
+
+use super::{keystroke_log::KeystrokeLog, stat_helper::StatHelper};
+use crate::config::{Difficulty, TypingDuration, Visibility};
+use chrono::{DateTime, Local, Utc};
 use ratatui::{
   style::{Color, Style},
   text::{Line, Span},
 };
 use serde::{Deserialize, Serialize};
 
+/// Mode in which the run was typed
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Debug, Clone, Copy, Default)]
+pub enum RunMode {
+  /// Generated text typed for the selected duration
+  #[default]
+  Timed,
+
+  /// Generated text of the host typed in a network race
+  Race,
+
+  /// Free typing without a text, stopped by the user
+  Zen,
+}
+
+impl RunMode {
+  /// Returns all the modes
+  pub fn all() -> &'static [RunMode] {
+    &[RunMode::Timed, RunMode::Race, RunMode::Zen]
+  }
+}
+
+impl Display for RunMode {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let display_text = match self {
+      RunMode::Timed => "Timed",
+      RunMode::Race => "Race",
+      RunMode::Zen => "Zen",
+    };
+
+    write!(f, "{display_text}")
+  }
+}
+
+/// Advanced metrics of the run
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct RunMetrics {
+  // Consistency of the per-second WPM in percents (see `StatHelper::get_calculated_consistency`)
+  pub consistency: f64,
+
+  // Mistakes fixed during the run
+  pub corrected_errors: usize,
+
+  // Mistakes left in the text at the end of the run
+  pub uncorrected_errors: usize,
+
+  // Characters typed in place of a space
+  pub extra_chars: usize,
+
+  // Characters skipped by typing a space too early
+  pub missed_chars: usize,
+
+  // All the pressed keys (including backspaces)
+  pub keystrokes: usize,
+
+  // Milliseconds from the run start to the first mistake
+  pub time_to_first_error_ms: Option<u64>,
+}
+
+impl RunMetrics {
+  /// Returns the time to the first error formatted for the UI
+  pub fn get_time_to_first_error_pretty(&self) -> String {
+    self
+      .time_to_first_error_ms
+      .map(|ms| format!("{:.1}s", ms as f64 / 1000.0))
+      .unwrap_or(String::from("-"))
+  }
+}
+
+/// Irregularities detected during the run
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct StatFlags {
+  // Text was pasted during the run (the pasted text is rejected)
+  pub has_paste: bool,
+
+  // Terminal lost the focus during the run
+  pub has_focus_loss: bool,
+
+  // Run was paused (the paused time isn't counted)
+  pub was_paused: bool,
+
+  // No keystrokes for a while during the run (the run is invalid)
+  pub is_afk: bool,
+}
+
+impl StatFlags {
+  /// Verifies if any irregularity was detected
+  pub fn is_flagged(&self) -> bool {
+    self.has_paste || self.has_focus_loss || self.was_paused || self.is_afk
+  }
+
+  /// Verifies if the run is invalid
+  ///
+  /// Invalid runs are excluded from the overview and the best scores.
+  pub fn is_invalid(&self) -> bool {
+    self.is_afk
+  }
+
+  /// Returns the detected irregularities formatted for the UI
+  pub fn get_pretty(&self) -> String {
+    let mut labels = Vec::new();
+
+    if self.has_paste {
+      labels.push("paste");
+    }
+
+    if self.has_focus_loss {
+      labels.push("focus lost");
+    }
+
+    if self.was_paused {
+      labels.push("paused");
+    }
+
+    if self.is_afk {
+      labels.push("AFK (invalid)");
+    }
+
+    if labels.is_empty() {
+      String::from("-")
+    } else {
+      labels.join(", ")
+    }
+  }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Stat {
   typing_duration: TypingDuration,
@@ -15,6 +143,32 @@ pub struct Stat {
   raw_wpm: usize,
 
   accuracy: f64,
+
+  // Language code of the typed text (empty for older stats)
+  language: String,
+
+  mode: RunMode,
+
+  // Unix timestamp of the run completion (None for older stats)
+  created_at: Option<i64>,
+
+  // Advanced metrics (None for older stats)
+  metrics: Option<RunMetrics>,
+
+  // Keystrokes with their timing (None for older stats)
+  keystroke_log: Option<KeystrokeLog>,
+
+  // Irregularities of the run
+  flags: StatFlags,
+
+  // Difficulty the run was typed with
+  difficulty: Difficulty,
+
+  // Visibility of the text during the run
+  visibility: Visibility,
+
+  // Typed seconds of the untimed run (zen mode) or of the run ended early
+  elapsed_secs: Option<usize>,
 }
 
 impl Stat {
@@ -26,6 +180,7 @@ impl Stat {
   ///   * Accuracy
   pub fn new(
     typing_duration: TypingDuration,
+    language: String,
     chars_counter: usize,
     mistakes_counter: usize,
   ) -> Self {
@@ -40,9 +195,199 @@ impl Stat {
       ),
       raw_wpm: StatHelper::get_calculated_raw_wpm(chars_counter, typing_duration_in_seconds),
       accuracy: StatHelper::get_calculated_accuracy(chars_counter, mistakes_counter),
+      language,
+      mode: RunMode::Timed,
+      created_at: Some(Utc::now().timestamp()),
+      metrics: None,
+      keystroke_log: None,
+      flags: StatFlags::default(),
+      difficulty: Difficulty::default(),
+      visibility: Visibility::default(),
+      elapsed_secs: None,
     }
   }
 
+  /// Creates a Stat of the run ended before its duration (completed text or a failure)
+  ///
+  /// WPM is calculated from the typed seconds instead of the whole duration.
+  pub fn new_ended_early(
+    typing_duration: TypingDuration,
+    elapsed_secs: usize,
+    language: String,
+    chars_counter: usize,
+    mistakes_counter: usize,
+  ) -> Self {
+    let elapsed_secs = elapsed_secs.clamp(1, typing_duration.as_seconds());
+
+    Self {
+      average_wpm: StatHelper::get_calculated_wpm(chars_counter, mistakes_counter, elapsed_secs),
+      raw_wpm: StatHelper::get_calculated_raw_wpm(chars_counter, elapsed_secs),
+      elapsed_secs: Some(elapsed_secs),
+      ..Stat::new(typing_duration, language, chars_counter, mistakes_counter)
+    }
+  }
+
+  /// Creates a Stat of the untimed run (zen mode)
+  ///
+  /// WPM is calculated from the typed seconds, the free text has no mistakes.
+  pub fn new_untimed(elapsed_secs: usize, language: String, chars_counter: usize) -> Self {
+    let elapsed_secs = elapsed_secs.max(1);
+
+    Self {
+      average_wpm: StatHelper::get_calculated_wpm(chars_counter, 0, elapsed_secs),
+      raw_wpm: StatHelper::get_calculated_raw_wpm(chars_counter, elapsed_secs),
+      accuracy: 100.0,
+      language,
+      mode: RunMode::Zen,
+      created_at: Some(Utc::now().timestamp()),
+      elapsed_secs: Some(elapsed_secs),
+      ..Default::default()
+    }
+  }
+
+  /// Sets the advanced metrics of the run
+  pub fn with_metrics(mut self, metrics: RunMetrics) -> Self {
+    self.metrics = Some(metrics);
+    self
+  }
+
+  /// Sets the mode of the run
+  pub fn with_mode(mut self, mode: RunMode) -> Self {
+    self.mode = mode;
+    self
+  }
+
+  /// Sets the keystrokes of the run
+  pub fn with_keystroke_log(mut self, keystroke_log: KeystrokeLog) -> Self {
+    self.keystroke_log = Some(keystroke_log);
+    self
+  }
+
+  /// Sets the irregularities of the run
+  pub fn with_flags(mut self, flags: StatFlags) -> Self {
+    self.flags = flags;
+    self
+  }
+
+  /// Sets the difficulty of the run
+  pub fn with_difficulty(mut self, difficulty: Difficulty) -> Self {
+    self.difficulty = difficulty;
+    self
+  }
+
+  /// Sets the visibility of the text during the run
+  pub fn with_visibility(mut self, visibility: Visibility) -> Self {
+    self.visibility = visibility;
+    self
+  }
+
+  /// Creates a Stat from the legacy storage data
+  ///
+  /// Language and time of the legacy stats are unknown.
+  pub fn from_legacy(
+    typing_duration: TypingDuration,
+    average_wpm: usize,
+    raw_wpm: usize,
+    accuracy: f64,
+  ) -> Self {
+    Self {
+      typing_duration,
+      average_wpm,
+      raw_wpm,
+      accuracy,
+      ..Default::default()
+    }
+  }
+
+  /// Creates a Stat from the storage format version 1
+  ///
+  /// Metrics and keystrokes of the older stats are unknown.
+  pub fn from_v1(
+    typing_duration: TypingDuration,
+    average_wpm: usize,
+    raw_wpm: usize,
+    accuracy: f64,
+    language: String,
+    mode: RunMode,
+    created_at: Option<i64>,
+  ) -> Self {
+    Self {
+      typing_duration,
+      average_wpm,
+      raw_wpm,
+      accuracy,
+      language,
+      mode,
+      created_at,
+      ..Default::default()
+    }
+  }
+
+  /// Returns the typing duration
+  pub fn get_typing_duration(&self) -> &TypingDuration {
+    &self.typing_duration
+  }
+
+  /// Returns the language code
+  pub fn get_language(&self) -> &String {
+    &self.language
+  }
+
+  /// Returns the run mode
+  pub fn get_mode(&self) -> RunMode {
+    self.mode
+  }
+
+  /// Returns the local date time of the run
+  pub fn get_created_at(&self) -> Option<DateTime<Local>> {
+    self
+      .created_at
+      .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+      .map(|date_time| date_time.with_timezone(&Local))
+  }
+
+  /// Returns the date time formatted for the UI
+  pub fn get_created_at_pretty(&self) -> String {
+    self
+      .get_created_at()
+      .map(|date_time| date_time.format("%Y-%m-%d %H:%M").to_string())
+      .unwrap_or(String::from("-"))
+  }
+
+  /// Returns the advanced metrics
+  pub fn get_metrics(&self) -> Option<&RunMetrics> {
+    self.metrics.as_ref()
+  }
+
+  /// Returns the keystrokes of the run
+  pub fn get_keystroke_log(&self) -> Option<&KeystrokeLog> {
+    self.keystroke_log.as_ref()
+  }
+
+  /// Returns the difficulty of the run
+  pub fn get_difficulty(&self) -> Difficulty {
+    self.difficulty
+  }
+
+  /// Returns the visibility of the text during the run
+  pub fn get_visibility(&self) -> Visibility {
+    self.visibility
+  }
+
+  /// Returns the irregularities of the run
+  pub fn get_flags(&self) -> &StatFlags {
+    &self.flags
+  }
+
+  /// Returns the consistency formatted for the UI
+  pub fn get_consistency_pretty(&self) -> String {
+    self
+      .metrics
+      .as_ref()
+      .map(|metrics| format!("{:.0}%", metrics.consistency))
+      .unwrap_or(String::from("-"))
+  }
+
   /// Returns the average wpm
   pub fn get_average_wpm(&self) -> usize {
     self.average_wpm
@@ -62,12 +407,38 @@ impl Stat {
 
   /// Returns the duration
   pub fn get_duration_pretty(&self) -> Line<'static> {
+    match self.elapsed_secs {
+      Some(elapsed_secs) if self.mode == RunMode::Zen => {
+        return Line::from(vec![
+          Span::from(format!("{elapsed_secs}s")),
+          Span::from(" (Zen)").style(Style::default().fg(Color::Magenta)),
+        ]);
+      }
+      Some(elapsed_secs) => {
+        return Line::from(vec![
+          Span::from(format!(
+            "{elapsed_secs}/{}s",
+            self.typing_duration.as_seconds()
+          )),
+          self.get_time_difficulty(),
+        ]);
+      }
+      None => {}
+    }
+
     Line::from(vec![
       Span::from(format!("{}s", self.typing_duration.as_seconds())),
       self.get_time_difficulty(),
     ])
   }
 
+  /// Returns the time spent typing the run in seconds
+  pub fn get_practice_secs(&self) -> usize {
+    self
+      .elapsed_secs
+      .unwrap_or_else(|| self.typing_duration.as_seconds())
+  }
+
   /// Returns the raw WPM
   pub fn get_raw_wpm(&self) -> usize {
     self.raw_wpm
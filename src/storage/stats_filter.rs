@@ -0,0 +1,228 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Days, Local, Months, NaiveTime};
+
+use crate::config::TypingDuration;
+
+use super::stats::{RunMode, Stat};
+
+/// Date range of the displayed stats
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Default)]
+pub enum DateRange {
+  #[default]
+  AllTime,
+  Today,
+  LastWeek,
+  LastMonth,
+  LastYear,
+}
+
+impl DateRange {
+  /// Returns the next date range (used for a switching)
+  pub fn next(&self) -> Self {
+    use DateRange::*;
+
+    match self {
+      AllTime => Today,
+      Today => LastWeek,
+      LastWeek => LastMonth,
+      LastMonth => LastYear,
+      LastYear => AllTime,
+    }
+  }
+
+  /// Returns the start of the date range
+  ///
+  /// None if the range is unbounded.
+  pub fn get_start(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    use DateRange::*;
+
+    let today_start = now.with_time(NaiveTime::MIN).earliest().unwrap_or(now);
+
+    match self {
+      AllTime => None,
+      Today => Some(today_start),
+      LastWeek => today_start.checked_sub_days(Days::new(6)),
+      LastMonth => today_start.checked_sub_months(Months::new(1)),
+      LastYear => today_start.checked_sub_months(Months::new(12)),
+    }
+  }
+}
+
+impl Display for DateRange {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    use DateRange::*;
+
+    let display_text = match self {
+      AllTime => "all time",
+      Today => "today",
+      LastWeek => "last 7 days",
+      LastMonth => "last month",
+      LastYear => "last year",
+    };
+
+    write!(f, "{display_text}")
+  }
+}
+
+/// Filter of the stats displayed on the stats screen
+///
+/// `None` values match all stats.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Default)]
+pub struct StatsFilter {
+  pub typing_duration: Option<TypingDuration>,
+
+  pub language: Option<String>,
+
+  pub mode: Option<RunMode>,
+
+  pub date_range: DateRange,
+}
+
+impl StatsFilter {
+  /// Verifies if the stat matches the filter
+  ///
+  /// Stats without a known time are matched only by the `AllTime` range.
+  pub fn matches(&self, stat: &Stat, now: DateTime<Local>) -> bool {
+    // Zen runs have no duration
+    if let Some(typing_duration) = &self.typing_duration
+      && (stat.get_typing_duration() != typing_duration || stat.get_mode() == RunMode::Zen)
+    {
+      return false;
+    }
+
+    if let Some(language) = &self.language
+      && stat.get_language() != language
+    {
+      return false;
+    }
+
+    if let Some(mode) = self.mode
+      && stat.get_mode() != mode
+    {
+      return false;
+    }
+
+    match self.date_range.get_start(now) {
+      Some(start) => stat
+        .get_created_at()
+        .is_some_and(|created_at| created_at >= start),
+      None => true,
+    }
+  }
+
+  /// Verifies if any filter is set
+  pub fn is_active(&self) -> bool {
+    *self != StatsFilter::default()
+  }
+
+  /// Switches the typing duration filter
+  ///
+  /// All → 15s → 30s → 60s → 180s → All
+  pub fn switch_typing_duration(&mut self) {
+    use TypingDuration::*;
+
+    self.typing_duration = match self.typing_duration {
+      None => Some(FifteenSec),
+      Some(FifteenSec) => Some(ThirtySec),
+      Some(ThirtySec) => Some(Minute),
+      Some(Minute) => Some(ThreeMinutes),
+      Some(ThreeMinutes) => None,
+    };
+  }
+
+  /// Switches the language filter through the given languages
+  pub fn switch_language(&mut self, languages: &[String]) {
+    self.language = StatsFilter::get_next_option(&self.language, languages);
+  }
+
+  /// Switches the mode filter through all the modes
+  pub fn switch_mode(&mut self) {
+    self.mode = StatsFilter::get_next_option(&self.mode, RunMode::all());
+  }
+
+  /// Switches the date range filter
+  pub fn switch_date_range(&mut self) {
+    self.date_range = self.date_range.next();
+  }
+
+  /// Returns the next option from the values
+  ///
+  /// After the last value returns None (i.e. matches all).
+  fn get_next_option<T: PartialEq + Clone>(current: &Option<T>, values: &[T]) -> Option<T> {
+    let next_index = match current {
+      None => 0,
+      Some(value) => values
+        .iter()
+        .position(|v| v == value)
+        .map_or(values.len(), |index| index + 1),
+    };
+
+    values.get(next_index).cloned()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::TimeZone;
+
+  fn get_test_stat() -> Stat {
+    Stat::new(TypingDuration::Minute, String::from("en"), 80, 5)
+  }
+
+  #[test]
+  fn filter_matches_stat() {
+    let stat = get_test_stat();
+    let now = Local::now();
+
+    let mut filter = StatsFilter::default();
+    assert!(filter.matches(&stat, now));
+
+    filter.typing_duration = Some(TypingDuration::Minute);
+    filter.language = Some(String::from("en"));
+    filter.mode = Some(RunMode::Timed);
+    filter.date_range = DateRange::Today;
+    assert!(filter.matches(&stat, now));
+
+    filter.language = Some(String::from("fr"));
+    assert!(!filter.matches(&stat, now));
+
+    filter.language = None;
+    filter.typing_duration = Some(TypingDuration::ThirtySec);
+    assert!(!filter.matches(&stat, now));
+  }
+
+  #[test]
+  fn filter_excludes_stats_out_of_range() {
+    let stat = get_test_stat();
+    let two_days_later = Local::now() + chrono::Duration::days(2);
+
+    let filter = StatsFilter {
+      date_range: DateRange::Today,
+      ..Default::default()
+    };
+
+    assert!(!filter.matches(&stat, two_days_later));
+
+    let legacy_stat = Stat::from_legacy(TypingDuration::Minute, 50, 50, 100.0);
+    let now = Local.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+    assert!(!filter.matches(&legacy_stat, now));
+    assert!(StatsFilter::default().matches(&legacy_stat, now));
+  }
+
+  #[test]
+  fn switch_language_cycles_through_languages() {
+    let languages = vec![String::from("en"), String::from("fr")];
+    let mut filter = StatsFilter::default();
+
+    filter.switch_language(&languages);
+    assert_eq!(filter.language.as_deref(), Some("en"));
+
+    filter.switch_language(&languages);
+    assert_eq!(filter.language.as_deref(), Some("fr"));
+
+    filter.switch_language(&languages);
+    assert_eq!(filter.language, None);
+  }
+}
@@ -1,14 +1,34 @@
 use std::{
+  cell::RefCell,
+  collections::BTreeMap,
   fmt::{Debug, Display},
   path::{Path, PathBuf},
+  rc::Rc,
 };
 
-use anyhow::Result;
+use anyhow::{Result, bail};
+use bincode::Options;
+use chrono::{Local, NaiveDate, Utc};
+use serde::de::DeserializeOwned;
 
-use crate::config::{TukaiLayoutName, TypingDuration};
+use crate::config::{Difficulty, PaceCaret, TukaiLayoutName, TypingDuration, Visibility};
 use crate::file_handler::FileHandler;
-
-use super::stats::Stat;
+use crate::keyboard::KeyboardLayout;
+
+use super::{
+  achievements::{Achievement, AchievementContext, UnlockedAchievement},
+  drills::DrillResult,
+  goals::{DailyGoals, DailyProgress, get_daily_progress},
+  key_stats::{KeyCounter, KeyStats},
+  legacy::{
+    LegacyStorageData, StorageDataV1, StorageDataV2, StorageDataV3, migrate_legacy_data,
+    migrate_v1_data, migrate_v2_data, migrate_v3_data,
+  },
+  lessons::{Lesson, LessonProgress, LessonResult},
+  stat_helper::StatHelper,
+  stats::{RunMode, Stat},
+  stats_filter::StatsFilter,
+};
 
 #[derive(Debug)]
 pub struct StorageHandlerError {
@@ -33,7 +53,40 @@ impl StorageHandlerError {
 /// Storage data type
 ///
 /// Represents types saved on a device's secondary memory.
-pub type StorageData = (Vec<Stat>, TypingDuration, TukaiLayoutName, bool, usize);
+pub type StorageData = (
+  Vec<Stat>,
+  TypingDuration,
+  TukaiLayoutName,
+  bool,
+  usize,
+  DailyGoals,
+  Vec<UnlockedAchievement>,
+  PaceCaret,
+  bool,
+  Difficulty,
+  Visibility,
+  Vec<DrillResult>,
+  Vec<LessonProgress>,
+  KeyboardLayout,
+  bool,
+  Vec<KeyStats>,
+);
+
+/// Header of the storage file
+///
+/// Files written by older versions have no header (see the `legacy` module).
+const STORAGE_FILE_HEADER: &[u8] = b"TUKAI";
+
+/// Version of the storage data format, written after the header
+///
+/// Bump the version whenever `StorageData` or `Stat` changes
+/// and add a migration of the previous format (see the `legacy` module).
+///
+/// 1. Stats with the language, mode and date
+/// 2. Run metrics, keystrokes, goals, achievements, preferences, drills and lessons
+/// 3. Lesson progress of every keyboard layout
+/// 4. Key stats of every keyboard layout
+const STORAGE_FILE_VERSION: u16 = 4;
 
 /// Default data for storage
 ///
@@ -44,6 +97,21 @@ static DEFAULT_STORAGE_DATA: StorageData = (
   TukaiLayoutName::Iced,
   false,
   0,
+  DailyGoals {
+    minutes_per_day: 0,
+    runs_per_day: 0,
+    target_wpm: 0,
+  },
+  Vec::<UnlockedAchievement>::new(),
+  PaceCaret::Off,
+  false,
+  Difficulty::Normal,
+  Visibility::Normal,
+  Vec::<DrillResult>::new(),
+  Vec::<LessonProgress>::new(),
+  KeyboardLayout::Qwerty,
+  false,
+  Vec::<KeyStats>::new(),
 );
 
 /// Represents a storage file with a specified file path
@@ -56,6 +124,10 @@ pub struct StorageHandler {
 
   // Data stored in the `storage` binary file
   data: Option<StorageData>,
+
+  // Cached aggregates of the stats for the last used filter
+  // Invalidated when a new stat is inserted
+  stats_aggregates: RefCell<Option<(StatsFilter, Rc<StatsAggregates>)>>,
 }
 
 /// Total statistics overview
@@ -65,57 +137,195 @@ pub struct StatOverview {
   pub total_stats_count: usize,
   pub total_average_wpm: usize,
   pub total_average_accuracy: f64,
+  pub total_practice_secs: usize,
+  pub best_wpm: usize,
+}
+
+/// Windows (count of runs) of the rolling WPM averages in the chart
+pub const CHART_ROLLING_AVERAGE_WINDOWS: (usize, usize) = (10, 50);
+
+/// Data for the chart widget
+///
+/// Points are `(x, y)` pairs. The x value is the run timestamp
+/// if all runs have a known time, otherwise the run order.
+pub struct ChartData {
+  pub best_wpm: usize,
+  pub is_time_axis: bool,
+  pub x_bounds: [f64; 2],
+  pub wpm: Vec<(f64, f64)>,
+  pub accuracy: Vec<(f64, f64)>,
+  pub rolling_average_short: Vec<(f64, f64)>,
+  pub rolling_average_long: Vec<(f64, f64)>,
+  pub personal_bests: Vec<(f64, f64)>,
+}
+
+/// Precomputed data displayed on the stats screen
+///
+/// Computed once and cached by the `StorageHandler`.
+pub struct StatsAggregates {
+  pub overview: StatOverview,
+  pub chart_data: ChartData,
+  pub stats_reversed: Vec<Stat>,
+  pub stats_best: Vec<Stat>,
+  // Practice of all the stats (not filtered) by the day
+  pub daily_progress: BTreeMap<NaiveDate, DailyProgress>,
 }
 
 impl StorageHandler {
+  /// Returns the app local directory
+  ///
+  /// Uses a local directory path or `/tmp` as the default location.
+  pub fn get_local_dir_path() -> PathBuf {
+    dirs::data_local_dir()
+      .unwrap_or(PathBuf::from("/tmp"))
+      .join("tukai")
+  }
+
   /// Creates a new `storage` file
   ///
   /// Uses a local directory path or `/tmp` as the default location.
   pub fn new<P: AsRef<Path>>(file_path: P) -> Self {
-    let local_dir_path = dirs::data_local_dir().unwrap_or(PathBuf::from("/tmp"));
-
-    let full_path = local_dir_path.join("tukai").join(file_path);
+    let full_path = StorageHandler::get_local_dir_path().join(file_path);
 
     Self {
       file_path: full_path,
       data: None,
+      stats_aggregates: RefCell::new(None),
     }
   }
 
   #[cfg(test)]
   pub fn delete_file(&self) -> Result<()> {
     std::fs::remove_file(&self.file_path)?;
+
+    let backup_path = FileHandler::get_backup_path(&self.file_path);
+    if backup_path.exists() {
+      std::fs::remove_file(backup_path)?;
+    }
+
     Ok(())
   }
 
   /// Inits empty data and write into the `storage file`
   fn init_empty_data(&mut self) -> Result<()> {
-    let data = self
-      .data
-      .get_or_insert_with(|| DEFAULT_STORAGE_DATA.clone());
+    self.data = Some(DEFAULT_STORAGE_DATA.clone());
+    self.flush()
+  }
+
+  /// Encodes the storage data with the header and the format version
+  fn encode_data(data: &StorageData) -> Result<Vec<u8>> {
+    let mut data_bytes = STORAGE_FILE_HEADER.to_vec();
+    data_bytes.extend_from_slice(&STORAGE_FILE_VERSION.to_le_bytes());
+    data_bytes.extend(bincode::serialize(data)?);
+    Ok(data_bytes)
+  }
+
+  /// Decodes the storage data
+  ///
+  /// Data without the header are decoded as the legacy data and migrated.
+  fn decode_data(data_bytes: &[u8]) -> Option<StorageData> {
+    let Some(versioned_bytes) = data_bytes.strip_prefix(STORAGE_FILE_HEADER) else {
+      return bincode::deserialize::<Option<LegacyStorageData>>(data_bytes)
+        .ok()
+        .map(|legacy_data| {
+          legacy_data
+            .map(migrate_legacy_data)
+            .unwrap_or_else(|| DEFAULT_STORAGE_DATA.clone())
+        });
+    };
+
+    let (version_bytes, bytes) = versioned_bytes.split_first_chunk::<2>()?;
+
+    match u16::from_le_bytes(*version_bytes) {
+      1 => StorageHandler::deserialize::<StorageDataV1>(bytes).map(migrate_v1_data),
+      2 => StorageHandler::deserialize::<StorageDataV2>(bytes)
+        .map(migrate_v2_data)
+        .map(migrate_v3_data),
+      3 => StorageHandler::deserialize::<StorageDataV3>(bytes).map(migrate_v3_data),
+      STORAGE_FILE_VERSION => StorageHandler::deserialize::<StorageData>(bytes),
+      _ => None,
+    }
+  }
+
+  /// Deserializes the versioned data
+  ///
+  /// Trailing bytes mean the data of a different format, so they're rejected.
+  fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> Option<T> {
+    bincode::DefaultOptions::new()
+      .with_fixint_encoding()
+      .reject_trailing_bytes()
+      .deserialize(bytes)
+      .ok()
+  }
 
-    let data_bytes = bincode::serialize(&data).unwrap();
-    FileHandler::write_bytes_into_file(&self.file_path, &data_bytes)?;
+  /// Returns the format version from the header of the data (None for the legacy data)
+  fn get_data_version(data_bytes: &[u8]) -> Option<u16> {
+    let versioned_bytes = data_bytes.strip_prefix(STORAGE_FILE_HEADER)?;
+    let (version_bytes, _) = versioned_bytes.split_first_chunk::<2>()?;
+    Some(u16::from_le_bytes(*version_bytes))
+  }
+
+  /// Verifies the data isn't written by a newer version of the app
+  ///
+  /// Newer data can't be decoded, so the file must never be overwritten.
+  fn ensure_supported_version(path: &Path, data_bytes: &[u8]) -> Result<()> {
+    if let Some(version) = StorageHandler::get_data_version(data_bytes)
+      && version > STORAGE_FILE_VERSION
+    {
+      bail!(
+        "Storage file {} is written by a newer version of tukai (format {version}), please update tukai",
+        path.display()
+      );
+    }
 
     Ok(())
   }
 
+  /// Reads and decodes the storage data from the file
+  ///
+  /// Returns None if the file is missing or corrupted.
+  /// Fails if the file can't be read or is written by a newer version.
+  fn read_data(path: &Path) -> Result<Option<StorageData>> {
+    if !path.exists() {
+      return Ok(None);
+    }
+
+    let data_bytes = FileHandler::read_bytes_from_file(path)?;
+    StorageHandler::ensure_supported_version(path, &data_bytes)?;
+
+    Ok(StorageHandler::decode_data(&data_bytes))
+  }
+
   /// Inits the storage
   ///
   /// Try to read all bytes from the storage file
   /// Then set into the data
+  ///
+  /// If the storage file can't be decoded, recovers the data
+  /// from the backup file. Falls back to empty data if both fail.
+  /// Corrupted files are moved aside (see `FileHandler::move_aside`).
   pub fn init(mut self) -> Result<Self> {
-    if !self.file_path.exists() {
-      self.init_empty_data()?;
+    if let Some(data) = StorageHandler::read_data(&self.file_path)? {
+      self.data = Some(data);
       return Ok(self);
     }
 
-    let data_bytes = FileHandler::read_bytes_from_file(&self.file_path)?;
-
-    match bincode::deserialize(&data_bytes) {
-      Ok(data) => self.data = data,
-      Err(_) => self.init_empty_data()?,
-    };
+    let backup_path = FileHandler::get_backup_path(&self.file_path);
+    let backup_data = StorageHandler::read_data(&backup_path)?;
+
+    // Corrupted file would be rotated over the backup by the next flush
+    FileHandler::move_aside(&self.file_path)?;
+
+    match backup_data {
+      Some(data) => {
+        self.data = Some(data);
+        self.flush()?;
+      }
+      None => {
+        FileHandler::move_aside(&backup_path)?;
+        self.init_empty_data()?;
+      }
+    }
 
     Ok(self)
   }
@@ -136,15 +346,67 @@ impl StorageHandler {
     self.data.as_mut()
   }
 
+  /// Returns stats matching the filter
+  ///
+  /// Oldest first
+  pub fn get_filtered_stats(&self, filter: &StatsFilter) -> Vec<&Stat> {
+    let now = Local::now();
+
+    self
+      .get_data()
+      .0
+      .iter()
+      .filter(|stat| filter.matches(stat, now))
+      .collect::<Vec<&Stat>>()
+  }
+
+  /// Returns valid stats matching the filter
+  ///
+  /// Invalid runs (e.g. AFK) are excluded.
+  /// Zen runs have no text, they're compared only when filtered by their mode. Oldest first
+  pub fn get_valid_stats(&self, filter: &StatsFilter) -> Vec<&Stat> {
+    self
+      .get_filtered_stats(filter)
+      .into_iter()
+      .filter(|stat| !stat.get_flags().is_invalid())
+      .filter(|stat| stat.get_mode() != RunMode::Zen || filter.mode == Some(RunMode::Zen))
+      .collect::<Vec<&Stat>>()
+  }
+
+  /// Returns languages of all the stored stats
+  ///
+  /// Sorted, without duplicates and unknown languages.
+  pub fn get_stats_languages(&self) -> Vec<String> {
+    let mut languages = self
+      .get_data()
+      .0
+      .iter()
+      .map(|stat| stat.get_language())
+      .filter(|language| !language.is_empty())
+      .cloned()
+      .collect::<Vec<String>>();
+
+    languages.sort();
+    languages.dedup();
+    languages
+  }
+
   /// Returns the complete statistics overview
   ///
   /// (average WPM, average accuracy)
-  pub fn get_data_for_overview(&self) -> StatOverview {
-    let stats = &self.get_data().0;
-
-    let (sum_wpm, sum_accuracy) = stats.iter().fold((0, 0.0), |(wpm, acc), stat| {
-      (wpm + stat.get_average_wpm(), acc + stat.get_accuracy())
-    });
+  ///
+  /// Invalid runs are excluded.
+  pub fn get_data_for_overview(&self, filter: &StatsFilter) -> StatOverview {
+    let stats = self.get_valid_stats(filter);
+
+    let (sum_wpm, sum_accuracy, sum_practice_secs) =
+      stats.iter().fold((0, 0.0, 0), |(wpm, acc, secs), stat| {
+        (
+          wpm + stat.get_average_wpm(),
+          acc + stat.get_accuracy(),
+          secs + stat.get_practice_secs(),
+        )
+      });
 
     let accuracy = (sum_accuracy / stats.len() as f64).round();
 
@@ -152,49 +414,178 @@ impl StorageHandler {
       total_stats_count: stats.len(),
       total_average_wpm: sum_wpm.checked_div(stats.len()).unwrap_or(0),
       total_average_accuracy: if accuracy.is_nan() { 0.0 } else { accuracy },
+      total_practice_secs: sum_practice_secs,
+      best_wpm: stats
+        .iter()
+        .map(|stat| stat.get_average_wpm())
+        .max()
+        .unwrap_or(0),
     }
   }
 
   /// Returns data for the chart widget
   ///
-  /// Creates a dataset for the chart and calculates the best WPM.
-  pub fn get_data_for_chart(&self) -> (usize, Vec<(f64, f64)>) {
-    let stats = &self.get_data().0;
+  /// Creates datasets for the chart (WPM, accuracy, rolling averages
+  /// and personal bests) and calculates the best WPM.
+  /// Only the valid stats are charted (see `get_valid_stats`), as in the overview.
+  pub fn get_data_for_chart(&self, filter: &StatsFilter) -> ChartData {
+    let stats = self.get_valid_stats(filter);
 
-    let mut best_wpm = 0_usize;
-    let dataset = stats
+    let timestamps = stats
       .iter()
-      .enumerate()
-      .rev()
-      .map(|(index, stat)| {
-        let stat_wpm = stat.get_average_wpm();
+      .map(|stat| stat.get_created_at().map(|date_time| date_time.timestamp()))
+      .collect::<Option<Vec<i64>>>()
+      .filter(|timestamps| !timestamps.is_empty());
+
+    let is_time_axis = timestamps.is_some();
+
+    let x_values: Vec<f64> = timestamps.map_or_else(
+      || (0..stats.len()).map(|index| index as f64).collect(),
+      |timestamps| {
+        timestamps
+          .iter()
+          .map(|timestamp| *timestamp as f64)
+          .collect()
+      },
+    );
 
-        best_wpm = best_wpm.max(stat_wpm);
+    let to_points = |values: Vec<f64>| {
+      x_values
+        .iter()
+        .copied()
+        .zip(values)
+        .collect::<Vec<(f64, f64)>>()
+    };
 
-        (index as f64, stat_wpm as f64)
-      })
-      .collect::<Vec<(f64, f64)>>();
+    let wpm_values = stats
+      .iter()
+      .map(|stat| stat.get_average_wpm() as f64)
+      .collect::<Vec<f64>>();
+
+    let mut best_wpm = 0_usize;
+    let mut personal_bests = Vec::new();
+
+    for (x, stat) in x_values.iter().zip(stats.iter()) {
+      let stat_wpm = stat.get_average_wpm();
+
+      if stat_wpm > best_wpm {
+        best_wpm = stat_wpm;
+        personal_bests.push((*x, stat_wpm as f64));
+      }
+    }
 
-    (best_wpm.max(100), dataset)
+    let (short_window, long_window) = CHART_ROLLING_AVERAGE_WINDOWS;
+
+    let x_bounds = match (x_values.first(), x_values.last()) {
+      (Some(first), Some(last)) if first < last => [*first, *last],
+      (Some(first), _) => [*first - 1.0, *first + 1.0],
+      _ => [0.0, 1.0],
+    };
+
+    ChartData {
+      best_wpm,
+      is_time_axis,
+      x_bounds,
+      wpm: to_points(wpm_values.clone()),
+      accuracy: to_points(stats.iter().map(|stat| stat.get_accuracy()).collect()),
+      rolling_average_short: to_points(StatHelper::get_rolling_average(&wpm_values, short_window)),
+      rolling_average_long: to_points(StatHelper::get_rolling_average(&wpm_values, long_window)),
+      personal_bests,
+    }
+  }
+
+  /// Returns the cached stats aggregates for the filter
+  ///
+  /// Aggregates are computed on the first call after a stat was inserted
+  /// or when the filter changed.
+  pub fn get_stats_aggregates(&self, filter: &StatsFilter) -> Rc<StatsAggregates> {
+    let mut stats_aggregates = self.stats_aggregates.borrow_mut();
+
+    if let Some((cached_filter, aggregates)) = stats_aggregates.as_ref()
+      && cached_filter == filter
+    {
+      return Rc::clone(aggregates);
+    }
+
+    let aggregates = Rc::new(StatsAggregates {
+      overview: self.get_data_for_overview(filter),
+      chart_data: self.get_data_for_chart(filter),
+      stats_reversed: self.get_data_stats_reversed(filter),
+      stats_best: self.get_data_stats_best(filter),
+      daily_progress: get_daily_progress(&self.get_data().0),
+    });
+
+    stats_aggregates.replace((filter.clone(), Rc::clone(&aggregates)));
+
+    aggregates
   }
 
   /// Returns stats in reversed order
   ///
   /// Newest first
-  pub fn get_data_stats_reversed(&self) -> Vec<Stat> {
-    let stats = &self.get_data().0;
-    stats.iter().rev().cloned().collect::<Vec<Stat>>()
+  pub fn get_data_stats_reversed(&self, filter: &StatsFilter) -> Vec<Stat> {
+    let stats = self.get_filtered_stats(filter);
+    stats.into_iter().rev().cloned().collect::<Vec<Stat>>()
   }
 
   /// Returns stats sorted by average WPM
   ///
-  /// Used to determine the `best score`.
-  pub fn get_data_stats_best(&self) -> Vec<Stat> {
-    let mut data = self.get_data().0.clone();
+  /// Used to determine the `best score`, invalid runs are excluded.
+  pub fn get_data_stats_best(&self, filter: &StatsFilter) -> Vec<Stat> {
+    let mut data = self
+      .get_valid_stats(filter)
+      .into_iter()
+      .cloned()
+      .collect::<Vec<Stat>>();
+
     data.sort_by_key(|b| std::cmp::Reverse(b.get_average_wpm()));
     data
   }
 
+  /// Returns the best run which can be raced as a ghost
+  ///
+  /// Only valid runs with the keystrokes of the same duration and mode are considered.
+  pub fn get_ghost_stat(&self, typing_duration: &TypingDuration, mode: RunMode) -> Option<&Stat> {
+    self
+      .get_data()
+      .0
+      .iter()
+      .filter(|stat| {
+        !stat.get_flags().is_invalid()
+          && stat.get_keystroke_log().is_some()
+          && stat.get_typing_duration() == typing_duration
+          && stat.get_mode() == mode
+      })
+      .max_by_key(|stat| stat.get_average_wpm())
+  }
+
+  /// Resolves the target WPM of the pace caret
+  ///
+  /// Average and best WPM are taken from the timed runs with the duration.
+  /// None if the pace caret is off or there are no runs.
+  pub fn get_pace_wpm(
+    &self,
+    pace_caret: PaceCaret,
+    typing_duration: &TypingDuration,
+  ) -> Option<usize> {
+    let get_overview = || {
+      self.get_data_for_overview(&StatsFilter {
+        typing_duration: Some(typing_duration.clone()),
+        mode: Some(RunMode::Timed),
+        ..Default::default()
+      })
+    };
+
+    let pace_wpm = match pace_caret {
+      PaceCaret::Off => return None,
+      PaceCaret::Fixed(wpm) => wpm,
+      PaceCaret::Average => get_overview().total_average_wpm,
+      PaceCaret::Best => get_overview().best_wpm,
+    };
+
+    Some(pace_wpm).filter(|wpm| *wpm > 0)
+  }
+
   /// Returns a TypingDuration
   pub fn get_typing_duration(&self) -> TypingDuration {
     self.get_data().1.clone()
@@ -215,11 +606,57 @@ impl StorageHandler {
     self.get_data().3
   }
 
+  /// Returns the selected pace caret
+  pub fn get_pace_caret(&self) -> PaceCaret {
+    self.get_data().7
+  }
+
+  /// Returns if the keystrokes of the runs are recorded
+  pub fn get_record_keystrokes(&self) -> bool {
+    self.get_data().8
+  }
+
+  /// Returns the selected difficulty
+  pub fn get_difficulty(&self) -> Difficulty {
+    self.get_data().9
+  }
+
+  /// Returns the selected visibility of the text
+  pub fn get_visibility(&self) -> Visibility {
+    self.get_data().10
+  }
+
+  /// Returns the selected physical keyboard layout
+  pub fn get_keyboard_layout(&self) -> KeyboardLayout {
+    self.get_data().13
+  }
+
+  /// Returns if the typed characters are remapped into the keyboard layout
+  pub fn get_remap_keys(&self) -> bool {
+    self.get_data().14
+  }
+
+  /// Returns the daily goals
+  pub fn get_daily_goals(&self) -> &DailyGoals {
+    &self.get_data().5
+  }
+
   /// Serialize `StorageData` into a bytes.
   ///
   /// Flushes all serialized data to the storage file.
+  ///
+  /// Refuses to overwrite a file written by a newer version.
   pub fn flush(&self) -> Result<()> {
-    let data_bytes = bincode::serialize(&self.data)?;
+    if self.file_path.exists() {
+      let header_bytes = FileHandler::read_first_bytes_from_file(
+        &self.file_path,
+        STORAGE_FILE_HEADER.len() + size_of::<u16>(),
+      )?;
+
+      StorageHandler::ensure_supported_version(&self.file_path, &header_bytes)?;
+    }
+
+    let data_bytes = StorageHandler::encode_data(self.get_data())?;
     FileHandler::write_bytes_into_file(&self.file_path, &data_bytes)
   }
 
@@ -231,9 +668,110 @@ impl StorageHandler {
       storage_data.0.push(stat.clone());
     }
 
+    self.stats_aggregates.replace(None);
+
+    self.flush().is_ok()
+  }
+
+  /// Inserts the result of a finished drill and flushes it
+  ///
+  /// Drill results are stored separately from the stats.
+  pub fn insert_drill_result(&mut self, drill_result: DrillResult) -> bool {
+    if let Some(storage_data) = self.get_data_mut() {
+      storage_data.11.push(drill_result);
+    }
+
     self.flush().is_ok()
   }
 
+  /// Returns the results of the finished drills (oldest first)
+  pub fn get_drill_results(&self) -> &Vec<DrillResult> {
+    &self.get_data().11
+  }
+
+  /// Returns the progress of the lessons typed on the keyboard layout
+  pub fn get_lessons_progress(&self, keyboard_layout: KeyboardLayout) -> Vec<LessonProgress> {
+    self
+      .get_data()
+      .12
+      .iter()
+      .filter(|progress| progress.keyboard_layout == keyboard_layout)
+      .cloned()
+      .collect()
+  }
+
+  /// Adds the result of the lesson exercise to its progress and flushes it
+  ///
+  /// Returns whether the result passes the lesson.
+  pub fn add_lesson_result(
+    &mut self,
+    keyboard_layout: KeyboardLayout,
+    lesson: Lesson,
+    result: &LessonResult,
+  ) -> bool {
+    let Some(storage_data) = self.get_data_mut() else {
+      return false;
+    };
+
+    let lessons_progress = &mut storage_data.12;
+
+    let progress_index = match lessons_progress
+      .iter()
+      .position(|progress| progress.keyboard_layout == keyboard_layout && progress.lesson == lesson)
+    {
+      Some(progress_index) => progress_index,
+      None => {
+        lessons_progress.push(LessonProgress::new(keyboard_layout, lesson));
+        lessons_progress.len() - 1
+      }
+    };
+
+    let is_passed = lessons_progress[progress_index].add_result(result);
+
+    let _ = self.flush();
+
+    is_passed
+  }
+
+  /// Returns the key stats of the runs typed on the keyboard layout
+  pub fn get_key_stats(&self, keyboard_layout: KeyboardLayout) -> KeyStats {
+    self
+      .get_data()
+      .15
+      .iter()
+      .find(|key_stats| key_stats.keyboard_layout == keyboard_layout)
+      .cloned()
+      .unwrap_or_else(|| KeyStats::new(keyboard_layout))
+  }
+
+  /// Adds the key counters of a run typed on the keyboard layout
+  ///
+  /// Not flushed, the stat or the lesson result of the run is inserted right after.
+  pub fn add_key_counters(
+    &mut self,
+    keyboard_layout: KeyboardLayout,
+    counters: &BTreeMap<char, KeyCounter>,
+  ) {
+    let Some(storage_data) = self.get_data_mut() else {
+      return;
+    };
+
+    let key_stats = &mut storage_data.15;
+
+    let key_stats_index = match key_stats
+      .iter()
+      .position(|key_stats| key_stats.keyboard_layout == keyboard_layout)
+    {
+      Some(key_stats_index) => key_stats_index,
+      None => {
+        key_stats.push(KeyStats::new(keyboard_layout));
+        key_stats.len() - 1
+      }
+    };
+
+    key_stats[key_stats_index].add_counters(counters);
+  }
+
   /// Sets a new typing duration
   pub fn set_typing_duration(&mut self, typin_duration: TypingDuration) {
     if let Some(storage_data) = self.get_data_mut() {
@@ -255,30 +793,145 @@ impl StorageHandler {
     }
   }
 
+  /// Returns the unlocked achievements
+  pub fn get_unlocked_achievements(&self) -> &Vec<UnlockedAchievement> {
+    &self.get_data().6
+  }
+
+  /// Unlocks the newly reached achievements and flushes them
+  ///
+  /// Only the valid runs without a paste or a focus loss earn the achievements.
+  /// Returns the unlocked achievements (used after the run is completed).
+  pub fn unlock_achievements(&mut self, lang_codes: &[String]) -> Vec<Achievement> {
+    let today = Local::now().date_naive();
+
+    let streak = self
+      .get_daily_goals()
+      .get_streak(&get_daily_progress(&self.get_data().0), today);
+
+    let stats = self
+      .get_valid_stats(&StatsFilter::default())
+      .into_iter()
+      .filter(|stat| !stat.get_flags().has_paste && !stat.get_flags().has_focus_loss)
+      .collect::<Vec<&Stat>>();
+
+    let context = AchievementContext {
+      stats: &stats,
+      streak,
+      lang_codes,
+    };
+
+    let unlocked_achievements = self.get_unlocked_achievements();
+
+    let new_achievements = Achievement::all()
+      .iter()
+      .filter(|achievement| {
+        !unlocked_achievements
+          .iter()
+          .any(|unlocked| unlocked.achievement == **achievement)
+      })
+      .filter(|achievement| achievement.is_reached(&context))
+      .copied()
+      .collect::<Vec<Achievement>>();
+
+    if new_achievements.is_empty() {
+      return new_achievements;
+    }
+
+    let unlocked_at = Utc::now().timestamp();
+
+    if let Some(storage_data) = self.get_data_mut() {
+      storage_data.6.extend(
+        new_achievements
+          .iter()
+          .map(|achievement| UnlockedAchievement {
+            achievement: *achievement,
+            unlocked_at,
+          }),
+      );
+    }
+
+    self.stats_aggregates.replace(None);
+    let _ = self.flush();
+
+    new_achievements
+  }
+
+  /// Sets a new pace caret
+  pub fn set_pace_caret(&mut self, pace_caret: PaceCaret) {
+    if let Some(storage_data) = self.get_data_mut() {
+      storage_data.7 = pace_caret;
+    }
+  }
+
+  /// Sets new daily goals
+  pub fn set_daily_goals(&mut self, daily_goals: DailyGoals) {
+    if let Some(storage_data) = self.get_data_mut() {
+      storage_data.5 = daily_goals;
+    }
+  }
+
   /// Toggles a background transparency
   pub fn set_transparent_bg(&mut self, state: bool) {
     if let Some(storage_data) = self.get_data_mut() {
       storage_data.3 = state;
     }
   }
+
+  /// Toggles the keystrokes recording
+  pub fn set_record_keystrokes(&mut self, state: bool) {
+    if let Some(storage_data) = self.get_data_mut() {
+      storage_data.8 = state;
+    }
+  }
+
+  /// Sets a new difficulty
+  pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+    if let Some(storage_data) = self.get_data_mut() {
+      storage_data.9 = difficulty;
+    }
+  }
+
+  /// Sets a new visibility of the text
+  pub fn set_visibility(&mut self, visibility: Visibility) {
+    if let Some(storage_data) = self.get_data_mut() {
+      storage_data.10 = visibility;
+    }
+  }
+
+  /// Sets a new physical keyboard layout
+  pub fn set_keyboard_layout(&mut self, keyboard_layout: KeyboardLayout) {
+    if let Some(storage_data) = self.get_data_mut() {
+      storage_data.13 = keyboard_layout;
+    }
+  }
+
+  /// Sets if the typed characters are remapped into the keyboard layout
+  pub fn set_remap_keys(&mut self, remap_keys: bool) {
+    if let Some(storage_data) = self.get_data_mut() {
+      storage_data.14 = remap_keys;
+    }
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::config::TypingDuration;
+  use crate::{config::TypingDuration, storage::stats::StatFlags};
   use uuid::Uuid;
 
+  fn get_test_file_path() -> String {
+    format!("tests/{}.tukai", Uuid::new_v4())
+  }
+
   fn get_storage_handler() -> StorageHandler {
-    let storage_helper = StorageHandler::new(format!("tests/{}.tukai", Uuid::new_v4()))
+    StorageHandler::new(get_test_file_path())
       .init()
-      .expect("Failed to initialize storage file");
-
-    storage_helper
+      .expect("Failed to initialize storage file")
   }
 
   fn get_test_stat() -> Stat {
-    Stat::new(TypingDuration::Minute, 80, 5)
+    Stat::new(TypingDuration::Minute, String::from("en"), 80, 5)
   }
 
   //#[test]
@@ -313,7 +966,7 @@ mod tests {
       "Insert into the storage error occured"
     );
 
-    let _stats = storage_handler.get_data_stats_reversed();
+    let _stats = storage_handler.get_data_stats_reversed(&StatsFilter::default());
 
     // assert!(
     //   stats.is_some(),
@@ -331,6 +984,36 @@ mod tests {
     //   .expect("Error occured while deleting file");
   }
 
+  #[test]
+  // AFK runs are excluded from the overview and the best scores
+  fn invalid_stats_excluded() {
+    let mut storage_handler = get_storage_handler();
+
+    let afk_stat =
+      Stat::new(TypingDuration::Minute, String::from("en"), 5, 0).with_flags(StatFlags {
+        is_afk: true,
+        ..Default::default()
+      });
+
+    storage_handler.insert_into_stats(&get_test_stat());
+    storage_handler.insert_into_stats(&afk_stat);
+
+    let filter = StatsFilter::default();
+
+    assert_eq!(
+      storage_handler
+        .get_data_for_overview(&filter)
+        .total_stats_count,
+      1
+    );
+    assert_eq!(storage_handler.get_data_stats_best(&filter).len(), 1);
+    assert_eq!(storage_handler.get_data_stats_reversed(&filter).len(), 2);
+
+    storage_handler
+      .delete_file()
+      .expect("Error occured while deleting file");
+  }
+
   #[test]
   fn flush_data() {
     let mut storage_handler = get_storage_handler();
@@ -348,10 +1031,570 @@ mod tests {
     let mut storage_handler = get_storage_handler();
     storage_handler.insert_into_stats(&get_test_stat());
 
-    println!("{:?}", storage_handler.get_data());
+    println!("{:?}", storage_handler.get_data().0);
+
+    let data = storage_handler.get_data();
+    println!("{:?}", data.0);
+
+    storage_handler
+      .delete_file()
+      .expect("Error occured while deleting file");
+  }
+
+  #[test]
+  // Writing a smaller payload must not leave trailing bytes of the previous one
+  fn flush_truncates_previous_data() {
+    let mut storage_handler = get_storage_handler();
+
+    for _ in 0..10 {
+      storage_handler.insert_into_stats(&get_test_stat());
+    }
+
+    storage_handler.get_data_mut().unwrap().0.clear();
+    storage_handler.flush().expect("Failed to flush data");
+
+    let data_bytes = FileHandler::read_bytes_from_file(&storage_handler.file_path).unwrap();
+    assert_eq!(
+      data_bytes,
+      StorageHandler::encode_data(storage_handler.get_data()).unwrap()
+    );
+
+    storage_handler
+      .delete_file()
+      .expect("Error occured while deleting file");
+  }
+
+  #[test]
+  // Corrupted storage file is recovered from the backup
+  fn init_recovers_from_backup() {
+    let file_path = get_test_file_path();
+
+    let mut storage_handler = StorageHandler::new(&file_path).init().unwrap();
+    storage_handler.insert_into_stats(&get_test_stat());
+    storage_handler.flush().expect("Failed to flush data");
+
+    std::fs::write(&storage_handler.file_path, b"corrupted").unwrap();
+
+    let recovered_storage_handler = StorageHandler::new(&file_path).init().unwrap();
+    assert_eq!(recovered_storage_handler.get_data().0.len(), 1);
+
+    // The recovered data is written back into the storage file
+    let storage_handler = StorageHandler::new(&file_path).init().unwrap();
+    assert_eq!(storage_handler.get_data().0.len(), 1);
+
+    storage_handler
+      .delete_file()
+      .expect("Error occured while deleting file");
+  }
+
+  #[test]
+  // Unreadable storage file (without a backup) is moved aside, never overwritten
+  fn init_keeps_corrupted_file_aside() {
+    let file_path = get_test_file_path();
+    let full_path = StorageHandler::get_local_dir_path().join(&file_path);
+
+    FileHandler::write_bytes_into_file(&full_path, b"corrupted").unwrap();
+
+    let storage_handler = StorageHandler::new(&file_path).init().unwrap();
+    assert!(storage_handler.get_data().0.is_empty());
+
+    storage_handler.flush().expect("Failed to flush data");
+    storage_handler.flush().expect("Failed to flush data");
+
+    let corrupt_path = PathBuf::from(format!("{}.corrupt", full_path.display()));
+    assert_eq!(
+      FileHandler::read_bytes_from_file(&corrupt_path).unwrap(),
+      b"corrupted"
+    );
+
+    std::fs::remove_file(corrupt_path).unwrap();
+    storage_handler
+      .delete_file()
+      .expect("Error occured while deleting file");
+  }
+
+  #[test]
+  // File written by a newer version is refused and kept untouched
+  fn init_refuses_newer_version() {
+    let file_path = get_test_file_path();
+    let full_path = StorageHandler::get_local_dir_path().join(&file_path);
+
+    let mut data_bytes = STORAGE_FILE_HEADER.to_vec();
+    data_bytes.extend_from_slice(&(STORAGE_FILE_VERSION + 1).to_le_bytes());
+    data_bytes.extend_from_slice(b"newer data");
+
+    FileHandler::write_bytes_into_file(&full_path, &data_bytes).unwrap();
+
+    assert!(StorageHandler::new(&file_path).init().is_err());
+    assert_eq!(
+      FileHandler::read_bytes_from_file(&full_path).unwrap(),
+      data_bytes
+    );
+
+    std::fs::remove_file(full_path).unwrap();
+  }
+
+  #[test]
+  // Missing storage file falls back to the backup (crash between renames)
+  fn init_recovers_missing_file_from_backup() {
+    let file_path = get_test_file_path();
+
+    let mut storage_handler = StorageHandler::new(&file_path).init().unwrap();
+    storage_handler.insert_into_stats(&get_test_stat());
+    storage_handler.flush().expect("Failed to flush data");
+
+    std::fs::remove_file(&storage_handler.file_path).unwrap();
+
+    let recovered_storage_handler = StorageHandler::new(&file_path).init().unwrap();
+    assert_eq!(recovered_storage_handler.get_data().0.len(), 1);
+
+    recovered_storage_handler
+      .delete_file()
+      .expect("Error occured while deleting file");
+  }
+
+  #[test]
+  // Cached aggregates are reused until a new stat is inserted
+  fn stats_aggregates_invalidated_on_insert() {
+    let mut storage_handler = get_storage_handler();
+
+    let aggregates = storage_handler.get_stats_aggregates(&StatsFilter::default());
+    assert_eq!(aggregates.overview.total_stats_count, 0);
+    assert!(Rc::ptr_eq(
+      &aggregates,
+      &storage_handler.get_stats_aggregates(&StatsFilter::default())
+    ));
+
+    storage_handler.insert_into_stats(&get_test_stat());
+
+    let aggregates = storage_handler.get_stats_aggregates(&StatsFilter::default());
+    assert_eq!(aggregates.overview.total_stats_count, 1);
+    assert_eq!(aggregates.stats_reversed.len(), 1);
+
+    storage_handler
+      .delete_file()
+      .expect("Error occured while deleting file");
+  }
+
+  #[test]
+  // Data stored by the versions without the header are migrated
+  fn decode_legacy_data() {
+    #[derive(serde::Serialize)]
+    struct LegacyStat {
+      typing_duration: TypingDuration,
+      average_wpm: usize,
+      raw_wpm: usize,
+      accuracy: f64,
+    }
+
+    let legacy_data = Some((
+      vec![LegacyStat {
+        typing_duration: TypingDuration::ThirtySec,
+        average_wpm: 72,
+        raw_wpm: 80,
+        accuracy: 96.5,
+      }],
+      TypingDuration::FifteenSec,
+      TukaiLayoutName::Rust,
+      true,
+      2_usize,
+    ));
+
+    let data = StorageHandler::decode_data(&bincode::serialize(&legacy_data).unwrap())
+      .expect("Failed to decode legacy data");
+
+    assert_eq!(data.0.len(), 1);
+    assert_eq!(data.0[0].get_average_wpm(), 72);
+    assert_eq!(data.0[0].get_raw_wpm(), 80);
+    assert!(data.0[0].get_created_at().is_none());
+    assert_eq!(data.1, TypingDuration::FifteenSec);
+    assert_eq!(data.2, TukaiLayoutName::Rust);
+    assert!(data.3);
+    assert_eq!(data.4, 2);
+  }
+
+  #[test]
+  // File of the storage format version 1 is migrated with its stats
+  fn init_migrates_v1_file() {
+    #[derive(serde::Serialize)]
+    struct StatV1 {
+      typing_duration: TypingDuration,
+      average_wpm: usize,
+      raw_wpm: usize,
+      accuracy: f64,
+      language: String,
+      mode: RunMode,
+      created_at: Option<i64>,
+    }
+
+    let data_v1 = (
+      vec![StatV1 {
+        typing_duration: TypingDuration::ThirtySec,
+        average_wpm: 72,
+        raw_wpm: 80,
+        accuracy: 96.5,
+        language: String::from("en"),
+        mode: RunMode::Timed,
+        created_at: Some(1_700_000_000),
+      }],
+      TypingDuration::FifteenSec,
+      TukaiLayoutName::Rust,
+      true,
+      2_usize,
+    );
+
+    let mut data_bytes = STORAGE_FILE_HEADER.to_vec();
+    data_bytes.extend_from_slice(&1_u16.to_le_bytes());
+    data_bytes.extend(bincode::serialize(&data_v1).unwrap());
 
+    let file_path = get_test_file_path();
+    FileHandler::write_bytes_into_file(
+      StorageHandler::get_local_dir_path().join(&file_path),
+      &data_bytes,
+    )
+    .unwrap();
+
+    let storage_handler = StorageHandler::new(&file_path).init().unwrap();
     let data = storage_handler.get_data();
-    println!("{:?}", data);
+
+    assert_eq!(data.0.len(), 1);
+    assert_eq!(data.0[0].get_average_wpm(), 72);
+    assert_eq!(data.0[0].get_language(), "en");
+    assert!(data.0[0].get_created_at().is_some());
+    assert_eq!(data.1, TypingDuration::FifteenSec);
+    assert_eq!(data.2, TukaiLayoutName::Rust);
+    assert_eq!(data.4, 2);
+
+    // The migrated data is written in the current format
+    storage_handler.flush().expect("Failed to flush data");
+    let data_bytes = FileHandler::read_bytes_from_file(&storage_handler.file_path).unwrap();
+    assert_eq!(
+      StorageHandler::get_data_version(&data_bytes),
+      Some(STORAGE_FILE_VERSION)
+    );
+
+    storage_handler
+      .delete_file()
+      .expect("Error occured while deleting file");
+  }
+
+  #[test]
+  // File of the storage format version 2 keeps the lesson progress of the selected keyboard layout
+  fn init_migrates_v2_file() {
+    #[derive(serde::Serialize)]
+    struct LessonProgressV2 {
+      lesson: Lesson,
+      attempts: usize,
+      best_wpm: usize,
+      best_accuracy: f64,
+      is_passed: bool,
+    }
+
+    let defaults = DEFAULT_STORAGE_DATA.clone();
+
+    let data_v2 = (
+      vec![get_test_stat()],
+      defaults.1,
+      defaults.2,
+      defaults.3,
+      defaults.4,
+      defaults.5,
+      defaults.6,
+      defaults.7,
+      defaults.8,
+      defaults.9,
+      defaults.10,
+      defaults.11,
+      vec![LessonProgressV2 {
+        lesson: Lesson::HomeRow,
+        attempts: 2,
+        best_wpm: 24,
+        best_accuracy: 95.0,
+        is_passed: true,
+      }],
+      KeyboardLayout::Colemak,
+      true,
+    );
+
+    let mut data_bytes = STORAGE_FILE_HEADER.to_vec();
+    data_bytes.extend_from_slice(&2_u16.to_le_bytes());
+    data_bytes.extend(bincode::serialize(&data_v2).unwrap());
+
+    let file_path = get_test_file_path();
+    FileHandler::write_bytes_into_file(
+      StorageHandler::get_local_dir_path().join(&file_path),
+      &data_bytes,
+    )
+    .unwrap();
+
+    let mut storage_handler = StorageHandler::new(&file_path).init().unwrap();
+
+    assert_eq!(storage_handler.get_data().0.len(), 1);
+    assert_eq!(
+      storage_handler.get_keyboard_layout(),
+      KeyboardLayout::Colemak
+    );
+    assert!(storage_handler.get_remap_keys());
+
+    let colemak_progress = storage_handler.get_lessons_progress(KeyboardLayout::Colemak);
+    assert_eq!(colemak_progress.len(), 1);
+    assert_eq!(colemak_progress[0].best_wpm, 24);
+    assert!(Lesson::TopRow.is_unlocked(&colemak_progress));
+
+    // Passing the lesson on a layout doesn't unlock the lessons of the other layouts
+    let passed = LessonResult::new(100, 0, 0, 60_000);
+    assert!(storage_handler.add_lesson_result(KeyboardLayout::Qwerty, Lesson::HomeRow, &passed));
+    assert!(
+      Lesson::TopRow.is_unlocked(&storage_handler.get_lessons_progress(KeyboardLayout::Qwerty))
+    );
+    assert!(
+      !Lesson::TopRow.is_unlocked(&storage_handler.get_lessons_progress(KeyboardLayout::Dvorak))
+    );
+
+    let data_bytes = FileHandler::read_bytes_from_file(&storage_handler.file_path).unwrap();
+    assert_eq!(
+      StorageHandler::get_data_version(&data_bytes),
+      Some(STORAGE_FILE_VERSION)
+    );
+
+    storage_handler
+      .delete_file()
+      .expect("Error occured while deleting file");
+  }
+
+  #[test]
+  // File of the storage format version 3 has no key stats
+  fn init_migrates_v3_file() {
+    let defaults = DEFAULT_STORAGE_DATA.clone();
+
+    let data_v3: StorageDataV3 = (
+      vec![get_test_stat()],
+      defaults.1,
+      defaults.2,
+      defaults.3,
+      defaults.4,
+      defaults.5,
+      defaults.6,
+      defaults.7,
+      defaults.8,
+      defaults.9,
+      defaults.10,
+      defaults.11,
+      vec![LessonProgress::new(KeyboardLayout::Dvorak, Lesson::HomeRow)],
+      KeyboardLayout::Dvorak,
+      false,
+    );
+
+    let mut data_bytes = STORAGE_FILE_HEADER.to_vec();
+    data_bytes.extend_from_slice(&3_u16.to_le_bytes());
+    data_bytes.extend(bincode::serialize(&data_v3).unwrap());
+
+    let file_path = get_test_file_path();
+    FileHandler::write_bytes_into_file(
+      StorageHandler::get_local_dir_path().join(&file_path),
+      &data_bytes,
+    )
+    .unwrap();
+
+    let mut storage_handler = StorageHandler::new(&file_path).init().unwrap();
+
+    assert_eq!(storage_handler.get_data().0.len(), 1);
+    assert_eq!(
+      storage_handler
+        .get_lessons_progress(KeyboardLayout::Dvorak)
+        .len(),
+      1
+    );
+    assert!(
+      storage_handler
+        .get_key_stats(KeyboardLayout::Dvorak)
+        .counters
+        .is_empty()
+    );
+
+    // Key counters are kept by the keyboard layout
+    let counters = BTreeMap::from([(
+      'o',
+      KeyCounter {
+        presses: 3,
+        mistakes: 1,
+      },
+    )]);
+    storage_handler.add_key_counters(KeyboardLayout::Dvorak, &counters);
+    storage_handler.add_key_counters(KeyboardLayout::Dvorak, &counters);
+    storage_handler.flush().unwrap();
+
+    let storage_handler = StorageHandler::new(&file_path).init().unwrap();
+    assert_eq!(
+      storage_handler
+        .get_key_stats(KeyboardLayout::Dvorak)
+        .counters
+        .get(&'o'),
+      Some(&KeyCounter {
+        presses: 6,
+        mistakes: 2,
+      })
+    );
+    assert!(
+      storage_handler
+        .get_key_stats(KeyboardLayout::Qwerty)
+        .counters
+        .is_empty()
+    );
+
+    storage_handler
+      .delete_file()
+      .expect("Error occured while deleting file");
+  }
+
+  #[test]
+  // Aggregates are computed only from the stats matching the filter
+  fn stats_aggregates_filtered() {
+    let mut storage_handler = get_storage_handler();
+
+    storage_handler.insert_into_stats(&get_test_stat());
+    storage_handler.insert_into_stats(&Stat::new(
+      TypingDuration::FifteenSec,
+      String::from("fr"),
+      40,
+      0,
+    ));
+
+    let filter = StatsFilter {
+      language: Some(String::from("fr")),
+      ..Default::default()
+    };
+
+    let aggregates = storage_handler.get_stats_aggregates(&filter);
+    assert_eq!(aggregates.overview.total_stats_count, 1);
+    assert_eq!(aggregates.stats_best[0].get_language(), "fr");
+
+    assert_eq!(
+      storage_handler.get_stats_languages(),
+      vec![String::from("en"), String::from("fr")]
+    );
+
+    storage_handler
+      .delete_file()
+      .expect("Error occured while deleting file");
+  }
+
+  #[test]
+  fn chart_data_marks_personal_bests() {
+    let mut storage_handler = get_storage_handler();
+
+    for chars_counter in [200, 100, 300] {
+      storage_handler.insert_into_stats(&Stat::new(
+        TypingDuration::Minute,
+        String::from("en"),
+        chars_counter,
+        0,
+      ));
+    }
+
+    // Invalid run doesn't set a personal best
+    let afk_flags = StatFlags {
+      is_afk: true,
+      ..Default::default()
+    };
+
+    storage_handler.insert_into_stats(
+      &Stat::new(TypingDuration::Minute, String::from("en"), 500, 0).with_flags(afk_flags),
+    );
+
+    let chart_data = storage_handler.get_data_for_chart(&StatsFilter::default());
+
+    assert!(chart_data.is_time_axis);
+    assert_eq!(chart_data.best_wpm, 60);
+    assert_eq!(chart_data.wpm.len(), 3);
+    assert_eq!(chart_data.personal_bests.len(), 2);
+    assert_eq!(chart_data.rolling_average_short[2].1, 40.0);
+
+    storage_handler
+      .delete_file()
+      .expect("Error occured while deleting file");
+  }
+
+  #[test]
+  fn unlock_achievements_once() {
+    let mut storage_handler = get_storage_handler();
+    let lang_codes = vec![String::from("en")];
+
+    assert!(storage_handler.unlock_achievements(&lang_codes).is_empty());
+
+    // Run with a paste doesn't earn the achievements
+    storage_handler.insert_into_stats(&get_test_stat().with_flags(StatFlags {
+      has_paste: true,
+      ..Default::default()
+    }));
+    assert!(storage_handler.unlock_achievements(&lang_codes).is_empty());
+
+    storage_handler.insert_into_stats(&get_test_stat());
+
+    let unlocked = storage_handler.unlock_achievements(&lang_codes);
+    assert!(unlocked.contains(&Achievement::FirstRun));
+    assert!(unlocked.contains(&Achievement::Polyglot));
+    assert_eq!(
+      storage_handler.get_unlocked_achievements().len(),
+      unlocked.len()
+    );
+
+    storage_handler.insert_into_stats(&get_test_stat());
+    assert!(storage_handler.unlock_achievements(&lang_codes).is_empty());
+
+    // Unlocked achievements are flushed
+    let reloaded_storage_handler = StorageHandler::new(&storage_handler.file_path)
+      .init()
+      .unwrap();
+    assert_eq!(
+      reloaded_storage_handler.get_unlocked_achievements().len(),
+      unlocked.len()
+    );
+
+    storage_handler
+      .delete_file()
+      .expect("Error occured while deleting file");
+  }
+
+  #[test]
+  fn pace_wpm_resolved_from_stats() {
+    let mut storage_handler = get_storage_handler();
+    let typing_duration = TypingDuration::Minute;
+
+    assert_eq!(
+      storage_handler.get_pace_wpm(PaceCaret::Average, &typing_duration),
+      None
+    );
+
+    for chars_counter in [200, 400] {
+      storage_handler.insert_into_stats(&Stat::new(
+        typing_duration.clone(),
+        String::from("en"),
+        chars_counter,
+        0,
+      ));
+    }
+
+    assert_eq!(
+      storage_handler.get_pace_wpm(PaceCaret::Off, &typing_duration),
+      None
+    );
+    assert_eq!(
+      storage_handler.get_pace_wpm(PaceCaret::Fixed(60), &typing_duration),
+      Some(60)
+    );
+    assert_eq!(
+      storage_handler.get_pace_wpm(PaceCaret::Average, &typing_duration),
+      Some(60)
+    );
+    assert_eq!(
+      storage_handler.get_pace_wpm(PaceCaret::Best, &typing_duration),
+      Some(80)
+    );
+    assert_eq!(
+      storage_handler.get_pace_wpm(PaceCaret::Best, &TypingDuration::FifteenSec),
+      None
+    );
 
     storage_handler
       .delete_file()
@@ -0,0 +1,342 @@
+use std::{fs, path::PathBuf};
+
+use ratatui::{
+  Terminal,
+  backend::TestBackend,
+  buffer::Buffer,
+  crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+};
+use tukai::{
+  app::Tukai,
+  config::{TukaiConfig, TukaiConfigBuilder, TypingDuration},
+  engine::AFK_TIMEOUT_SECS,
+  event_handler::{ScriptedEvents, TukaiEvent},
+  file_handler::FileHandler,
+  helper::Generator,
+  storage::storage_handler::StorageHandler,
+};
+use uuid::Uuid;
+
+const SEED: u64 = 42;
+
+/// Storage file of the test (removed when dropped)
+struct TestStorage {
+  file_path: PathBuf,
+}
+
+impl TestStorage {
+  fn new() -> Self {
+    Self {
+      file_path: PathBuf::from(format!("tests/{}.tukai", Uuid::new_v4())),
+    }
+  }
+
+  fn get_config(&self) -> TukaiConfig {
+    TukaiConfigBuilder::new()
+      .file_path(&self.file_path)
+      .seed(SEED)
+      .build()
+  }
+}
+
+impl Drop for TestStorage {
+  fn drop(&mut self) {
+    let full_path = StorageHandler::get_local_dir_path().join(&self.file_path);
+
+    let _ = fs::remove_file(FileHandler::get_backup_path(&full_path));
+    let _ = fs::remove_file(full_path);
+  }
+}
+
+fn key(code: KeyCode) -> TukaiEvent {
+  TukaiEvent::Key(KeyEvent::new(code, KeyModifiers::NONE))
+}
+
+fn ctrl(c: char) -> TukaiEvent {
+  TukaiEvent::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL))
+}
+
+/// Types a character of the text every second (the run isn't idle)
+fn type_per_second<I: Iterator<Item = char>>(text: I, secs: usize) -> Vec<TukaiEvent> {
+  text
+    .take(secs)
+    .flat_map(|c| [key(KeyCode::Char(c)), TukaiEvent::Tick])
+    .collect()
+}
+
+/// Runs the app with the events (followed by the exit) and returns the last rendered frame
+async fn run_script(config: TukaiConfig, events: Vec<TukaiEvent>) -> Buffer {
+  let mut event_source = ScriptedEvents::new(events.into_iter().chain([ctrl('c')]));
+  let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+
+  let mut app = Tukai::try_new(&mut event_source, config).unwrap();
+  app.run(&mut terminal).await.unwrap();
+
+  terminal.backend().buffer().clone()
+}
+
+/// Compares the rendered buffer with the snapshot in `tests/snapshots`
+///
+/// Snapshots are written only with `UPDATE_SNAPSHOTS=1`, a missing snapshot fails.
+fn assert_snapshot(name: &str, buffer: &Buffer) {
+  let rendered = (0..buffer.area.height)
+    .map(|y| {
+      (0..buffer.area.width)
+        .map(|x| buffer[(x, y)].symbol())
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+    })
+    .map(|line| format!("{line}\n"))
+    .collect::<String>();
+
+  let snapshot_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("tests/snapshots")
+    .join(format!("{name}.txt"));
+
+  if std::env::var("UPDATE_SNAPSHOTS").is_ok_and(|value| value == "1") {
+    fs::create_dir_all(snapshot_path.parent().unwrap()).unwrap();
+    fs::write(&snapshot_path, &rendered).unwrap();
+    return;
+  }
+
+  let Ok(snapshot) = fs::read_to_string(&snapshot_path) else {
+    panic!("Missing snapshot `{name}` (UPDATE_SNAPSHOTS=1 writes it)");
+  };
+
+  assert_eq!(
+    snapshot, rendered,
+    "Snapshot `{name}` differs (UPDATE_SNAPSHOTS=1 rewrites it)"
+  );
+}
+
+#[tokio::test]
+async fn typing_screen() {
+  let storage = TestStorage::new();
+
+  let buffer = run_script(storage.get_config(), vec![]).await;
+
+  assert_snapshot("typing_screen", &buffer);
+}
+
+#[tokio::test]
+async fn repeat_screen() {
+  let storage = TestStorage::new();
+
+  let buffer = run_script(storage.get_config(), vec![ctrl('l')]).await;
+
+  assert_snapshot("repeat_screen", &buffer);
+}
+
+#[tokio::test]
+async fn stats_screen_without_runs() {
+  let storage = TestStorage::new();
+
+  let buffer = run_script(storage.get_config(), vec![ctrl('l'), ctrl('l')]).await;
+
+  assert_snapshot("stats_screen_without_runs", &buffer);
+}
+
+#[tokio::test]
+async fn typing_session_until_timeout() {
+  let storage = TestStorage::new();
+
+  // Same seed generates the same text as the app
+  let text = Generator::generate_random_string(&storage.get_config());
+
+  // Run stops after the typing duration
+  let events = type_per_second(text.chars(), TypingDuration::default().as_seconds());
+
+  let buffer = run_script(storage.get_config(), events).await;
+
+  assert_snapshot("typing_session_until_timeout", &buffer);
+}
+
+#[tokio::test]
+async fn pasted_text_flags_run() {
+  let storage = TestStorage::new();
+
+  let text = Generator::generate_random_string(&storage.get_config());
+
+  let mut events = vec![
+    key(KeyCode::Char(text.chars().next().unwrap())),
+    TukaiEvent::Paste(text.chars().skip(1).take(40).collect()),
+    TukaiEvent::Resize,
+  ];
+
+  events.extend(type_per_second(
+    text.chars().skip(1),
+    TypingDuration::default().as_seconds(),
+  ));
+
+  let buffer = run_script(storage.get_config(), events).await;
+
+  assert_snapshot("pasted_text_flags_run", &buffer);
+}
+
+#[tokio::test]
+async fn paused_run_freezes_timer() {
+  let storage = TestStorage::new();
+
+  let text = Generator::generate_random_string(&storage.get_config());
+
+  let mut events = type_per_second(text.chars(), 5);
+
+  events.push(key(KeyCode::Tab));
+  events.extend((0..10).map(|_| TukaiEvent::Tick));
+
+  let buffer = run_script(storage.get_config(), events).await;
+
+  assert_snapshot("paused_run_freezes_timer", &buffer);
+}
+
+#[tokio::test]
+async fn idle_run_is_paused_and_invalid() {
+  let storage = TestStorage::new();
+
+  let text = Generator::generate_random_string(&storage.get_config());
+
+  let mut events = type_per_second(text.chars(), 5);
+
+  // Idle run is paused, the first key only resumes it
+  events.extend((0..AFK_TIMEOUT_SECS + 5).map(|_| TukaiEvent::Tick));
+  events.push(key(KeyCode::Char('x')));
+
+  // Keys after the end of the run are ignored
+  events.extend(type_per_second(
+    text.chars().skip(5),
+    TypingDuration::default().as_seconds(),
+  ));
+
+  let buffer = run_script(storage.get_config(), events).await;
+
+  assert_snapshot("idle_run_is_paused_and_invalid", &buffer);
+}
+
+#[tokio::test]
+async fn masked_run_shows_current_word() {
+  let storage = TestStorage::new();
+
+  let text = Generator::generate_random_string(&storage.get_config());
+
+  // Normal -> blind -> memory -> masked
+  let mut events = vec![ctrl('b'), ctrl('b'), ctrl('b')];
+  events.extend(type_per_second(text.chars(), 8));
+
+  let buffer = run_script(storage.get_config(), events).await;
+
+  assert_snapshot("masked_run_shows_current_word", &buffer);
+}
+
+#[tokio::test]
+async fn zen_run_until_enter() {
+  let storage = TestStorage::new();
+
+  let mut events = vec![ctrl('z')];
+  events.extend(type_per_second(
+    "typing freely without any text".chars(),
+    30,
+  ));
+  events.push(key(KeyCode::Enter));
+
+  let buffer = run_script(storage.get_config(), events).await;
+
+  assert_snapshot("zen_run_until_enter", &buffer);
+}
+
+#[tokio::test]
+async fn completed_drill_is_saved() {
+  let storage = TestStorage::new();
+
+  // Drill of the entered phrase with the fewest repetitions
+  let mut events = vec![ctrl('l'), ctrl('w')];
+  events.extend("go on".chars().map(|c| key(KeyCode::Char(c))));
+  events.extend([key(KeyCode::Down), key(KeyCode::Enter)]);
+  events.extend("go xn".chars().map(|c| key(KeyCode::Char(c))));
+  events.extend([key(KeyCode::Backspace), key(KeyCode::Backspace)]);
+  events.extend("on".chars().map(|c| key(KeyCode::Char(c))));
+  events.extend("go on".repeat(4).chars().map(|c| key(KeyCode::Char(c))));
+
+  run_script(storage.get_config(), events).await;
+
+  let storage_handler = StorageHandler::new(&storage.file_path).init().unwrap();
+  let drill_results = storage_handler.get_drill_results();
+
+  assert_eq!(drill_results.len(), 1);
+  assert_eq!(drill_results[0].phrase, "go on");
+  assert_eq!(drill_results[0].repetitions.len(), 5);
+  assert_eq!(drill_results[0].repetitions[0].accuracy, 85.71);
+  assert!(storage_handler.get_data().0.is_empty());
+}
+
+#[tokio::test]
+async fn locked_lesson_is_not_started() {
+  let storage = TestStorage::new();
+
+  let events = vec![ctrl('n'), key(KeyCode::Down), key(KeyCode::Enter)];
+
+  let buffer = run_script(storage.get_config(), events).await;
+
+  assert_snapshot("locked_lesson_is_not_started", &buffer);
+}
+
+#[tokio::test]
+async fn remapped_keys_type_dvorak() {
+  let storage = TestStorage::new();
+
+  // QWERTY keys of the Dvorak `hello`
+  let mut events = vec![ctrl('y'), ctrl('u'), ctrl('z')];
+  events.extend(type_per_second("jdpps".chars(), 5));
+
+  let buffer = run_script(storage.get_config(), events).await;
+
+  assert_snapshot("remapped_keys_type_dvorak", &buffer);
+}
+
+#[tokio::test]
+async fn remapped_keys_skip_drill_phrase_entry() {
+  let storage = TestStorage::new();
+
+  // Entered phrase is kept as typed, only the drilled phrase is remapped
+  let mut events = vec![ctrl('y'), ctrl('u'), key(KeyCode::Right), ctrl('w')];
+  events.extend("jdpps".chars().map(|c| key(KeyCode::Char(c))));
+
+  let buffer = run_script(storage.get_config(), events).await;
+
+  assert_snapshot("remapped_keys_skip_drill_phrase_entry", &buffer);
+}
+
+#[tokio::test]
+async fn ghost_race_without_recorded_run() {
+  let storage = TestStorage::new();
+
+  let buffer = run_script(storage.get_config(), vec![ctrl('g')]).await;
+
+  assert_snapshot("ghost_race_without_recorded_run", &buffer);
+}
+
+#[tokio::test]
+async fn stats_key_heatmap_after_run() {
+  let storage = TestStorage::new();
+
+  let text = Generator::generate_random_string(&storage.get_config());
+
+  // Every 7th character is mistaken
+  let typed_text = text
+    .chars()
+    .enumerate()
+    .map(|(index, c)| if index % 7 == 3 { 'q' } else { c });
+
+  let mut events = type_per_second(typed_text, TypingDuration::default().as_seconds());
+  events.extend([
+    ctrl('l'),
+    ctrl('l'),
+    key(KeyCode::Tab),
+    key(KeyCode::Tab),
+    key(KeyCode::Tab),
+  ]);
+
+  let buffer = run_script(storage.get_config(), events).await;
+
+  assert_snapshot("stats_key_heatmap_after_run", &buffer);
+}